@@ -1,8 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
-use flatzinc::Stmt;
 use log::error;
-use std::path::PathBuf;
+use std::{fs::File, io::BufReader, path::PathBuf};
 
 /// flatzinc parser
 #[derive(Parser, Debug)]
@@ -23,12 +22,12 @@ fn run() -> Result<()> {
     env_logger::builder().format_timestamp(None).init();
 
     let opt = Opt::parse();
-    let buf = std::fs::read_to_string(opt.file)?;
-    for line in buf.lines() {
-        match <Stmt as std::str::FromStr>::from_str(line) {
-            Ok(result) => println!("{:#?}", result),
+    let reader = BufReader::new(File::open(opt.file)?);
+    for res in flatzinc::StmtReader::new(reader) {
+        match res {
+            Ok(item) => println!("{:#?}", item.stmt),
             Err(e) => {
-                error!("Failed to parse flatzinc statement:\n{}", e);
+                error!("Failed to parse flatzinc model:\n{}", e);
             }
         }
     }
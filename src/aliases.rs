@@ -0,0 +1,368 @@
+//! Resolving variables that are declared as aliases of other variables.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    expressions::{map_ann_exprs, AnnExpr, Annotation, Expr, IntExpr, StrRole},
+    model::Model,
+    solution::{assigned_array, assigned_expr, set_ranges},
+    solve_items::SolveItem,
+    variables::declarations::VarDeclItem,
+};
+
+/// An error produced while resolving aliases.
+#[derive(PartialEq, Clone, Debug)]
+pub enum AliasError {
+    /// The variables are assigned to each other in a cycle, in the order
+    /// in which the assignments are followed
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for AliasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AliasError::Cycle(ids) => write!(f, "cyclic aliases: {}", ids.join(" = ")),
+        }
+    }
+}
+
+impl std::error::Error for AliasError {}
+
+/// The aliases of a [`Model`], see [`Model::resolve_aliases`].
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Aliases {
+    canonical: HashMap<String, Expr>,
+}
+
+impl Aliases {
+    /// The canonical form of an alias: the identifier of the variable it
+    /// stands for, or for an array an array literal of such identifiers.
+    ///
+    /// Returns `None` if `id` is not an alias.
+    pub fn get(&self, id: &str) -> Option<&Expr> {
+        self.canonical.get(id)
+    }
+
+    /// The aliases and their canonical forms, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Expr)> {
+        self.canonical.iter().map(|(id, e)| (id.as_str(), e))
+    }
+
+    pub fn len(&self) -> usize {
+        self.canonical.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.canonical.is_empty()
+    }
+
+    /// The canonical variable of a scalar identifier, or the identifier itself.
+    fn scalar<'a>(&'a self, id: &'a str) -> &'a str {
+        match self.canonical.get(id) {
+            Some(Expr::VarParIdentifier(target)) => target,
+            _ => id,
+        }
+    }
+
+    fn expr(&self, e: &Expr) -> Expr {
+        if let Expr::VarParIdentifier(id) = e {
+            if let Some(canonical) = self.canonical.get(id) {
+                return canonical.clone();
+            }
+        }
+        e.clone().map_str(&mut |s, role| match role {
+            StrRole::Identifier => self.scalar(&s).to_string(),
+            StrRole::Name => s,
+        })
+    }
+
+    fn annotations(&self, annos: &[Annotation]) -> Vec<Annotation> {
+        map_ann_exprs(annos, &|e| AnnExpr::Expr(self.expr(e)), &|id, index| {
+            let element = match (self.canonical.get(id), index) {
+                (Some(Expr::ArrayOfBool(v)), IntExpr::Int(i)) => element(v, *i),
+                (Some(Expr::ArrayOfInt(v)), IntExpr::Int(i)) => element(v, *i),
+                (Some(Expr::ArrayOfFloat(v)), IntExpr::Int(i)) => element(v, *i),
+                (Some(Expr::ArrayOfSet(v)), IntExpr::Int(i)) => element(v, *i),
+                _ => None,
+            };
+            match (element, self.canonical.get(id)) {
+                (Some(element), _) => AnnExpr::Expr(element),
+                (None, Some(Expr::VarParIdentifier(target))) => {
+                    AnnExpr::ArrayAccess(target.clone(), index.clone())
+                }
+                (None, _) => AnnExpr::ArrayAccess(id.to_string(), index.clone()),
+            }
+        })
+    }
+}
+
+/// The declared domain of a variable or of the elements of an array of
+/// variables, as sorted ranges.
+enum Domain {
+    Int(Vec<(i128, i128)>),
+    Float(Vec<(f64, f64)>),
+}
+
+/// The domain of `item`, or `None` if it is not restricted.
+fn domain(item: &VarDeclItem) -> Option<Domain> {
+    match item {
+        VarDeclItem::IntInRange { lb, ub, .. }
+        | VarDeclItem::ArrayOfIntInRange { lb, ub, .. }
+        | VarDeclItem::SubSetOfIntRange { lb, ub, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntRange { lb, ub, .. } => {
+            Some(Domain::Int(set_ranges([(*lb, *ub)])))
+        }
+        VarDeclItem::IntInSet { set, .. }
+        | VarDeclItem::ArrayOfIntInSet { set, .. }
+        | VarDeclItem::SubSetOfIntSet { set, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntSet { set, .. } => {
+            Some(Domain::Int(set_ranges(set.iter().map(|i| (*i, *i)))))
+        }
+        VarDeclItem::BoundedFloat { lb, ub, .. }
+        | VarDeclItem::ArrayOfBoundedFloat { lb, ub, .. } => Some(Domain::Float(vec![(*lb, *ub)])),
+        VarDeclItem::FloatInSet { set, .. } | VarDeclItem::ArrayOfFloatInSet { set, .. } => {
+            Some(Domain::Float(set.iter().map(|f| (*f, *f)).collect()))
+        }
+        _ => None,
+    }
+}
+
+/// Whether the domain of `alias` contains the domain of `target`.
+fn covers(alias: &VarDeclItem, target: &VarDeclItem) -> bool {
+    match (domain(alias), domain(target)) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(Domain::Int(a)), Some(Domain::Int(t))) => t
+            .iter()
+            .all(|(lb, ub)| a.iter().any(|(alb, aub)| alb <= lb && ub <= aub)),
+        (Some(Domain::Float(a)), Some(Domain::Float(t))) => t
+            .iter()
+            .all(|(lb, ub)| a.iter().any(|(alb, aub)| alb <= lb && ub <= aub)),
+        _ => false,
+    }
+}
+
+/// The element of an array literal at the 1-based index `i`.
+fn element<T: Clone + Into<Expr>>(v: &[T], i: i128) -> Option<Expr> {
+    let i = usize::try_from(i).ok()?.checked_sub(1)?;
+    v.get(i).cloned().map(Into::into)
+}
+
+impl Model {
+    /// Replace every reference to an alias in the constraints and the solve
+    /// item with the variable it stands for.
+    ///
+    /// A variable is an alias if it is assigned another variable, like
+    /// `var int: x = y;`, and an array of variables is an alias if it is
+    /// assigned an array literal or another array of variables. Chains of
+    /// aliases are followed to the end, and references to an aliased array
+    /// are replaced by its array literal. A variable whose declared domain
+    /// does not contain the domain of a variable assigned to it, like
+    /// `var 0..5: x = y;` with `var int: y;`, is not an alias, since
+    /// replacing it would drop its domain. Declarations are left unchanged,
+    /// so that output variables can be printed with the returned [`Aliases`].
+    /// On error the model is left unchanged.
+    pub fn resolve_aliases(&mut self) -> Result<Aliases, AliasError> {
+        let assigned: HashMap<&str, Expr> = self
+            .vars
+            .iter()
+            .filter_map(|item| {
+                let e = assigned_expr(item).or_else(|| assigned_array(item))?;
+                let mut narrowed = false;
+                e.for_each_identifier(&mut |target| {
+                    if let Some(target) = self.var(target) {
+                        narrowed |= !covers(item, target);
+                    }
+                });
+                match &e {
+                    _ if narrowed => None,
+                    Expr::VarParIdentifier(target) if self.var(target).is_none() => None,
+                    Expr::VarParIdentifier(_)
+                    | Expr::ArrayOfBool(_)
+                    | Expr::ArrayOfInt(_)
+                    | Expr::ArrayOfFloat(_)
+                    | Expr::ArrayOfSet(_) => Some((item.id(), e)),
+                    _ => None,
+                }
+            })
+            .collect();
+        let mut aliases = Aliases::default();
+        // follow the chains in declaration order, so cycles are reported
+        // starting from the first declared variable
+        for item in &self.vars {
+            let mut id = item.id();
+            let mut chain: Vec<&str> = vec![];
+            let canonical = loop {
+                if let Some(canonical) = aliases.canonical.get(id) {
+                    break Some(canonical.clone());
+                }
+                match assigned.get(id) {
+                    Some(Expr::VarParIdentifier(target)) => {
+                        chain.push(id);
+                        if let Some(start) = chain.iter().position(|other| other == target) {
+                            let cycle = chain[start..].iter().map(|id| id.to_string());
+                            return Err(AliasError::Cycle(cycle.collect()));
+                        }
+                        id = target;
+                    }
+                    Some(array) => {
+                        chain.push(id);
+                        break Some(array.clone());
+                    }
+                    None if chain.is_empty() => break None,
+                    None => break Some(Expr::VarParIdentifier(id.to_string())),
+                }
+            };
+            if let Some(canonical) = canonical {
+                for id in chain {
+                    aliases.canonical.insert(id.to_string(), canonical.clone());
+                }
+            }
+        }
+        // all scalar aliases are known now, so the elements of array literals
+        // can be replaced
+        let arrays: Vec<_> = aliases
+            .canonical
+            .iter()
+            .filter(|(_, e)| !matches!(e, Expr::VarParIdentifier(_)))
+            .map(|(id, e)| (id.clone(), aliases.expr(e)))
+            .collect();
+        aliases.canonical.extend(arrays);
+
+        for c in &mut self.constraints {
+            c.exprs = c.exprs.iter().map(|e| aliases.expr(e)).collect();
+            c.annos = aliases.annotations(&c.annos);
+        }
+        let goal = self.solve.goal.clone().map_str(&mut |s, role| match role {
+            StrRole::Identifier => aliases.scalar(&s).to_string(),
+            StrRole::Name => s,
+        });
+        self.solve = SolveItem {
+            goal,
+            annotations: aliases.annotations(&self.solve.annotations),
+        };
+        Ok(aliases)
+    }
+}
+#[test]
+fn test_resolve_aliases() {
+    use crate::solve_items::{Goal, OptimizationType};
+    let mut model: Model = "var int: x;\n\
+        var int: y = x;\n\
+        var int: z = y;\n\
+        var 0..5: w = 3;\n\
+        array [1..2] of var int: a = [z,w];\n\
+        array [1..2] of var int: b = a;\n\
+        var int: obj :: output_var = z;\n\
+        constraint int_lin_le([1,1],b,0) :: defines_var(z);\n\
+        constraint int_le(y,w) :: foo(b[1], a[3]);\n\
+        solve :: int_search(a,input_order,indomain_min,complete) minimize obj;"
+        .parse()
+        .unwrap();
+    let aliases = model.resolve_aliases().unwrap();
+    let id = |id: &str| IntExpr::VarParIdentifier(id.to_string());
+    let x = Expr::VarParIdentifier("x".to_string());
+    let array = Expr::ArrayOfInt(vec![id("x"), id("w")]);
+    assert_eq!(aliases.len(), 5);
+    assert_eq!(aliases.get("y"), Some(&x));
+    assert_eq!(aliases.get("z"), Some(&x));
+    assert_eq!(aliases.get("obj"), Some(&x));
+    assert_eq!(aliases.get("a"), Some(&array));
+    assert_eq!(aliases.get("b"), Some(&array));
+    assert_eq!(aliases.get("w"), None);
+    assert_eq!(aliases.get("x"), None);
+
+    let c = model.constraints();
+    assert_eq!(c[0].exprs[1], array);
+    assert_eq!(c[0].annos[0].expressions, vec![AnnExpr::Expr(x.clone())]);
+    assert_eq!(
+        c[1].exprs,
+        vec![x.clone(), Expr::VarParIdentifier("w".to_string())]
+    );
+    assert_eq!(
+        c[1].annos[0].expressions,
+        vec![
+            AnnExpr::Expr(x.clone()),
+            AnnExpr::ArrayAccess("a".to_string(), IntExpr::Int(3)),
+        ]
+    );
+    assert_eq!(
+        model.solve().annotations[0].expressions[0],
+        AnnExpr::Expr(array)
+    );
+    assert!(matches!(
+        model.var("y").and_then(assigned_expr),
+        Some(Expr::VarParIdentifier(target)) if target == "x"
+    ));
+    // the parser reads the objective as a bool expression until it is resolved
+    model.resolve().unwrap();
+    assert_eq!(
+        model.solve().goal,
+        Goal::OptimizeInt(OptimizationType::Minimize, id("x"))
+    );
+}
+#[test]
+fn test_resolve_aliases_narrowed() {
+    let mut model: Model = "var int: y;\n\
+        var 1..3: u;\n\
+        var 0..5: x = y;\n\
+        var 0..5: v = u;\n\
+        var {1,3}: t = u;\n\
+        array [1..2] of var 0..5: a = [u,y];\n\
+        array [1..1] of var 0..5: b = [u];\n\
+        constraint int_le(x,v);\n\
+        constraint int_lin_le([1],b,t);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let aliases = model.resolve_aliases().unwrap();
+    let id = |id: &str| Expr::VarParIdentifier(id.to_string());
+    assert_eq!(aliases.len(), 2);
+    assert_eq!(aliases.get("x"), None);
+    assert_eq!(aliases.get("t"), None);
+    assert_eq!(aliases.get("a"), None);
+    assert_eq!(aliases.get("v"), Some(&id("u")));
+    assert_eq!(
+        aliases.get("b"),
+        Some(&Expr::ArrayOfInt(vec![IntExpr::VarParIdentifier(
+            "u".to_string()
+        )]))
+    );
+    let c = model.constraints();
+    assert_eq!(c[0].exprs, vec![id("x"), id("u")]);
+    assert_eq!(c[1].exprs[1], aliases.get("b").unwrap().clone());
+    assert_eq!(c[1].exprs[2], id("t"));
+}
+#[test]
+fn test_resolve_aliases_cycle() {
+    let mut model: Model = "var int: x = y;\n\
+        var int: y = z;\n\
+        var int: z = y;\n\
+        constraint int_le(x,z);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let before = model.clone();
+    assert_eq!(
+        model.resolve_aliases(),
+        Err(AliasError::Cycle(vec!["y".to_string(), "z".to_string()]))
+    );
+    assert_eq!(model, before);
+    let mut model: Model = "var int: x = x;\nsolve satisfy;".parse().unwrap();
+    assert_eq!(
+        model.resolve_aliases(),
+        Err(AliasError::Cycle(vec!["x".to_string()]))
+    );
+}
+#[test]
+fn test_resolve_aliases_jobshop() {
+    let mut model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    let aliases = model.resolve_aliases().unwrap();
+    assert_eq!(aliases.len(), 2);
+    assert_eq!(aliases.get("s"), aliases.get("X_INTRODUCED_59_"));
+    let Some(AnnExpr::Expr(Expr::ArrayOfInt(v))) = model.solve().annotations[0].expressions.first()
+    else {
+        panic!("expected an array of variables");
+    };
+    assert_eq!(v.len(), 12);
+}
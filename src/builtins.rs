@@ -0,0 +1,504 @@
+//! Typed view of the constraints of the FlatZinc standard library (MiniZinc 2.8.3).
+
+use std::fmt;
+
+use crate::{
+    constraints::ConstraintItem,
+    expressions::{
+        ArrayOfBoolExpr, ArrayOfFloatExpr, ArrayOfIntExpr, ArrayOfSetExpr, BoolExpr, Expr,
+        FloatExpr, IntExpr, SetExpr,
+    },
+};
+
+/// An error produced while converting a [`ConstraintItem`] into a [`Builtin`].
+#[derive(PartialEq, Clone, Debug)]
+pub enum BuiltinError {
+    /// The constraint calls a predicate that is not a standard builtin
+    NotABuiltin(String),
+    WrongArity {
+        constraint: String,
+        expected: usize,
+        found: usize,
+    },
+    /// An argument has the wrong type, like a float where an int is expected
+    InvalidArgument { constraint: String, position: usize },
+}
+
+impl fmt::Display for BuiltinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuiltinError::NotABuiltin(id) => write!(f, "`{}` is not a builtin constraint", id),
+            BuiltinError::WrongArity {
+                constraint,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{}` expects {} arguments, found {}",
+                constraint, expected, found
+            ),
+            BuiltinError::InvalidArgument {
+                constraint,
+                position,
+            } => write!(f, "invalid argument {} of `{}`", position + 1, constraint),
+        }
+    }
+}
+
+impl std::error::Error for BuiltinError {}
+
+/// An argument type of a builtin.
+trait Arg: Sized + Into<Expr> {
+    fn from_expr(e: &Expr) -> Option<Self>;
+}
+
+impl Arg for BoolExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        e.clone().try_into().ok()
+    }
+}
+
+impl Arg for IntExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        e.clone().try_into().ok()
+    }
+}
+
+impl Arg for FloatExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        e.clone().try_into().ok()
+    }
+}
+
+impl Arg for SetExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        e.clone().try_into().ok()
+    }
+}
+
+impl Arg for ArrayOfBoolExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        e.clone().try_into().ok()
+    }
+}
+
+impl Arg for ArrayOfIntExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        match e {
+            Expr::ArrayOfBool(v) => Some(ArrayOfIntExpr::Array(
+                identifiers(v)?.map(IntExpr::VarParIdentifier).collect(),
+            )),
+            _ => e.clone().try_into().ok(),
+        }
+    }
+}
+
+impl Arg for ArrayOfFloatExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        match e {
+            Expr::ArrayOfBool(v) => Some(ArrayOfFloatExpr::Array(
+                identifiers(v)?.map(FloatExpr::VarParIdentifier).collect(),
+            )),
+            _ => e.clone().try_into().ok(),
+        }
+    }
+}
+
+impl Arg for ArrayOfSetExpr {
+    fn from_expr(e: &Expr) -> Option<Self> {
+        match e {
+            Expr::ArrayOfBool(v) => Some(ArrayOfSetExpr::Array(
+                identifiers(v)?.map(SetExpr::VarParIdentifier).collect(),
+            )),
+            _ => e.clone().try_into().ok(),
+        }
+    }
+}
+
+/// The identifiers of an array literal that the parser read as an array of
+/// bools, see [`Model::resolve`](crate::Model::resolve).
+fn identifiers(v: &[BoolExpr]) -> Option<impl Iterator<Item = String> + '_> {
+    v.iter()
+        .all(|e| matches!(e, BoolExpr::VarParIdentifier(_)))
+        .then(|| {
+            v.iter().filter_map(|e| match e {
+                BoolExpr::VarParIdentifier(id) => Some(id.clone()),
+                BoolExpr::Bool(_) => None,
+            })
+        })
+}
+
+/// The arguments of a constraint, converted one after the other.
+struct Args<'a> {
+    constraint: &'a str,
+    exprs: &'a [Expr],
+    position: usize,
+}
+
+impl Args<'_> {
+    fn next<T: Arg>(&mut self) -> Result<T, BuiltinError> {
+        let position = self.position;
+        self.position += 1;
+        T::from_expr(&self.exprs[position]).ok_or_else(|| BuiltinError::InvalidArgument {
+            constraint: self.constraint.to_string(),
+            position,
+        })
+    }
+}
+
+macro_rules! builtins {
+    ($($variant:ident($name:literal, $reif:literal) { $($field:ident: $ty:ty),* })*) => {
+        /// A constraint of the FlatZinc standard library with typed arguments.
+        ///
+        /// The fields are named after the parameters in the FlatZinc
+        /// specification. Arrays of identifiers that the parser read as
+        /// arrays of bools are accepted for arrays of any type.
+        #[derive(PartialEq, Clone, Debug)]
+        pub enum Builtin {
+            $($variant { $($field: $ty),* },)*
+            /// A reified constraint `<name>_reif(..., r)`: `r` holds if and only if the constraint holds
+            Reif(Box<Builtin>, BoolExpr),
+            /// A half-reified constraint `<name>_imp(..., r)`: `r` implies the constraint
+            Imp(Box<Builtin>, BoolExpr),
+        }
+
+        impl Builtin {
+            /// The identifier of the constraint, like `int_lin_le_reif`.
+            pub fn name(&self) -> String {
+                match self {
+                    $(Builtin::$variant { .. } => $name.to_string(),)*
+                    Builtin::Reif(b, _) => format!("{}_reif", b.name()),
+                    Builtin::Imp(b, _) => format!("{}_imp", b.name()),
+                }
+            }
+
+            /// The arguments of the constraint.
+            pub fn exprs(&self) -> Vec<Expr> {
+                match self {
+                    $(Builtin::$variant { $($field),* } => vec![$($field.clone().into()),*],)*
+                    Builtin::Reif(b, r) | Builtin::Imp(b, r) => {
+                        let mut exprs = b.exprs();
+                        exprs.push(r.clone().into());
+                        exprs
+                    }
+                }
+            }
+
+            /// Whether the constraint has `_reif` and `_imp` variants.
+            pub fn is_reifiable(&self) -> bool {
+                match self {
+                    $(Builtin::$variant { .. } => $reif,)*
+                    Builtin::Reif(..) | Builtin::Imp(..) => false,
+                }
+            }
+
+            /// Whether `id` names a constraint that is neither reified nor
+            /// half-reified, and if so whether it has `_reif` and `_imp` variants.
+            fn reifiable(id: &str) -> Option<bool> {
+                match id {
+                    $($name => Some($reif),)*
+                    _ => None,
+                }
+            }
+
+            /// Convert a constraint that is neither reified nor half-reified.
+            fn from_parts(id: &str, exprs: &[Expr]) -> Result<Builtin, BuiltinError> {
+                let mut args = Args {
+                    constraint: id,
+                    exprs,
+                    position: 0,
+                };
+                match id {
+                    $($name => {
+                        check_arity(id, [$(stringify!($field)),*].len(), exprs.len())?;
+                        Ok(Builtin::$variant { $($field: args.next()?),* })
+                    })*
+                    _ => Err(BuiltinError::NotABuiltin(id.to_string())),
+                }
+            }
+        }
+    };
+}
+
+fn check_arity(constraint: &str, expected: usize, found: usize) -> Result<(), BuiltinError> {
+    match expected == found {
+        true => Ok(()),
+        false => Err(BuiltinError::WrongArity {
+            constraint: constraint.to_string(),
+            expected,
+            found,
+        }),
+    }
+}
+
+builtins! {
+    // bool
+    ArrayBoolAnd("array_bool_and", false) { bs: ArrayOfBoolExpr, r: BoolExpr }
+    ArrayBoolAndImp("array_bool_and_imp", false) { bs: ArrayOfBoolExpr, r: BoolExpr }
+    ArrayBoolElement("array_bool_element", false) { index: IntExpr, array: ArrayOfBoolExpr, value: BoolExpr }
+    ArrayBoolOr("array_bool_or", false) { bs: ArrayOfBoolExpr, r: BoolExpr }
+    ArrayBoolOrImp("array_bool_or_imp", false) { bs: ArrayOfBoolExpr, r: BoolExpr }
+    ArrayBoolXor("array_bool_xor", false) { bs: ArrayOfBoolExpr }
+    ArrayVarBoolElement("array_var_bool_element", false) { index: IntExpr, array: ArrayOfBoolExpr, value: BoolExpr }
+    Bool2Int("bool2int", false) { a: BoolExpr, b: IntExpr }
+    BoolAnd("bool_and", false) { a: BoolExpr, b: BoolExpr, r: BoolExpr }
+    BoolAndImp("bool_and_imp", false) { a: BoolExpr, b: BoolExpr, r: BoolExpr }
+    BoolClause("bool_clause", true) { pos: ArrayOfBoolExpr, neg: ArrayOfBoolExpr }
+    BoolEq("bool_eq", true) { a: BoolExpr, b: BoolExpr }
+    BoolLe("bool_le", true) { a: BoolExpr, b: BoolExpr }
+    BoolLinEq("bool_lin_eq", false) { coeffs: ArrayOfIntExpr, vars: ArrayOfBoolExpr, c: IntExpr }
+    BoolLinLe("bool_lin_le", false) { coeffs: ArrayOfIntExpr, vars: ArrayOfBoolExpr, c: IntExpr }
+    BoolLt("bool_lt", true) { a: BoolExpr, b: BoolExpr }
+    BoolNot("bool_not", false) { a: BoolExpr, b: BoolExpr }
+    BoolOr("bool_or", false) { a: BoolExpr, b: BoolExpr, r: BoolExpr }
+    BoolOrImp("bool_or_imp", false) { a: BoolExpr, b: BoolExpr, r: BoolExpr }
+    BoolXor("bool_xor", false) { a: BoolExpr, b: BoolExpr, r: BoolExpr }
+    BoolXorImp("bool_xor_imp", false) { a: BoolExpr, b: BoolExpr, r: BoolExpr }
+    // int
+    ArrayIntElement("array_int_element", false) { index: IntExpr, array: ArrayOfIntExpr, value: IntExpr }
+    ArrayIntMaximum("array_int_maximum", false) { m: IntExpr, array: ArrayOfIntExpr }
+    ArrayIntMinimum("array_int_minimum", false) { m: IntExpr, array: ArrayOfIntExpr }
+    ArrayVarIntElement("array_var_int_element", false) { index: IntExpr, array: ArrayOfIntExpr, value: IntExpr }
+    IntAbs("int_abs", false) { a: IntExpr, b: IntExpr }
+    IntDiv("int_div", false) { a: IntExpr, b: IntExpr, c: IntExpr }
+    IntEq("int_eq", true) { a: IntExpr, b: IntExpr }
+    IntLe("int_le", true) { a: IntExpr, b: IntExpr }
+    IntLinEq("int_lin_eq", true) { coeffs: ArrayOfIntExpr, vars: ArrayOfIntExpr, c: IntExpr }
+    IntLinLe("int_lin_le", true) { coeffs: ArrayOfIntExpr, vars: ArrayOfIntExpr, c: IntExpr }
+    IntLinNe("int_lin_ne", true) { coeffs: ArrayOfIntExpr, vars: ArrayOfIntExpr, c: IntExpr }
+    IntLt("int_lt", true) { a: IntExpr, b: IntExpr }
+    IntMax("int_max", false) { a: IntExpr, b: IntExpr, c: IntExpr }
+    IntMin("int_min", false) { a: IntExpr, b: IntExpr, c: IntExpr }
+    IntMod("int_mod", false) { a: IntExpr, b: IntExpr, c: IntExpr }
+    IntNe("int_ne", true) { a: IntExpr, b: IntExpr }
+    IntPlus("int_plus", false) { a: IntExpr, b: IntExpr, c: IntExpr }
+    IntPow("int_pow", false) { a: IntExpr, b: IntExpr, c: IntExpr }
+    IntTimes("int_times", false) { a: IntExpr, b: IntExpr, c: IntExpr }
+    // float
+    ArrayFloatElement("array_float_element", false) { index: IntExpr, array: ArrayOfFloatExpr, value: FloatExpr }
+    ArrayFloatMaximum("array_float_maximum", false) { m: FloatExpr, array: ArrayOfFloatExpr }
+    ArrayFloatMinimum("array_float_minimum", false) { m: FloatExpr, array: ArrayOfFloatExpr }
+    ArrayVarFloatElement("array_var_float_element", false) { index: IntExpr, array: ArrayOfFloatExpr, value: FloatExpr }
+    FloatAbs("float_abs", false) { a: FloatExpr, b: FloatExpr }
+    FloatAcos("float_acos", false) { a: FloatExpr, b: FloatExpr }
+    FloatAcosh("float_acosh", false) { a: FloatExpr, b: FloatExpr }
+    FloatAsin("float_asin", false) { a: FloatExpr, b: FloatExpr }
+    FloatAsinh("float_asinh", false) { a: FloatExpr, b: FloatExpr }
+    FloatAtan("float_atan", false) { a: FloatExpr, b: FloatExpr }
+    FloatAtanh("float_atanh", false) { a: FloatExpr, b: FloatExpr }
+    FloatCos("float_cos", false) { a: FloatExpr, b: FloatExpr }
+    FloatCosh("float_cosh", false) { a: FloatExpr, b: FloatExpr }
+    FloatDiv("float_div", false) { a: FloatExpr, b: FloatExpr, c: FloatExpr }
+    FloatDom("float_dom", false) { x: FloatExpr, bounds: ArrayOfFloatExpr }
+    FloatEq("float_eq", true) { a: FloatExpr, b: FloatExpr }
+    FloatExp("float_exp", false) { a: FloatExpr, b: FloatExpr }
+    FloatIn("float_in", true) { x: FloatExpr, a: FloatExpr, b: FloatExpr }
+    FloatLe("float_le", true) { a: FloatExpr, b: FloatExpr }
+    FloatLinEq("float_lin_eq", true) { coeffs: ArrayOfFloatExpr, vars: ArrayOfFloatExpr, c: FloatExpr }
+    FloatLinLe("float_lin_le", true) { coeffs: ArrayOfFloatExpr, vars: ArrayOfFloatExpr, c: FloatExpr }
+    FloatLinLt("float_lin_lt", true) { coeffs: ArrayOfFloatExpr, vars: ArrayOfFloatExpr, c: FloatExpr }
+    FloatLinNe("float_lin_ne", true) { coeffs: ArrayOfFloatExpr, vars: ArrayOfFloatExpr, c: FloatExpr }
+    FloatLn("float_ln", false) { a: FloatExpr, b: FloatExpr }
+    FloatLog10("float_log10", false) { a: FloatExpr, b: FloatExpr }
+    FloatLog2("float_log2", false) { a: FloatExpr, b: FloatExpr }
+    FloatLt("float_lt", true) { a: FloatExpr, b: FloatExpr }
+    FloatMax("float_max", false) { a: FloatExpr, b: FloatExpr, c: FloatExpr }
+    FloatMin("float_min", false) { a: FloatExpr, b: FloatExpr, c: FloatExpr }
+    FloatNe("float_ne", true) { a: FloatExpr, b: FloatExpr }
+    FloatPlus("float_plus", false) { a: FloatExpr, b: FloatExpr, c: FloatExpr }
+    FloatPow("float_pow", false) { a: FloatExpr, b: FloatExpr, c: FloatExpr }
+    FloatSin("float_sin", false) { a: FloatExpr, b: FloatExpr }
+    FloatSinh("float_sinh", false) { a: FloatExpr, b: FloatExpr }
+    FloatSqrt("float_sqrt", false) { a: FloatExpr, b: FloatExpr }
+    FloatTan("float_tan", false) { a: FloatExpr, b: FloatExpr }
+    FloatTanh("float_tanh", false) { a: FloatExpr, b: FloatExpr }
+    FloatTimes("float_times", false) { a: FloatExpr, b: FloatExpr, c: FloatExpr }
+    Int2Float("int2float", false) { a: IntExpr, b: FloatExpr }
+    // set
+    ArraySetElement("array_set_element", false) { index: IntExpr, array: ArrayOfSetExpr, value: SetExpr }
+    ArrayVarSetElement("array_var_set_element", false) { index: IntExpr, array: ArrayOfSetExpr, value: SetExpr }
+    SetCard("set_card", false) { s: SetExpr, n: IntExpr }
+    SetDiff("set_diff", false) { a: SetExpr, b: SetExpr, c: SetExpr }
+    SetEq("set_eq", true) { a: SetExpr, b: SetExpr }
+    SetIn("set_in", true) { x: IntExpr, s: SetExpr }
+    SetIntersect("set_intersect", false) { a: SetExpr, b: SetExpr, c: SetExpr }
+    SetLe("set_le", true) { a: SetExpr, b: SetExpr }
+    SetLt("set_lt", true) { a: SetExpr, b: SetExpr }
+    SetNe("set_ne", true) { a: SetExpr, b: SetExpr }
+    SetSubset("set_subset", true) { a: SetExpr, b: SetExpr }
+    SetSuperset("set_superset", true) { a: SetExpr, b: SetExpr }
+    SetSymdiff("set_symdiff", false) { a: SetExpr, b: SetExpr, c: SetExpr }
+    SetUnion("set_union", false) { a: SetExpr, b: SetExpr, c: SetExpr }
+}
+
+impl Builtin {
+    /// Whether `id` names a standard builtin, including reified and half-reified variants.
+    pub fn is_builtin(id: &str) -> bool {
+        match Builtin::reifiable(id) {
+            Some(_) => true,
+            None => Builtin::reified_base(id).is_some(),
+        }
+    }
+
+    /// The base constraint of a `_reif` or `_imp` constraint and whether it is reified.
+    pub(crate) fn reified_base(id: &str) -> Option<(&str, bool)> {
+        let (base, reif) = match (id.strip_suffix("_reif"), id.strip_suffix("_imp")) {
+            (Some(base), _) => (base, true),
+            (_, Some(base)) => (base, false),
+            _ => return None,
+        };
+        Builtin::reifiable(base)?.then_some((base, reif))
+    }
+
+    fn parse(id: &str, exprs: &[Expr]) -> Result<Builtin, BuiltinError> {
+        match Builtin::from_parts(id, exprs) {
+            Err(BuiltinError::NotABuiltin(_)) => {}
+            res => return res,
+        }
+        let Some((base, reif)) = Builtin::reified_base(id) else {
+            return Err(BuiltinError::NotABuiltin(id.to_string()));
+        };
+        // the positions and arity refer to the reified constraint
+        let builtin = Builtin::from_parts(base, &exprs[..exprs.len().saturating_sub(1)]).map_err(
+            |e| match e {
+                BuiltinError::NotABuiltin(_) => unreachable!("checked by reified_base"),
+                BuiltinError::WrongArity { expected, .. } => BuiltinError::WrongArity {
+                    constraint: id.to_string(),
+                    expected: expected + 1,
+                    found: exprs.len(),
+                },
+                BuiltinError::InvalidArgument { position, .. } => BuiltinError::InvalidArgument {
+                    constraint: id.to_string(),
+                    position,
+                },
+            },
+        )?;
+        let mut args = Args {
+            constraint: id,
+            exprs,
+            position: exprs.len() - 1,
+        };
+        let r = args.next()?;
+        Ok(match reif {
+            true => Builtin::Reif(Box::new(builtin), r),
+            false => Builtin::Imp(Box::new(builtin), r),
+        })
+    }
+}
+
+impl TryFrom<&ConstraintItem> for Builtin {
+    type Error = BuiltinError;
+
+    fn try_from(c: &ConstraintItem) -> Result<Self, Self::Error> {
+        Builtin::parse(&c.id, &c.exprs)
+    }
+}
+#[test]
+fn test_builtin_jobshop() {
+    use crate::model::Model;
+    let mut model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    let builtins: Vec<Builtin> = model
+        .constraints()
+        .iter()
+        .map(|c| Builtin::try_from(c).unwrap())
+        .collect();
+    assert_eq!(
+        builtins[3],
+        Builtin::IntLinLe {
+            coeffs: ArrayOfIntExpr::VarParIdentifier("X_INTRODUCED_22_".to_string()),
+            vars: ArrayOfIntExpr::Array(vec![
+                IntExpr::VarParIdentifier("X_INTRODUCED_0_".to_string()),
+                IntExpr::VarParIdentifier("X_INTRODUCED_1_".to_string()),
+            ]),
+            c: IntExpr::Int(-3),
+        }
+    );
+    let Builtin::Reif(base, r) = builtins.last().unwrap() else {
+        panic!("expected a reified constraint");
+    };
+    assert!(matches!(**base, Builtin::IntLinLe { .. }));
+    assert_eq!(
+        r,
+        &BoolExpr::VarParIdentifier("X_INTRODUCED_58_".to_string())
+    );
+
+    // converting back yields the resolved arguments
+    model.resolve().unwrap();
+    for (builtin, c) in builtins.iter().zip(model.constraints()) {
+        assert_eq!(builtin.name(), c.id);
+        assert_eq!(builtin.exprs(), c.exprs);
+    }
+}
+#[test]
+fn test_builtin_errors() {
+    use crate::statements::Stmt;
+    let builtin = |input: &str| {
+        let Ok(Stmt::Constraint(c)) = input.parse::<Stmt>() else {
+            panic!("expected a constraint");
+        };
+        Builtin::try_from(&c)
+    };
+    assert_eq!(
+        builtin("constraint set_in_imp(x, 1..3, r);"),
+        Ok(Builtin::Imp(
+            Box::new(Builtin::SetIn {
+                x: IntExpr::VarParIdentifier("x".to_string()),
+                s: SetExpr::Set(crate::expressions::SetLiteralExpr::IntInRange(
+                    IntExpr::Int(1),
+                    IntExpr::Int(3)
+                )),
+            }),
+            BoolExpr::VarParIdentifier("r".to_string())
+        ))
+    );
+    assert_eq!(
+        builtin("constraint all_different_int([x, y]);"),
+        Err(BuiltinError::NotABuiltin("all_different_int".to_string()))
+    );
+    assert_eq!(
+        builtin("constraint int_plus_reif(x, y, z, r);"),
+        Err(BuiltinError::NotABuiltin("int_plus_reif".to_string()))
+    );
+    assert_eq!(
+        builtin("constraint int_le_reif(x, y);"),
+        Err(BuiltinError::WrongArity {
+            constraint: "int_le_reif".to_string(),
+            expected: 3,
+            found: 2
+        })
+    );
+    assert_eq!(
+        builtin("constraint int_le_reif(x, 1.5, r);"),
+        Err(BuiltinError::InvalidArgument {
+            constraint: "int_le_reif".to_string(),
+            position: 1
+        })
+    );
+    assert_eq!(
+        builtin("constraint int_le_reif(x, y, 1);"),
+        Err(BuiltinError::InvalidArgument {
+            constraint: "int_le_reif".to_string(),
+            position: 2
+        })
+    );
+    assert_eq!(
+        builtin("constraint float_lin_eq([x, true], [y, z], 0.0);"),
+        Err(BuiltinError::InvalidArgument {
+            constraint: "float_lin_eq".to_string(),
+            position: 0
+        })
+    );
+    assert!(Builtin::is_builtin("bool_clause"));
+    assert!(Builtin::is_builtin("float_lin_lt_imp"));
+    assert!(Builtin::is_builtin("bool_clause_reif"));
+    assert!(Builtin::is_builtin("float_in_reif"));
+    assert!(!Builtin::is_builtin("bool_and_reif"));
+    assert_eq!(
+        builtin("constraint bool_or_imp(x, y, r);"),
+        Ok(Builtin::BoolOrImp {
+            a: BoolExpr::VarParIdentifier("x".to_string()),
+            b: BoolExpr::VarParIdentifier("y".to_string()),
+            r: BoolExpr::VarParIdentifier("r".to_string()),
+        })
+    );
+}
@@ -0,0 +1,325 @@
+//! Checking constraints that call declared predicates against their declarations.
+
+use std::{collections::BTreeSet, fmt};
+
+use crate::{
+    basic_types::BasicType,
+    builtins::Builtin,
+    expressions::{Expr, IntExpr, SetLiteralExpr},
+    model::{Model, ScalarType},
+    parameters::types::BasicParType,
+    predicates::types::{BasicPredParType, PredIndexSet, PredParType},
+    variables::types::BasicVarType,
+};
+
+/// A problem found by [`Model::check_predicate_calls`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct CallDiagnostic {
+    /// Position of the constraint in [`Model::constraints`]
+    pub constraint: usize,
+    pub kind: CallDiagnosticKind,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum CallDiagnosticKind {
+    /// The predicate is neither a standard builtin nor declared
+    UndeclaredPredicate(String),
+    WrongArity {
+        predicate: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The argument at `position` does not fit the type of its parameter
+    ArgumentMismatch {
+        predicate: String,
+        position: usize,
+        expected: PredParType,
+    },
+}
+
+impl fmt::Display for CallDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "constraint {}: {}", self.constraint, self.kind)
+    }
+}
+
+impl fmt::Display for CallDiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallDiagnosticKind::UndeclaredPredicate(id) => {
+                write!(f, "predicate `{}` is not declared", id)
+            }
+            CallDiagnosticKind::WrongArity {
+                predicate,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{}` expects {} arguments, found {}",
+                predicate, expected, found
+            ),
+            CallDiagnosticKind::ArgumentMismatch {
+                predicate,
+                position,
+                ..
+            } => write!(
+                f,
+                "argument {} of `{}` does not match its parameter",
+                position + 1,
+                predicate
+            ),
+        }
+    }
+}
+
+impl Model {
+    /// Check every constraint that does not call a standard builtin against
+    /// the declaration of its predicate.
+    ///
+    /// Arguments are checked for their type, for passing a variable to a
+    /// parameter that is not `var`, for the length of arrays with a fixed
+    /// index set, and for literals outside of a declared domain.
+    pub fn check_predicate_calls(&self) -> Vec<CallDiagnostic> {
+        let mut diagnostics = vec![];
+        for (constraint, c) in self.constraints.iter().enumerate() {
+            if Builtin::is_builtin(&c.id) {
+                continue;
+            }
+            let mut report = |kind| diagnostics.push(CallDiagnostic { constraint, kind });
+            let Some(predicate) = self.predicate(&c.id) else {
+                report(CallDiagnosticKind::UndeclaredPredicate(c.id.clone()));
+                continue;
+            };
+            if predicate.parameters.len() != c.exprs.len() {
+                report(CallDiagnosticKind::WrongArity {
+                    predicate: c.id.clone(),
+                    expected: predicate.parameters.len(),
+                    found: c.exprs.len(),
+                });
+                continue;
+            }
+            for (position, ((ty, _), e)) in predicate.parameters.iter().zip(&c.exprs).enumerate() {
+                if !self.fits(e, ty) {
+                    report(CallDiagnosticKind::ArgumentMismatch {
+                        predicate: c.id.clone(),
+                        position,
+                        expected: ty.clone(),
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+
+    fn fits(&self, e: &Expr, ty: &PredParType) -> bool {
+        let (ix, element) = match ty {
+            PredParType::Basic(ty) => return self.fits_scalar(e, ty),
+            PredParType::Array { ix, par_type } => (ix, par_type),
+        };
+        let len = match e {
+            Expr::VarParIdentifier(id) => {
+                let Some(decl) = self.type_of(id) else {
+                    return false;
+                };
+                let (scalar, var) = expected_type(element);
+                if !decl.array || decl.scalar != scalar || (decl.var && !var) {
+                    return false;
+                }
+                self.declared_len(id)
+            }
+            Expr::ArrayOfBool(v) => elements_fit(self, v, element),
+            Expr::ArrayOfInt(v) => elements_fit(self, v, element),
+            Expr::ArrayOfFloat(v) => elements_fit(self, v, element),
+            Expr::ArrayOfSet(v) => elements_fit(self, v, element),
+            _ => None,
+        };
+        match (ix, len) {
+            (_, None) => false,
+            (PredIndexSet::IndexSet(n), Some(len)) => *n == len as i128,
+            (PredIndexSet::Int, Some(_)) => true,
+        }
+    }
+
+    fn fits_scalar(&self, e: &Expr, ty: &BasicPredParType) -> bool {
+        let (scalar, var) = expected_type(ty);
+        let found = match e {
+            Expr::Bool(_) => ScalarType::Bool,
+            Expr::Int(_) => ScalarType::Int,
+            Expr::Float(_) => ScalarType::Float,
+            Expr::Set(SetLiteralExpr::IntInRange(..) | SetLiteralExpr::SetInts(_)) => {
+                ScalarType::SetOfInt
+            }
+            Expr::VarParIdentifier(id) => match self.type_of(id) {
+                Some(decl) if !decl.array && (var || !decl.var) => decl.scalar,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        found == scalar && in_domain(e, ty)
+    }
+}
+
+/// The length of an array literal, or `None` if an element does not fit.
+fn elements_fit<T: Clone + Into<Expr>>(
+    model: &Model,
+    v: &[T],
+    ty: &BasicPredParType,
+) -> Option<usize> {
+    v.iter()
+        .all(|e| model.fits_scalar(&e.clone().into(), ty))
+        .then_some(v.len())
+}
+
+/// The type of the values of a parameter and whether it accepts variables.
+fn expected_type(ty: &BasicPredParType) -> (ScalarType, bool) {
+    let basic = |ty: &BasicType| match ty {
+        BasicType::Bool => ScalarType::Bool,
+        BasicType::Int => ScalarType::Int,
+        BasicType::Float => ScalarType::Float,
+    };
+    match ty {
+        BasicPredParType::BasicParType(BasicParType::BasicType(ty)) => (basic(ty), false),
+        BasicPredParType::BasicParType(BasicParType::SetOfInt) => (ScalarType::SetOfInt, false),
+        BasicPredParType::BasicVarType(ty) => match ty {
+            BasicVarType::BasicType(ty) => (basic(ty), true),
+            BasicVarType::IntInRange(..) | BasicVarType::IntInSet(_) => (ScalarType::Int, true),
+            BasicVarType::BoundedFloat(..) | BasicVarType::FloatInSet(_) => {
+                (ScalarType::Float, true)
+            }
+            BasicVarType::SubSetOfIntSet(_) | BasicVarType::SubSetOfIntRange(..) => {
+                (ScalarType::SetOfInt, true)
+            }
+        },
+        BasicPredParType::VarSetOfInt => (ScalarType::SetOfInt, true),
+        BasicPredParType::IntInRange(..) | BasicPredParType::IntInSet(_) => {
+            (ScalarType::Int, false)
+        }
+        BasicPredParType::BoundedFloat(..) | BasicPredParType::FloatInSet(_) => {
+            (ScalarType::Float, false)
+        }
+        BasicPredParType::SubSetOfIntSet(_) | BasicPredParType::SubSetOfIntRange(..) => {
+            (ScalarType::SetOfInt, false)
+        }
+    }
+}
+
+/// Whether a literal lies in the domain of a parameter. Identifiers are
+/// not checked.
+fn in_domain(e: &Expr, ty: &BasicPredParType) -> bool {
+    match e {
+        Expr::Int(i) => range_in_domain(*i, *i, ty),
+        Expr::Float(f) => match ty {
+            BasicPredParType::BoundedFloat(lb, ub)
+            | BasicPredParType::BasicVarType(BasicVarType::BoundedFloat(lb, ub)) => {
+                lb <= f && f <= ub
+            }
+            BasicPredParType::FloatInSet(set)
+            | BasicPredParType::BasicVarType(BasicVarType::FloatInSet(set)) => set.contains(f),
+            _ => true,
+        },
+        Expr::Set(SetLiteralExpr::IntInRange(IntExpr::Int(lb), IntExpr::Int(ub))) => {
+            lb > ub || range_in_domain(*lb, *ub, ty)
+        }
+        Expr::Set(SetLiteralExpr::SetInts(v)) => v.iter().all(|i| match i {
+            IntExpr::Int(i) => range_in_domain(*i, *i, ty),
+            IntExpr::VarParIdentifier(_) => true,
+        }),
+        _ => true,
+    }
+}
+
+/// Whether the integers from `lb` to `ub` all lie in the integer domain of
+/// a parameter, or of the elements of its sets.
+fn range_in_domain(lb: i128, ub: i128, ty: &BasicPredParType) -> bool {
+    match ty {
+        BasicPredParType::IntInRange(min, max)
+        | BasicPredParType::SubSetOfIntRange(min, max)
+        | BasicPredParType::BasicVarType(
+            BasicVarType::IntInRange(min, max) | BasicVarType::SubSetOfIntRange(min, max),
+        ) => *min <= lb && ub <= *max,
+        BasicPredParType::IntInSet(set)
+        | BasicPredParType::SubSetOfIntSet(set)
+        | BasicPredParType::BasicVarType(
+            BasicVarType::IntInSet(set) | BasicVarType::SubSetOfIntSet(set),
+        ) => {
+            // the range is covered if the set has as many distinct elements in it
+            let covered: BTreeSet<_> = set.iter().filter(|i| lb <= **i && **i <= ub).collect();
+            ub.checked_sub(lb).and_then(|n| n.checked_add(1)) == Some(covered.len() as i128)
+        }
+        _ => true,
+    }
+}
+#[test]
+fn test_check_predicate_calls() {
+    let model: Model = "predicate my_pred(array [int] of var int: xs, 1..5: k, var bool: b);\n\
+        predicate fixed(array [1..2] of int: c, set of int: s);\n\
+        array [1..2] of int: c = [1,2];\n\
+        array [1..3] of int: d = [1,2,3];\n\
+        var int: x;\n\
+        var bool: b;\n\
+        constraint my_pred([x,x],3,b);\n\
+        constraint my_pred([x,1],5,true);\n\
+        constraint fixed(c,1..3);\n\
+        constraint int_le(x,x);\n\
+        constraint my_pred([x],6,b);\n\
+        constraint my_pred([x],x,b);\n\
+        constraint fixed(d,{1,2});\n\
+        constraint fixed([x,x],{});\n\
+        constraint my_pred([b],1,x);\n\
+        constraint my_pred([x],1);\n\
+        constraint unknown(x);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let mismatch = |constraint, predicate: &str, position| {
+        let predicate_item = model.predicate(predicate).unwrap();
+        CallDiagnostic {
+            constraint,
+            kind: CallDiagnosticKind::ArgumentMismatch {
+                predicate: predicate.to_string(),
+                position,
+                expected: predicate_item.parameters[position].0.clone(),
+            },
+        }
+    };
+    assert_eq!(
+        model.check_predicate_calls(),
+        vec![
+            mismatch(4, "my_pred", 1),
+            mismatch(5, "my_pred", 1),
+            mismatch(6, "fixed", 0),
+            mismatch(7, "fixed", 0),
+            mismatch(8, "my_pred", 0),
+            mismatch(8, "my_pred", 2),
+            CallDiagnostic {
+                constraint: 9,
+                kind: CallDiagnosticKind::WrongArity {
+                    predicate: "my_pred".to_string(),
+                    expected: 3,
+                    found: 2
+                }
+            },
+            CallDiagnostic {
+                constraint: 10,
+                kind: CallDiagnosticKind::UndeclaredPredicate("unknown".to_string())
+            },
+        ]
+    );
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    assert_eq!(model.check_predicate_calls(), vec![]);
+    let model: Model = "predicate p(set of int: s);\n\
+        predicate q(set of 1..100000000000: s);\n\
+        predicate r(set of {1,2,3,5}: s);\n\
+        constraint p(1..100000000000);\n\
+        constraint q(1..100000000000);\n\
+        constraint q(0..100000000000);\n\
+        constraint r(1..3);\n\
+        constraint r(1..5);\n\
+        constraint r(3..2);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let diagnostics = model.check_predicate_calls();
+    let constraints: Vec<_> = diagnostics.iter().map(|d| d.constraint).collect();
+    assert_eq!(constraints, vec![2, 4]);
+}
@@ -0,0 +1,969 @@
+//! Checking a solution against the semantics of the FlatZinc builtins.
+//!
+//! Floats are compared with a relative tolerance of `1e-6`, since solvers
+//! print them with limited precision.
+
+use std::{cmp::Ordering, fmt};
+
+use crate::{
+    builtins::Builtin,
+    expressions::Expr,
+    model::Model,
+    solution::{assigned_expr, set_card, set_ranges, SolutionError, Value},
+    solve_items::Goal,
+    variables::declarations::VarDeclItem,
+};
+
+/// A part of the model that the solution does not satisfy.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Violation {
+    /// The constraint at position `index` does not hold
+    Constraint { index: usize, id: String },
+    /// The value of a variable is outside of its declared domain
+    Domain(String),
+    /// The value of a variable differs from the expression assigned to it
+    Assignment(String),
+    /// The claimed objective value differs from the value of the objective
+    Objective { claimed: Value, actual: Value },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Constraint { index, id } => {
+                write!(f, "constraint {} (`{}`) is violated", index, id)
+            }
+            Violation::Domain(id) => write!(f, "value of `{}` is outside its domain", id),
+            Violation::Assignment(id) => {
+                write!(f, "value of `{}` differs from its assignment", id)
+            }
+            Violation::Objective { claimed, actual } => write!(
+                f,
+                "objective is {}, but the solution claims {}",
+                actual, claimed
+            ),
+        }
+    }
+}
+
+/// An error that prevents checking a solution.
+#[derive(PartialEq, Clone, Debug)]
+pub enum CheckError {
+    /// No value was given for the variable
+    MissingValue(String),
+    /// The constraint at position `index` has the wrong number or types of arguments
+    InvalidArguments { index: usize, id: String },
+    /// The value of an expression, such as a float set, cannot be represented
+    Unsupported(String),
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::MissingValue(id) => write!(f, "missing value of `{}`", id),
+            CheckError::InvalidArguments { index, id } => {
+                write!(f, "constraint {} (`{}`) has invalid arguments", index, id)
+            }
+            CheckError::Unsupported(e) => write!(f, "cannot evaluate `{}`", e),
+        }
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+/// The result of checking a solution.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct CheckReport {
+    pub violations: Vec<Violation>,
+    /// Positions of the constraints that call predicates which are not builtins
+    pub unchecked: Vec<usize>,
+}
+
+impl CheckReport {
+    /// Whether no violations were found.
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Errors while evaluating a constraint, before the constraint is known.
+enum EvalError {
+    Missing(String),
+    Invalid,
+}
+
+impl From<SolutionError> for EvalError {
+    fn from(e: SolutionError) -> Self {
+        match e {
+            SolutionError::MissingValue(id) => EvalError::Missing(id),
+            _ => EvalError::Invalid,
+        }
+    }
+}
+
+impl Model {
+    /// Check a full assignment against the domains, the constraints and,
+    /// if `objective` is given, the objective of the model.
+    ///
+    /// Variables without a value take the value of the expression they are assigned.
+    pub fn check_solution(
+        &self,
+        value: impl Fn(&str) -> Option<Value>,
+        objective: Option<&Value>,
+    ) -> Result<CheckReport, CheckError> {
+        let mut report = CheckReport::default();
+        let eval_error = |e| match e {
+            SolutionError::MissingValue(id) => CheckError::MissingValue(id),
+            SolutionError::Unsupported(e) => CheckError::Unsupported(e),
+            SolutionError::Output(e) => unreachable!("no output annotations are read: {}", e),
+        };
+        for item in &self.vars {
+            let id = item.id();
+            let values = match self.type_of(id) {
+                Some(ty) if ty.array => self
+                    .array_values(&Expr::VarParIdentifier(id.to_string()), &value)
+                    .map_err(eval_error)?,
+                _ => vec![self.var_value(id, &value).map_err(eval_error)?],
+            };
+            if !values.iter().all(|v| in_domain(item, v)) {
+                report.violations.push(Violation::Domain(id.to_string()));
+            }
+            if let (Some(v), Some(e)) = (value(id), assigned_expr(item)) {
+                let assigned = self.value_of(&e, &value).map_err(eval_error)?;
+                if !same_value(&v, &assigned) {
+                    report
+                        .violations
+                        .push(Violation::Assignment(id.to_string()));
+                }
+            }
+        }
+        for (index, c) in self.constraints.iter().enumerate() {
+            let args = Args {
+                model: self,
+                value: &value,
+                exprs: &c.exprs,
+            };
+            match holds(&c.id, &args) {
+                Ok(Some(true)) => {}
+                Ok(Some(false)) => report.violations.push(Violation::Constraint {
+                    index,
+                    id: c.id.clone(),
+                }),
+                Ok(None) => report.unchecked.push(index),
+                Err(EvalError::Missing(id)) => return Err(CheckError::MissingValue(id)),
+                Err(EvalError::Invalid) => {
+                    return Err(CheckError::InvalidArguments {
+                        index,
+                        id: c.id.clone(),
+                    })
+                }
+            }
+        }
+        let goal = match &self.solve.goal {
+            Goal::Satisfy => None,
+            Goal::OptimizeBool(_, e) => Some(Expr::from(e.clone())),
+            Goal::OptimizeInt(_, e) => Some(Expr::from(e.clone())),
+            Goal::OptimizeFloat(_, e) => Some(Expr::from(e.clone())),
+            Goal::OptimizeSet(_, e) => Some(Expr::from(e.clone())),
+        };
+        if let (Some(claimed), Some(goal)) = (objective, goal) {
+            let actual = self.value_of(&goal, &value).map_err(eval_error)?;
+            if !same_value(claimed, &actual) {
+                report.violations.push(Violation::Objective {
+                    claimed: claimed.clone(),
+                    actual,
+                });
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn in_domain(item: &VarDeclItem, v: &Value) -> bool {
+    match (item, v) {
+        (VarDeclItem::Bool { .. } | VarDeclItem::ArrayOfBool { .. }, Value::Bool(_)) => true,
+        (VarDeclItem::Int { .. } | VarDeclItem::ArrayOfInt { .. }, Value::Int(_)) => true,
+        (
+            VarDeclItem::IntInRange { lb, ub, .. } | VarDeclItem::ArrayOfIntInRange { lb, ub, .. },
+            Value::Int(i),
+        ) => lb <= i && i <= ub,
+        (
+            VarDeclItem::IntInSet { set, .. } | VarDeclItem::ArrayOfIntInSet { set, .. },
+            Value::Int(i),
+        ) => set.contains(i),
+        (VarDeclItem::Float { .. } | VarDeclItem::ArrayOfFloat { .. }, Value::Float(_)) => true,
+        (
+            VarDeclItem::BoundedFloat { lb, ub, .. }
+            | VarDeclItem::ArrayOfBoundedFloat { lb, ub, .. },
+            Value::Float(f),
+        ) => float_le(*lb, *f) && float_le(*f, *ub),
+        (
+            VarDeclItem::FloatInSet { set, .. } | VarDeclItem::ArrayOfFloatInSet { set, .. },
+            Value::Float(f),
+        ) => set.iter().any(|g| float_eq(*f, *g)),
+        (VarDeclItem::SetOfInt { .. } | VarDeclItem::ArrayOfSet { .. }, Value::Set(_)) => true,
+        (
+            VarDeclItem::SubSetOfIntRange { lb, ub, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntRange { lb, ub, .. },
+            Value::Set(s),
+        ) => match (s.first(), s.last()) {
+            (Some((min, _)), Some((_, max))) => lb <= min && max <= ub,
+            _ => true,
+        },
+        (
+            VarDeclItem::SubSetOfIntSet { set, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntSet { set, .. },
+            Value::Set(s),
+        ) => is_subset(s, &set_ranges(set.iter().map(|i| (*i, *i)))),
+        _ => false,
+    }
+}
+
+fn float_eq(a: f64, b: f64) -> bool {
+    a == b || (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(1.0)
+}
+
+fn float_le(a: f64, b: f64) -> bool {
+    a <= b || float_eq(a, b)
+}
+
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Float(a), Value::Float(b)) => float_eq(*a, *b),
+        _ => a == b,
+    }
+}
+
+/// The arguments of a constraint, evaluated on demand.
+struct Args<'a, F> {
+    model: &'a Model,
+    value: &'a F,
+    exprs: &'a [Expr],
+}
+
+impl<'a, F: Fn(&str) -> Option<Value>> Args<'a, F> {
+    fn len(&self) -> usize {
+        self.exprs.len()
+    }
+
+    /// All arguments but the last, which is the control variable of a reified constraint.
+    fn init(&self) -> Args<'a, F> {
+        Args {
+            exprs: &self.exprs[..self.exprs.len().saturating_sub(1)],
+            ..*self
+        }
+    }
+
+    fn arity(&self, n: usize) -> Result<(), EvalError> {
+        match self.exprs.len() == n {
+            true => Ok(()),
+            false => Err(EvalError::Invalid),
+        }
+    }
+
+    fn value(&self, i: usize) -> Result<Value, EvalError> {
+        let e = self.exprs.get(i).ok_or(EvalError::Invalid)?;
+        Ok(self.model.value_of(e, self.value)?)
+    }
+
+    fn values(&self, i: usize) -> Result<Vec<Value>, EvalError> {
+        let e = self.exprs.get(i).ok_or(EvalError::Invalid)?;
+        Ok(self.model.array_values(e, self.value)?)
+    }
+
+    fn bool(&self, i: usize) -> Result<bool, EvalError> {
+        as_bool(self.value(i)?)
+    }
+
+    fn int(&self, i: usize) -> Result<i128, EvalError> {
+        as_int(self.value(i)?)
+    }
+
+    fn float(&self, i: usize) -> Result<f64, EvalError> {
+        as_float(self.value(i)?)
+    }
+
+    fn set(&self, i: usize) -> Result<Vec<(i128, i128)>, EvalError> {
+        as_set(self.value(i)?)
+    }
+
+    fn bools(&self, i: usize) -> Result<Vec<bool>, EvalError> {
+        self.values(i)?.into_iter().map(as_bool).collect()
+    }
+
+    fn ints(&self, i: usize) -> Result<Vec<i128>, EvalError> {
+        self.values(i)?.into_iter().map(as_int).collect()
+    }
+
+    fn floats(&self, i: usize) -> Result<Vec<f64>, EvalError> {
+        self.values(i)?.into_iter().map(as_float).collect()
+    }
+
+    fn sets(&self, i: usize) -> Result<Vec<Vec<(i128, i128)>>, EvalError> {
+        self.values(i)?.into_iter().map(as_set).collect()
+    }
+}
+
+impl<F> Clone for Args<'_, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F> Copy for Args<'_, F> {}
+
+fn as_bool(v: Value) -> Result<bool, EvalError> {
+    match v {
+        Value::Bool(b) => Ok(b),
+        _ => Err(EvalError::Invalid),
+    }
+}
+
+fn as_int(v: Value) -> Result<i128, EvalError> {
+    match v {
+        Value::Int(i) => Ok(i),
+        _ => Err(EvalError::Invalid),
+    }
+}
+
+fn as_float(v: Value) -> Result<f64, EvalError> {
+    match v {
+        Value::Float(f) => Ok(f),
+        Value::Int(i) => Ok(i as f64),
+        _ => Err(EvalError::Invalid),
+    }
+}
+
+fn as_set(v: Value) -> Result<Vec<(i128, i128)>, EvalError> {
+    match v {
+        Value::Set(s) => Ok(s),
+        _ => Err(EvalError::Invalid),
+    }
+}
+
+/// Whether the ranges of a set value contain `i`.
+fn set_contains(s: &[(i128, i128)], i: i128) -> bool {
+    let k = s.partition_point(|(_, ub)| *ub < i);
+    s.get(k).is_some_and(|(lb, _)| *lb <= i)
+}
+
+/// The set of the integers for which `op` holds, given whether they are
+/// contained in `x` and in `y`.
+fn set_combine(
+    x: &[(i128, i128)],
+    y: &[(i128, i128)],
+    op: impl Fn(bool, bool) -> bool,
+) -> Vec<(i128, i128)> {
+    // membership is constant between two consecutive bounds
+    let mut bounds: Vec<i128> = x
+        .iter()
+        .chain(y)
+        .flat_map(|(lb, ub)| [*lb, ub.saturating_add(1)])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+    let ranges = bounds.windows(2).filter_map(|w| {
+        op(set_contains(x, w[0]), set_contains(y, w[0])).then_some((w[0], w[1] - 1))
+    });
+    set_ranges(ranges)
+}
+
+fn is_subset(x: &[(i128, i128)], y: &[(i128, i128)]) -> bool {
+    set_combine(x, y, |x, y| x && !y).is_empty()
+}
+
+/// The lexicographic order of the sorted elements of two set values.
+fn set_cmp(x: &[(i128, i128)], y: &[(i128, i128)]) -> Ordering {
+    for i in 0.. {
+        let ((a, b), (c, d)) = match (x.get(i), y.get(i)) {
+            (None, None) => break,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => (x, y),
+        };
+        if a != c {
+            return a.cmp(c);
+        }
+        // the set with the shorter range either ends, or continues with an
+        // element that is greater than the next one of the other set
+        match b.cmp(d) {
+            Ordering::Equal => continue,
+            Ordering::Less if i + 1 == x.len() => return Ordering::Less,
+            Ordering::Less => return Ordering::Greater,
+            Ordering::Greater if i + 1 == y.len() => return Ordering::Greater,
+            Ordering::Greater => return Ordering::Less,
+        }
+    }
+    Ordering::Equal
+}
+
+/// The element at a 1-based index.
+fn element<T: Clone>(v: &[T], i: i128) -> Option<T> {
+    let i = usize::try_from(i.checked_sub(1)?).ok()?;
+    v.get(i).cloned()
+}
+
+/// The number of operands of a logical connective.
+fn connective_arity(id: &str) -> usize {
+    if id.starts_with("array") {
+        1
+    } else {
+        2
+    }
+}
+
+/// The value of a logical connective applied to its operands.
+fn connective<F: Fn(&str) -> Option<Value>>(id: &str, a: &Args<'_, F>) -> Result<bool, EvalError> {
+    Ok(match id {
+        "array_bool_and" => a.bools(0)?.into_iter().all(|b| b),
+        "array_bool_or" => a.bools(0)?.into_iter().any(|b| b),
+        "bool_and" => a.bool(0)? && a.bool(1)?,
+        "bool_or" => a.bool(0)? || a.bool(1)?,
+        _ => a.bool(0)? != a.bool(1)?,
+    })
+}
+
+/// The coefficients and the variables of a linear constraint must pair up.
+fn same_len<T, U>(coeffs: &[T], vars: &[U]) -> Result<(), EvalError> {
+    match coeffs.len() == vars.len() {
+        true => Ok(()),
+        false => Err(EvalError::Invalid),
+    }
+}
+
+/// Whether the constraint holds, or `None` if it is not a builtin.
+fn holds<F: Fn(&str) -> Option<Value>>(
+    id: &str,
+    a: &Args<'_, F>,
+) -> Result<Option<bool>, EvalError> {
+    if let Some(holds) = builtin(id, a)? {
+        return Ok(Some(holds));
+    }
+    // only the constraints that the standard declares reifiable have `_reif`
+    // and `_imp` variants
+    let Some((base, reif)) = Builtin::reified_base(id) else {
+        return Ok(None);
+    };
+    if a.len() == 0 {
+        return Err(EvalError::Invalid);
+    }
+    let Some(holds) = builtin(base, &a.init())? else {
+        return Ok(None);
+    };
+    let r = a.bool(a.len() - 1)?;
+    Ok(Some(if reif { r == holds } else { !r || holds }))
+}
+
+fn builtin<F: Fn(&str) -> Option<Value>>(
+    id: &str,
+    a: &Args<'_, F>,
+) -> Result<Option<bool>, EvalError> {
+    let holds = match id {
+        // the logical connectives are reified by their last argument
+        "array_bool_and" | "array_bool_or" | "bool_and" | "bool_or" | "bool_xor" => {
+            let n = connective_arity(id);
+            a.arity(n + 1)?;
+            connective(id, &a.init())? == a.bool(n)?
+        }
+        // and half-reified by the last argument of their `_imp` forms
+        "array_bool_and_imp" | "array_bool_or_imp" | "bool_and_imp" | "bool_or_imp"
+        | "bool_xor_imp" => {
+            let n = connective_arity(id);
+            a.arity(n + 1)?;
+            !a.bool(n)? || connective(id.trim_end_matches("_imp"), &a.init())?
+        }
+        "array_bool_xor" => {
+            a.arity(1)?;
+            a.bools(0)?.into_iter().filter(|b| *b).count() % 2 == 1
+        }
+        "array_bool_element" | "array_var_bool_element" => {
+            a.arity(3)?;
+            element(&a.bools(1)?, a.int(0)?) == Some(a.bool(2)?)
+        }
+        "bool2int" => {
+            a.arity(2)?;
+            i128::from(a.bool(0)?) == a.int(1)?
+        }
+        "bool_clause" => {
+            a.arity(2)?;
+            a.bools(0)?.into_iter().any(|b| b) || a.bools(1)?.into_iter().any(|b| !b)
+        }
+        "bool_eq" | "bool_le" | "bool_lt" | "bool_not" => {
+            a.arity(2)?;
+            let (x, y) = (a.bool(0)?, a.bool(1)?);
+            match id {
+                "bool_eq" => x == y,
+                "bool_le" => !x || y,
+                "bool_lt" => !x && y,
+                _ => x != y,
+            }
+        }
+        "bool_lin_eq" | "bool_lin_le" => {
+            a.arity(3)?;
+            let (cs, bs) = (a.ints(0)?, a.bools(1)?);
+            same_len(&cs, &bs)?;
+            let sum: i128 = cs
+                .iter()
+                .zip(&bs)
+                .filter(|(_, b)| **b)
+                .map(|(c, _)| c)
+                .sum();
+            match id {
+                "bool_lin_eq" => sum == a.int(2)?,
+                _ => sum <= a.int(2)?,
+            }
+        }
+        "array_int_element" | "array_var_int_element" => {
+            a.arity(3)?;
+            element(&a.ints(1)?, a.int(0)?) == Some(a.int(2)?)
+        }
+        "array_int_maximum" | "array_int_minimum" => {
+            a.arity(2)?;
+            let xs = a.ints(1)?.into_iter();
+            let m = if id == "array_int_maximum" {
+                xs.max()
+            } else {
+                xs.min()
+            };
+            m == Some(a.int(0)?)
+        }
+        "int_abs" => {
+            a.arity(2)?;
+            a.int(0)?.checked_abs() == Some(a.int(1)?)
+        }
+        "int_eq" | "int_le" | "int_lt" | "int_ne" => {
+            a.arity(2)?;
+            let (x, y) = (a.int(0)?, a.int(1)?);
+            match id {
+                "int_eq" => x == y,
+                "int_le" => x <= y,
+                "int_lt" => x < y,
+                _ => x != y,
+            }
+        }
+        "int_lin_eq" | "int_lin_le" | "int_lin_ne" => {
+            a.arity(3)?;
+            let (cs, xs) = (a.ints(0)?, a.ints(1)?);
+            same_len(&cs, &xs)?;
+            let sum = cs
+                .iter()
+                .zip(&xs)
+                .try_fold(0i128, |sum, (c, x)| sum.checked_add(c.checked_mul(*x)?));
+            let c = a.int(2)?;
+            match (id, sum) {
+                (_, None) => false,
+                ("int_lin_eq", Some(sum)) => sum == c,
+                ("int_lin_le", Some(sum)) => sum <= c,
+                (_, Some(sum)) => sum != c,
+            }
+        }
+        "int_div" | "int_max" | "int_min" | "int_mod" | "int_plus" | "int_pow" | "int_times" => {
+            a.arity(3)?;
+            let (x, y) = (a.int(0)?, a.int(1)?);
+            let z = match id {
+                "int_div" => x.checked_div(y),
+                "int_max" => Some(x.max(y)),
+                "int_min" => Some(x.min(y)),
+                "int_mod" => x.checked_rem(y),
+                "int_plus" => x.checked_add(y),
+                "int_times" => x.checked_mul(y),
+                _ => match (x, u32::try_from(y)) {
+                    (_, Ok(y)) => x.checked_pow(y),
+                    (1, Err(_)) => Some(1),
+                    (-1, Err(_)) => Some(if y % 2 == 0 { 1 } else { -1 }),
+                    _ => None,
+                },
+            };
+            z == Some(a.int(2)?)
+        }
+        "int2float" => {
+            a.arity(2)?;
+            float_eq(a.int(0)? as f64, a.float(1)?)
+        }
+        "array_float_element" | "array_var_float_element" => {
+            a.arity(3)?;
+            let y = a.float(2)?;
+            element(&a.floats(1)?, a.int(0)?).is_some_and(|x| float_eq(x, y))
+        }
+        "array_float_maximum" | "array_float_minimum" => {
+            a.arity(2)?;
+            let xs = a.floats(1)?.into_iter();
+            let m = if id == "array_float_maximum" {
+                xs.reduce(f64::max)
+            } else {
+                xs.reduce(f64::min)
+            };
+            let y = a.float(0)?;
+            m.is_some_and(|m| float_eq(m, y))
+        }
+        "float_abs" | "float_acos" | "float_acosh" | "float_asin" | "float_asinh"
+        | "float_atan" | "float_atanh" | "float_cos" | "float_cosh" | "float_exp" | "float_ln"
+        | "float_log10" | "float_log2" | "float_sin" | "float_sinh" | "float_sqrt"
+        | "float_tan" | "float_tanh" => {
+            a.arity(2)?;
+            let x = a.float(0)?;
+            let y = match id {
+                "float_abs" => x.abs(),
+                "float_acos" => x.acos(),
+                "float_acosh" => x.acosh(),
+                "float_asin" => x.asin(),
+                "float_asinh" => x.asinh(),
+                "float_atan" => x.atan(),
+                "float_atanh" => x.atanh(),
+                "float_cos" => x.cos(),
+                "float_cosh" => x.cosh(),
+                "float_exp" => x.exp(),
+                "float_ln" => x.ln(),
+                "float_log10" => x.log10(),
+                "float_log2" => x.log2(),
+                "float_sin" => x.sin(),
+                "float_sinh" => x.sinh(),
+                "float_sqrt" => x.sqrt(),
+                "float_tan" => x.tan(),
+                _ => x.tanh(),
+            };
+            float_eq(y, a.float(1)?)
+        }
+        "float_dom" => {
+            a.arity(2)?;
+            let (x, bounds) = (a.float(0)?, a.floats(1)?);
+            if bounds.len() % 2 != 0 {
+                return Err(EvalError::Invalid);
+            }
+            bounds
+                .chunks(2)
+                .any(|b| float_le(b[0], x) && float_le(x, b[1]))
+        }
+        "float_in" => {
+            a.arity(3)?;
+            let x = a.float(0)?;
+            float_le(a.float(1)?, x) && float_le(x, a.float(2)?)
+        }
+        "float_eq" | "float_le" | "float_lt" | "float_ne" => {
+            a.arity(2)?;
+            let (x, y) = (a.float(0)?, a.float(1)?);
+            match id {
+                "float_eq" => float_eq(x, y),
+                "float_le" => float_le(x, y),
+                "float_lt" => x < y,
+                _ => !float_eq(x, y),
+            }
+        }
+        "float_lin_eq" | "float_lin_le" | "float_lin_lt" | "float_lin_ne" => {
+            a.arity(3)?;
+            let (cs, xs) = (a.floats(0)?, a.floats(1)?);
+            same_len(&cs, &xs)?;
+            let sum: f64 = cs.iter().zip(&xs).map(|(c, x)| c * x).sum();
+            let c = a.float(2)?;
+            match id {
+                "float_lin_eq" => float_eq(sum, c),
+                "float_lin_le" => float_le(sum, c),
+                "float_lin_lt" => sum < c,
+                _ => !float_eq(sum, c),
+            }
+        }
+        "float_div" | "float_max" | "float_min" | "float_plus" | "float_pow" | "float_times" => {
+            a.arity(3)?;
+            let (x, y) = (a.float(0)?, a.float(1)?);
+            let z = match id {
+                "float_div" => x / y,
+                "float_max" => x.max(y),
+                "float_min" => x.min(y),
+                "float_plus" => x + y,
+                "float_pow" => x.powf(y),
+                _ => x * y,
+            };
+            float_eq(z, a.float(2)?)
+        }
+        "array_set_element" | "array_var_set_element" => {
+            a.arity(3)?;
+            element(&a.sets(1)?, a.int(0)?) == Some(a.set(2)?)
+        }
+        "set_card" => {
+            a.arity(2)?;
+            set_card(&a.set(0)?) == Some(a.int(1)?)
+        }
+        "set_in" => {
+            a.arity(2)?;
+            set_contains(&a.set(1)?, a.int(0)?)
+        }
+        "set_eq" | "set_le" | "set_lt" | "set_ne" | "set_subset" | "set_superset" => {
+            a.arity(2)?;
+            let (x, y) = (a.set(0)?, a.set(1)?);
+            match id {
+                "set_eq" => x == y,
+                "set_le" => set_cmp(&x, &y).is_le(),
+                "set_lt" => set_cmp(&x, &y).is_lt(),
+                "set_ne" => x != y,
+                "set_subset" => is_subset(&x, &y),
+                _ => is_subset(&y, &x),
+            }
+        }
+        "set_diff" | "set_intersect" | "set_symdiff" | "set_union" => {
+            a.arity(3)?;
+            let (x, y) = (a.set(0)?, a.set(1)?);
+            let z = match id {
+                "set_diff" => set_combine(&x, &y, |x, y| x && !y),
+                "set_intersect" => set_combine(&x, &y, |x, y| x && y),
+                "set_symdiff" => set_combine(&x, &y, |x, y| x != y),
+                _ => set_combine(&x, &y, |x, y| x || y),
+            };
+            z == a.set(2)?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(holds))
+}
+#[test]
+fn test_check_solution() {
+    let model: Model = "array [1..2] of int: coeffs = [1,-1];\n\
+        var 0..3: x;\n\
+        var 0..3: y;\n\
+        var bool: b;\n\
+        var int: z = x;\n\
+        var set of 1..3: s;\n\
+        var 0.0..10.0: f;\n\
+        constraint int_lin_le(coeffs,[x,y],-1);\n\
+        constraint int_lin_eq_reif(coeffs,[x,y],0,b);\n\
+        constraint bool_clause([b],[]);\n\
+        constraint array_int_element(2,[x,y,z],y);\n\
+        constraint set_in_reif(x,s,b);\n\
+        constraint float_times(f,2.0,5.0);\n\
+        constraint int_le_imp(y,x,b);\n\
+        constraint my_pred(x);\n\
+        solve maximize y;"
+        .parse()
+        .unwrap();
+    let value = |id: &str| match id {
+        "x" => Some(Value::Int(1)),
+        "y" => Some(Value::Int(2)),
+        "b" => Some(Value::Bool(false)),
+        "s" => Some(Value::set([(2, 3)])),
+        "f" => Some(Value::Float(2.5000000001)),
+        _ => None,
+    };
+    let report = model.check_solution(value, Some(&Value::Int(3))).unwrap();
+    assert_eq!(
+        report.violations,
+        vec![
+            Violation::Constraint {
+                index: 2,
+                id: "bool_clause".to_string()
+            },
+            Violation::Objective {
+                claimed: Value::Int(3),
+                actual: Value::Int(2)
+            },
+        ]
+    );
+    assert_eq!(report.unchecked, vec![7]);
+
+    let value = |id: &str| match id {
+        "x" => Some(Value::Int(4)),
+        "z" => Some(Value::Int(0)),
+        _ => value(id),
+    };
+    let report = model.check_solution(value, None).unwrap();
+    assert_eq!(
+        report.violations[..2],
+        [
+            Violation::Domain("x".to_string()),
+            Violation::Assignment("z".to_string())
+        ]
+    );
+    assert_eq!(
+        model.check_solution(|_| None, None),
+        Err(CheckError::MissingValue("x".to_string()))
+    );
+}
+#[test]
+fn test_check_solution_arguments() {
+    let value = |id: &str| match id {
+        "x" => Some(Value::Int(1)),
+        "b" => Some(Value::Bool(true)),
+        "f" => Some(Value::Float(1.0)),
+        _ => None,
+    };
+    for c in [
+        "int_lin_le([1,2],[x],3)",
+        "int_lin_eq_reif([1],[x,x],3,b)",
+        "bool_lin_eq([1],[b,b],1)",
+        "float_lin_le([1.0,2.0],[f],3.0)",
+    ] {
+        let model: Model = format!(
+            "var int: x;\nvar bool: b;\nvar float: f;\nconstraint {};\nsolve satisfy;",
+            c
+        )
+        .parse()
+        .unwrap();
+        let id = c.split('(').next().unwrap().to_string();
+        assert_eq!(
+            model.check_solution(value, None),
+            Err(CheckError::InvalidArguments { index: 0, id })
+        );
+    }
+    // only builtins that are declared reifiable are checked in reified form
+    let model: Model = "var int: x;\n\
+        var bool: b;\n\
+        var float: f;\n\
+        constraint int_plus_reif(x,x,x,b);\n\
+        constraint int_eq_reif(x,x,b);\n\
+        constraint bool_clause_reif([],[b],false);\n\
+        constraint bool_and_imp(b,false,false);\n\
+        constraint bool_or_imp(false,false,b);\n\
+        constraint float_in(f,0.5,1.0);\n\
+        constraint float_in_reif(f,2.0,3.0,false);\n\
+        constraint float_dom(f,[-1.0,0.0,1.0,2.0]);\n\
+        constraint float_dom(f,[2.0,3.0]);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let report = model.check_solution(value, None).unwrap();
+    assert_eq!(
+        report.violations,
+        [4, 8].map(|index| Violation::Constraint {
+            index,
+            id: model.constraints()[index].id.clone()
+        })
+    );
+    assert_eq!(report.unchecked, vec![0]);
+}
+#[test]
+fn test_check_solution_connective_arities() {
+    let value = |id: &str| (id == "b").then_some(Value::Bool(true));
+    for id in [
+        "array_bool_and",
+        "array_bool_or",
+        "array_bool_xor",
+        "bool_and",
+        "bool_or",
+        "bool_xor",
+        "array_bool_and_imp",
+        "array_bool_or_imp",
+        "bool_and_imp",
+        "bool_or_imp",
+        "bool_xor_imp",
+    ] {
+        for arity in 1..=4 {
+            let first = if id.starts_with("array") {
+                "[b,b]"
+            } else {
+                "b"
+            };
+            let args = std::iter::once(first)
+                .chain(std::iter::repeat_n("b", arity - 1))
+                .collect::<Vec<_>>()
+                .join(",");
+            let model: Model = format!("var bool: b;\nconstraint {id}({args});\nsolve satisfy;")
+                .parse()
+                .unwrap();
+            assert_eq!(
+                Builtin::try_from(&model.constraints()[0]).is_ok(),
+                model.check_solution(value, None).is_ok(),
+                "{id} with {arity} arguments"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_check_solution_sets() {
+    let model: Model = "var set of 1..100000000000: s;\n\
+        var set of 0..100000000000: t;\n\
+        constraint set_card(s,100000000000);\n\
+        constraint set_in(99999999999,s);\n\
+        constraint set_union(s,{0},t);\n\
+        constraint set_diff(t,{0,5},{1,2,3,4});\n\
+        constraint set_subset(s,t);\n\
+        constraint set_lt(t,s);\n\
+        constraint set_le({1,2,4},{1,2,3});\n\
+        constraint set_symdiff(s,1..3,4..100000000000);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let value = |id: &str| match id {
+        "s" => Some(Value::set([(1, 100000000000)])),
+        "t" => Some(Value::set([(0, 100000000000)])),
+        _ => None,
+    };
+    let report = model.check_solution(value, None).unwrap();
+    let violated: Vec<_> = report
+        .violations
+        .iter()
+        .map(|v| match v {
+            Violation::Constraint { index, .. } => *index,
+            _ => panic!("unexpected violation {}", v),
+        })
+        .collect();
+    assert_eq!(violated, vec![3, 6]);
+    assert_eq!(set_cmp(&[(1, 3)], &[(1, 5)]), Ordering::Less);
+    assert_eq!(set_cmp(&[(1, 3), (7, 7)], &[(1, 5)]), Ordering::Greater);
+    assert_eq!(set_cmp(&[(1, 5)], &[(1, 5)]), Ordering::Equal);
+}
+#[test]
+fn test_check_solution_jobshop() {
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    let durations = [3, 3, 4, 4, 4, 3, 2, 2, 3, 3, 3, 4];
+    // the jobs one after the other, so that no two tasks overlap
+    let starts = [0, 3, 6, 10, 14, 18, 21, 23, 25, 28, 31, 34];
+    let pairs = [
+        (4, 0),
+        (6, 1),
+        (5, 2),
+        (7, 3),
+        (10, 0),
+        (9, 1),
+        (11, 2),
+        (8, 3),
+        (10, 4),
+        (11, 5),
+        (9, 6),
+        (8, 7),
+    ];
+    let before = |a: usize, b: usize| starts[a] + durations[a] <= starts[b];
+    let value = |id: &str| {
+        let i: usize = id
+            .strip_prefix("X_INTRODUCED_")?
+            .strip_suffix('_')?
+            .parse()
+            .ok()?;
+        Some(match i {
+            0..=11 => Value::Int(starts[i]),
+            14 => Value::Int(25),
+            15 | 24 => Value::Int(38),
+            19 => Value::Int(14),
+            23 => Value::Int(25),
+            35..=58 => {
+                let (a, b) = pairs[(i - 35) / 2];
+                Value::Bool(if i % 2 == 1 {
+                    before(a, b)
+                } else {
+                    before(b, a)
+                })
+            }
+            _ => return None,
+        })
+    };
+    let report = model.check_solution(value, Some(&Value::Int(38))).unwrap();
+    assert!(report.is_ok());
+    assert!(report.unchecked.is_empty());
+
+    let value = |id: &str| match id {
+        "X_INTRODUCED_1_" => Some(Value::Int(2)),
+        _ => value(id),
+    };
+    let report = model.check_solution(value, Some(&Value::Int(37))).unwrap();
+    assert_eq!(
+        report.violations,
+        vec![
+            Violation::Constraint {
+                index: 3,
+                id: "int_lin_le".to_string()
+            },
+            Violation::Objective {
+                claimed: Value::Int(37),
+                actual: Value::Int(38)
+            },
+        ]
+    );
+}
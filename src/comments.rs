@@ -6,9 +6,11 @@ use winnow::{
     PResult, Parser,
 };
 
-use crate::statements::Stmt;
+use crate::{primitive_literals::ParseStr, statements::Stmt};
 
-pub fn space_or_comment<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<Stmt, E> {
+pub fn space_or_comment<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<Stmt<S>, E> {
     let s = space_or_comment0(input)?;
     Ok(Stmt::Comment(s.into()))
 }
@@ -32,7 +34,7 @@ fn test_comment() {
     let mut input = "% Comments can have anyth!ng in it really <3";
     assert_eq!(
         comment::<ContextError>(&mut input),
-        Ok(" Comments can have anyth!ng in it really <3".into())
+        Ok(" Comments can have anyth!ng in it really <3")
     );
 }
 #[test]
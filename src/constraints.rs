@@ -1,22 +1,41 @@
 use crate::{
     comments::{space_or_comment0, space_or_comment1},
-    expressions::{annotations, expr, Annotation, Expr},
-    primitive_literals::identifier,
+    error::StmtKind,
+    expressions::{annotations, expr, map_annotations, Annotation, Expr, StrRole},
+    primitive_literals::{identifier, ParseStr},
+    spans::{spanned, NodeKind},
 };
 use winnow::{
     combinator::{cut_err, separated},
-    error::{AddContext, FromExternalError, ParserError, StrContext},
+    error::{AddContext, FromExternalError, ParserError, StrContext, StrContextValue},
     PResult, Parser,
 };
 
 #[derive(PartialEq, Clone, Debug)]
-pub struct ConstraintItem {
-    pub id: String,
-    pub exprs: Vec<Expr>,
-    pub annos: Vec<Annotation>,
+pub struct ConstraintItem<S = String> {
+    pub id: S,
+    pub exprs: Vec<Expr<S>>,
+    pub annos: Vec<Annotation<S>>,
 }
 
-pub fn constraint_item<'a, E>(input: &mut &'a str) -> PResult<ConstraintItem, E>
+impl<S> ConstraintItem<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> ConstraintItem<T> {
+        ConstraintItem {
+            id: f(self.id, StrRole::Name),
+            exprs: self.exprs.into_iter().map(|e| e.map_str(f)).collect(),
+            annos: map_annotations(self.annos, f),
+        }
+    }
+}
+
+impl<S: Into<String>> ConstraintItem<S> {
+    /// Convert into a constraint that owns its strings.
+    pub fn into_owned(self) -> ConstraintItem {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+pub fn constraint_item<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<ConstraintItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -25,26 +44,38 @@ where
 {
     space_or_comment0(input)?;
     "constraint".parse_next(input)?;
-    cut_err(constraint_tail.context(StrContext::Label("Error while parsing constraint")))
+    cut_err(constraint_tail.context(StrContext::Label(StmtKind::Constraint.label())))
         .parse_next(input)
 }
-pub fn constraint_tail<'a, E>(input: &mut &'a str) -> PResult<ConstraintItem, E>
+pub fn constraint_tail<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<ConstraintItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
     space_or_comment1(input)?;
-    let id = identifier(input)?;
-    '('.parse_next(input)?;
+    let id = spanned(NodeKind::Identifier, identifier)
+        .context(StrContext::Expected(StrContextValue::Description(
+            "identifier",
+        )))
+        .parse_next(input)?;
+    '('.context(StrContext::Expected(StrContextValue::CharLiteral('(')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
-    let exprs = separated(1.., expr, ",").parse_next(input)?;
+    let exprs = separated(1.., expr, ",")
+        .context(StrContext::Expected(StrContextValue::Description(
+            "expression",
+        )))
+        .parse_next(input)?;
     space_or_comment0(input)?;
-    ')'.parse_next(input)?;
+    ')'.context(StrContext::Expected(StrContextValue::CharLiteral(')')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
     let annos = annotations(input)?;
     space_or_comment0(input)?;
-    ';'.parse_next(input)?;
+    ';'.context(StrContext::Expected(StrContextValue::CharLiteral(';')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
     Ok(ConstraintItem { id, exprs, annos })
 }
@@ -54,7 +85,7 @@ fn test_constraint_item_1() {
     use winnow::error::ContextError;
     let mut input = "constraint set_in_reif(X_26,1..2,X_52):: defines_var(X_52);";
     assert_eq!(
-        constraint_item::<ContextError>(&mut input),
+        constraint_item::<String, ContextError>(&mut input),
         Ok(ConstraintItem {
             id: "set_in_reif".to_string(),
             exprs: vec![
@@ -70,7 +101,7 @@ fn test_constraint_item_1() {
     );
     let mut input = "constraint array_var_int_element(INT01, w, 2);";
     assert_eq!(
-        constraint_item::<ContextError>(&mut input),
+        constraint_item::<String, ContextError>(&mut input),
         Ok(ConstraintItem {
             id: "array_var_int_element".to_string(),
             exprs: vec![
@@ -83,7 +114,7 @@ fn test_constraint_item_1() {
     );
     let mut input = "constraint array_var_int_element(INT01, w, 2.0);";
     assert_eq!(
-        constraint_item::<ContextError>(&mut input),
+        constraint_item::<String, ContextError>(&mut input),
         Ok(ConstraintItem {
             id: "array_var_int_element".to_string(),
             exprs: vec![
@@ -101,7 +132,7 @@ fn test_constraint_item_2() {
     use winnow::error::ContextError;
     let mut input = "constraint int_lin_eq([-1, 1], [INT01, p], -3);";
     assert_eq!(
-        constraint_item::<ContextError>(&mut input),
+        constraint_item::<String, ContextError>(&mut input),
         Ok(ConstraintItem {
             id: "int_lin_eq".to_string(),
             exprs: vec![
@@ -122,7 +153,7 @@ fn test_constraint_item_3() {
     use winnow::error::ContextError;
     let mut input = "constraint float_lin_eq(X_139,[X_27,X_28,X_29],1.0);";
     assert_eq!(
-        constraint_item::<ContextError>(&mut input),
+        constraint_item::<String, ContextError>(&mut input),
         Ok(ConstraintItem {
             id: "float_lin_eq".to_string(),
             exprs: vec![
@@ -144,7 +175,7 @@ fn test_constraint_item_4() {
     use winnow::error::ContextError;
     let mut input = "constraint array_bool_or([X_43,X_44],true);";
     assert_eq!(
-        constraint_item::<ContextError>(&mut input),
+        constraint_item::<String, ContextError>(&mut input),
         Ok(ConstraintItem {
             id: "array_bool_or".to_string(),
             exprs: vec![
@@ -164,7 +195,7 @@ fn test_constraint_item_5() {
     use winnow::error::ContextError;
     let mut input = "constraint bool_clause([],[X_81,X_77]);";
     assert_eq!(
-        constraint_item::<ContextError>(&mut input),
+        constraint_item::<String, ContextError>(&mut input),
         Ok(ConstraintItem {
             id: "bool_clause".to_string(),
             exprs: vec![
@@ -182,7 +213,7 @@ fn test_constraint_item_5() {
 fn test_constraint_item_6() {
     use winnow::error::ContextError;
     let mut input = "constraintX int_lin_le_reif(X_INTRODUCED_22_,,-2,X_INTRODUCED_58_):: defines_var(X_INTRODUCED_58_);";
-    let res = constraint_item::<ContextError>(&mut input);
+    let res = constraint_item::<String, ContextError>(&mut input);
     assert!(res.is_err());
     assert_eq!("Parsing Failure: ContextError { context: [Label(\"Error while parsing constraint\")], cause: None }", res.unwrap_err().to_string());
 }
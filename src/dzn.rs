@@ -0,0 +1,552 @@
+//! Reading assignments in the data format of MiniZinc (DZN), as used by data
+//! files and by the solutions that solvers print.
+
+use std::{collections::HashMap, fmt};
+
+use winnow::{
+    combinator::{alt, separated, terminated},
+    error::{ContextError, FromExternalError, ParserError},
+    token::one_of,
+    PResult, Parser,
+};
+
+use crate::{
+    comments::space_or_comment0,
+    error::line_column,
+    expressions::Expr,
+    model::{DeclType, Model, ScalarType},
+    output::array_len,
+    parameters::declarations::ParDeclItem,
+    primitive_literals::{bool_literal, dims_len, float_literal, int_literal, var_par_identifier},
+    solution::{set_ranges, Status, Value},
+    solver_output::{SolverEvent, SolverOutputError, SolverOutputReader},
+};
+
+/// The value assigned to an identifier.
+#[derive(PartialEq, Clone, Debug)]
+pub enum DznValue {
+    Scalar(Value),
+    /// The elements of an array in row-major order and the bounds of each dimension
+    Array {
+        dims: Vec<(i128, i128)>,
+        values: Vec<Value>,
+    },
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Assignment {
+    pub id: String,
+    pub value: DznValue,
+}
+
+/// The assignments of a data file or of a single solution, in input order.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Solution {
+    pub assignments: Vec<Assignment>,
+}
+
+impl Solution {
+    /// Look up the value assigned to an identifier.
+    pub fn get(&self, id: &str) -> Option<&DznValue> {
+        self.assignments
+            .iter()
+            .find(|a| a.id == id)
+            .map(|a| &a.value)
+    }
+}
+
+/// The solutions printed by a solver and the status printed after them, if any.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct SolverOutput {
+    pub solutions: Vec<Solution>,
+    pub status: Option<Status>,
+}
+
+/// An error produced while reading DZN assignments.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum DznError {
+    /// The input at this position is not an assignment
+    Syntax {
+        line: usize,
+        column: usize,
+    },
+    Undeclared(String),
+    /// The value does not match the declared type of the identifier
+    TypeMismatch {
+        id: String,
+        expected: DeclType,
+    },
+    /// The index set of the array declaration, like `1..-1`, is not a valid length
+    InvalidArrayLength(String),
+    /// The index sets of an `arrayNd` value have more elements than fit into a `usize`
+    InvalidIndexSets(String),
+    /// The number of elements of an array does not match its index sets or declaration
+    SizeMismatch {
+        id: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for DznError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DznError::Syntax { line, column } => {
+                write!(f, "{}:{}: invalid assignment", line, column)
+            }
+            DznError::Undeclared(id) => write!(f, "assignment to undeclared identifier `{}`", id),
+            DznError::TypeMismatch { id, expected } => {
+                write!(f, "value of `{}` is not of type {}", id, expected)
+            }
+            DznError::InvalidArrayLength(id) => {
+                write!(f, "invalid index set in the declaration of `{}`", id)
+            }
+            DznError::InvalidIndexSets(id) => write!(f, "invalid index sets in value of `{}`", id),
+            DznError::SizeMismatch {
+                id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "array `{}` has {} elements, expected {}",
+                id, found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DznError {}
+
+impl Model {
+    /// Parse DZN assignments and type them against the declarations of the model.
+    pub fn parse_dzn(&self, input: &str) -> Result<Solution, DznError> {
+        let mut rest = input;
+        let mut solution = Solution::default();
+        loop {
+            let _ = space_or_comment0::<ContextError>(&mut rest);
+            if rest.is_empty() {
+                return Ok(solution);
+            }
+            let Ok((id, literal)) = assignment::<ContextError>(&mut rest) else {
+                let (line, column) = line_column(input, input.len() - rest.len());
+                return Err(DznError::Syntax { line, column });
+            };
+            let value = self.dzn_value(&id, literal)?;
+            solution.assignments.push(Assignment { id, value });
+        }
+    }
+
+    /// Parse the output of a solver: solutions ended by
+    /// [`SOLUTION_SEPARATOR`](crate::SOLUTION_SEPARATOR) and an optional
+    /// status line such as `==========`.
+    ///
+    /// Assignments after the last separator are ignored, since the solver may
+    /// have been stopped while printing them. See [`SolverOutputReader`] for
+    /// reading the output incrementally.
+    pub fn parse_solver_output(&self, output: &str) -> Result<SolverOutput, DznError> {
+        let mut result = SolverOutput::default();
+        for event in SolverOutputReader::new(self, output.as_bytes()) {
+            match event {
+                Ok(SolverEvent::Solution(solution)) => result.solutions.push(solution),
+                Ok(SolverEvent::Status(status)) => result.status = Some(status),
+                Ok(_) => {}
+                Err(SolverOutputError::Dzn(e)) => return Err(e),
+                Err(SolverOutputError::Io(e)) => unreachable!("reading from a string: {}", e),
+            }
+        }
+        Ok(result)
+    }
+
+    /// The values of a solution by identifier, including the elements of
+    /// arrays that are declared as arrays of variables.
+    pub fn solution_values(&self, solution: &Solution) -> HashMap<String, Value> {
+        let mut values = HashMap::new();
+        for assignment in &solution.assignments {
+            match &assignment.value {
+                DznValue::Scalar(v) => {
+                    values.insert(assignment.id.clone(), v.clone());
+                }
+                DznValue::Array { values: v, .. } => {
+                    let elements = self.array_elements(&assignment.id).unwrap_or_default();
+                    for (e, v) in elements.iter().zip(v) {
+                        if let Expr::VarParIdentifier(id) = e {
+                            values.entry(id.clone()).or_insert_with(|| v.clone());
+                        }
+                    }
+                }
+            }
+        }
+        values
+    }
+
+    fn dzn_value(&self, id: &str, literal: Literal) -> Result<DznValue, DznError> {
+        let ty = self
+            .type_of(id)
+            .ok_or_else(|| DznError::Undeclared(id.to_string()))?;
+        let mismatch = || DznError::TypeMismatch {
+            id: id.to_string(),
+            expected: ty,
+        };
+        match (ty.array, literal) {
+            (false, literal) => Ok(DznValue::Scalar(
+                typed(ty.scalar, literal).ok_or_else(mismatch)?,
+            )),
+            (true, Literal::Array(dims, elements)) => {
+                let values: Vec<Value> = elements
+                    .into_iter()
+                    .map(|l| typed(ty.scalar, l))
+                    .collect::<Option<_>>()
+                    .ok_or_else(mismatch)?;
+                let dims = dims.unwrap_or_else(|| vec![(1, values.len() as i128)]);
+                let size =
+                    dims_len(&dims).ok_or_else(|| DznError::InvalidIndexSets(id.to_string()))?;
+                let declared = self
+                    .declared_len(id)
+                    .ok_or_else(|| DznError::InvalidArrayLength(id.to_string()))?;
+                for expected in [size, declared] {
+                    if expected != values.len() {
+                        return Err(DznError::SizeMismatch {
+                            id: id.to_string(),
+                            expected,
+                            found: values.len(),
+                        });
+                    }
+                }
+                Ok(DznValue::Array { dims, values })
+            }
+            (true, _) => Err(mismatch()),
+        }
+    }
+
+    /// The length of a declared array, if it fits into a `usize`.
+    pub(crate) fn declared_len(&self, id: &str) -> Option<usize> {
+        match self.par(id) {
+            Some(
+                ParDeclItem::ArrayOfBool { ix, .. }
+                | ParDeclItem::ArrayOfInt { ix, .. }
+                | ParDeclItem::ArrayOfFloat { ix, .. }
+                | ParDeclItem::ArrayOfSet { ix, .. },
+            ) => ix.length(),
+            _ => self.var(id).map_or(Some(1), array_len),
+        }
+    }
+}
+
+/// A DZN value before it is typed against a declaration.
+#[derive(PartialEq, Clone, Debug)]
+enum Literal {
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    /// A set literal or range as sorted, disjoint ranges, see [`Value::Set`]
+    Set(Vec<(i128, i128)>),
+    /// An array literal with the index sets given by `arrayNd`, if any
+    Array(Option<Vec<(i128, i128)>>, Vec<Literal>),
+}
+
+fn typed(scalar: ScalarType, literal: Literal) -> Option<Value> {
+    match (scalar, literal) {
+        (ScalarType::Bool, Literal::Bool(b)) => Some(Value::Bool(b)),
+        (ScalarType::Int, Literal::Int(i)) => Some(Value::Int(i)),
+        (ScalarType::Float, Literal::Float(f)) => Some(Value::Float(f)),
+        (ScalarType::Float, Literal::Int(i)) => Some(Value::Float(i as f64)),
+        (ScalarType::SetOfInt, Literal::Set(s)) => Some(Value::Set(s)),
+        _ => None,
+    }
+}
+
+// <identifier> "=" <value> ";"
+fn assignment<'a, E>(input: &mut &'a str) -> PResult<(String, Literal), E>
+where
+    E: ParserError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, std::num::ParseFloatError>,
+{
+    let id = var_par_identifier(input)?;
+    space_or_comment0(input)?;
+    '='.parse_next(input)?;
+    let value = literal(input)?;
+    space_or_comment0(input)?;
+    ';'.parse_next(input)?;
+    Ok((id, value))
+}
+
+fn literal<'a, E>(input: &mut &'a str) -> PResult<Literal, E>
+where
+    E: ParserError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, std::num::ParseFloatError>,
+{
+    space_or_comment0(input)?;
+    alt((
+        array_nd,
+        array.map(|v| Literal::Array(None, v)),
+        int_set,
+        range.map(|range| Literal::Set(set_ranges([range]))),
+        float_literal.map(Literal::Float),
+        int_literal.map(Literal::Int),
+        bool_literal.map(Literal::Bool),
+    ))
+    .parse_next(input)
+}
+
+// "array" <n> "d" "(" <range> "," ... <array> ")"
+fn array_nd<'a, E>(input: &mut &'a str) -> PResult<Literal, E>
+where
+    E: ParserError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, std::num::ParseFloatError>,
+{
+    "array".parse_next(input)?;
+    let n = one_of('1'..='6').parse_next(input)?;
+    "d".parse_next(input)?;
+    space_or_comment0(input)?;
+    '('.parse_next(input)?;
+    let mut dims = vec![];
+    for _ in '1'..=n {
+        space_or_comment0(input)?;
+        dims.push(range(input)?);
+        space_or_comment0(input)?;
+        ','.parse_next(input)?;
+    }
+    space_or_comment0(input)?;
+    let v = array(input)?;
+    space_or_comment0(input)?;
+    ')'.parse_next(input)?;
+    Ok(Literal::Array(Some(dims), v))
+}
+
+// "[" <value> "," ... "]"
+fn array<'a, E>(input: &mut &'a str) -> PResult<Vec<Literal>, E>
+where
+    E: ParserError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, std::num::ParseFloatError>,
+{
+    '['.parse_next(input)?;
+    space_or_comment0(input)?;
+    let v = separated(0.., terminated(literal, space_or_comment0), ',').parse_next(input)?;
+    space_or_comment0(input)?;
+    ']'.parse_next(input)?;
+    Ok(v)
+}
+
+// "{" <int-literal> "," ... "}"
+fn int_set<'a, E>(input: &mut &'a str) -> PResult<Literal, E>
+where
+    E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
+{
+    '{'.parse_next(input)?;
+    space_or_comment0(input)?;
+    let v: Vec<i128> =
+        separated(0.., terminated(int_literal, space_or_comment0), ',').parse_next(input)?;
+    space_or_comment0(input)?;
+    '}'.parse_next(input)?;
+    Ok(Literal::Set(set_ranges(v.into_iter().map(|i| (i, i)))))
+}
+
+// <int-literal> ".." <int-literal>
+fn range<'a, E>(input: &mut &'a str) -> PResult<(i128, i128), E>
+where
+    E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
+{
+    let lb = int_literal(input)?;
+    space_or_comment0(input)?;
+    "..".parse_next(input)?;
+    let ub = int_literal(input)?;
+    Ok((lb, ub))
+}
+#[test]
+fn test_literal() {
+    let mut input = "array2d(1..2, 0..1, [{3, 1}, 2..4, {}, 1..0])";
+    assert_eq!(
+        literal::<ContextError>(&mut input),
+        Ok(Literal::Array(
+            Some(vec![(1, 2), (0, 1)]),
+            vec![
+                Literal::Set(vec![(1, 1), (3, 3)]),
+                Literal::Set(vec![(2, 4)]),
+                Literal::Set(vec![]),
+                Literal::Set(vec![]),
+            ]
+        ))
+    );
+    let mut input = "[-1.5, 2, true]";
+    assert_eq!(
+        literal::<ContextError>(&mut input),
+        Ok(Literal::Array(
+            None,
+            vec![Literal::Float(-1.5), Literal::Int(2), Literal::Bool(true)]
+        ))
+    );
+}
+#[test]
+fn test_parse_dzn() {
+    let model: Model = "array [1..2] of int: c = [1,2];\n\
+        var 0..3: x;\n\
+        var float: f;\n\
+        var set of 1..5: s;\n\
+        array [1..4] of var bool: b;\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let solution = model
+        .parse_dzn(
+            "x = -1; % a comment\n\
+            f = 3;\n\
+            s = 2..4;\n\
+            b = array2d(1..2, 1..2, [true, false, false, true]);\n\
+            c = [3, 4];",
+        )
+        .unwrap();
+    assert_eq!(solution.get("x"), Some(&DznValue::Scalar(Value::Int(-1))));
+    assert_eq!(
+        solution.get("f"),
+        Some(&DznValue::Scalar(Value::Float(3.0)))
+    );
+    assert_eq!(
+        solution.get("s"),
+        Some(&DznValue::Scalar(Value::Set(vec![(2, 4)])))
+    );
+    assert_eq!(
+        solution.get("b"),
+        Some(&DznValue::Array {
+            dims: vec![(1, 2), (1, 2)],
+            values: [true, false, false, true].map(Value::Bool).to_vec(),
+        })
+    );
+    assert_eq!(
+        solution.get("c"),
+        Some(&DznValue::Array {
+            dims: vec![(1, 2)],
+            values: vec![Value::Int(3), Value::Int(4)],
+        })
+    );
+    assert_eq!(model.parse_dzn(""), Ok(Solution::default()));
+    let model: Model = "var set of 1..100000000000: s;\nsolve satisfy;"
+        .parse()
+        .unwrap();
+    let solution = model.parse_dzn("s = 1..100000000000;").unwrap();
+    assert_eq!(
+        solution.get("s"),
+        Some(&DznValue::Scalar(Value::Set(vec![(1, 100000000000)])))
+    );
+}
+#[test]
+fn test_parse_dzn_errors() {
+    let model: Model = "var 0..3: x;\n\
+        array [1..4] of var int: a;\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        model.parse_dzn("x = 1;\n  x = ;"),
+        Err(DznError::Syntax { line: 2, column: 7 })
+    );
+    assert_eq!(
+        model.parse_dzn("y = 1;"),
+        Err(DznError::Undeclared("y".to_string()))
+    );
+    assert_eq!(
+        model.parse_dzn("x = true;"),
+        Err(DznError::TypeMismatch {
+            id: "x".to_string(),
+            expected: model.type_of("x").unwrap(),
+        })
+    );
+    assert_eq!(
+        model.parse_dzn("a = 1;"),
+        Err(DznError::TypeMismatch {
+            id: "a".to_string(),
+            expected: model.type_of("a").unwrap(),
+        })
+    );
+    assert_eq!(
+        model.parse_dzn("a = array2d(1..2, 1..3, [1, 2, 3, 4]);"),
+        Err(DznError::SizeMismatch {
+            id: "a".to_string(),
+            expected: 6,
+            found: 4
+        })
+    );
+    assert_eq!(
+        model.parse_dzn("a = [1, 2, 3];"),
+        Err(DznError::SizeMismatch {
+            id: "a".to_string(),
+            expected: 4,
+            found: 3
+        })
+    );
+    assert_eq!(
+        model.parse_dzn("a = array1d(0..18446744073709551619, [1, 2, 3, 4]);"),
+        Err(DznError::InvalidIndexSets("a".to_string()))
+    );
+    assert_eq!(
+        model.parse_solver_output(
+            "a = array1d(-170141183460469231731687303715884105727..\
+            170141183460469231731687303715884105727, [1, 2, 3, 4]);\n----------\n"
+        ),
+        Err(DznError::InvalidIndexSets("a".to_string()))
+    );
+    let model: Model = "array [1..-1] of var int: a;\nsolve satisfy;"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        model.parse_dzn("a = [];"),
+        Err(DznError::InvalidArrayLength("a".to_string()))
+    );
+}
+#[test]
+fn test_parse_solver_output() {
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    let output = "s = array2d(1..3, 1..4, [0, 3, 6, 10, 14, 18, 21, 23, 25, 28, 31, 34]);\n\
+        ----------\n\
+        % a better solution\n\
+        s = array2d(1..3, 1..4, [0, 3, 6, 10, 0, 4, 7, 14, 3, 6, 10, 13]);\n\
+        ----------\n\
+        ==========\n";
+    let output = model.parse_solver_output(output).unwrap();
+    assert_eq!(output.solutions.len(), 2);
+    assert_eq!(output.status, Some(Status::Complete));
+    let values = model.solution_values(&output.solutions[1]);
+    assert_eq!(values.get("X_INTRODUCED_5_"), Some(&Value::Int(4)));
+    assert_eq!(values.get("s"), None);
+
+    let output = model
+        .parse_solver_output("=====UNSATISFIABLE=====\n")
+        .unwrap();
+    assert_eq!(output.solutions, vec![]);
+    assert_eq!(output.status, Some(Status::Unsatisfiable));
+    // an interrupted solution is not reported
+    let output = model.parse_solver_output("s = array2d(1..3,").unwrap();
+    assert_eq!(output, SolverOutput::default());
+    assert_eq!(
+        model.parse_solver_output("s = 1;\n----------\n"),
+        Err(DznError::TypeMismatch {
+            id: "s".to_string(),
+            expected: model.type_of("s").unwrap(),
+        })
+    );
+}
+#[test]
+fn test_check_parsed_solution() {
+    let model: Model = "var 1..3: x :: output_var;\n\
+        var 1..3: y :: output_var;\n\
+        constraint int_lt(x,y);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let output = model
+        .parse_solver_output("x = 1;\ny = 2;\n----------\nx = 2;\ny = 2;\n----------\n")
+        .unwrap();
+    let reports: Vec<bool> = output
+        .solutions
+        .iter()
+        .map(|solution| {
+            let values = model.solution_values(solution);
+            let report = model.check_solution(|id| values.get(id).cloned(), None);
+            report.unwrap().is_ok()
+        })
+        .collect();
+    assert_eq!(reports, vec![true, false]);
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use winnow::error::{ContextError, StrContext};
+
+/// The kind of statement a [`ParseError`] occurred in.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum StmtKind {
+    Predicate,
+    Parameter,
+    Variable,
+    Constraint,
+    SolveItem,
+}
+
+impl StmtKind {
+    const ALL: [StmtKind; 5] = [
+        StmtKind::Predicate,
+        StmtKind::Parameter,
+        StmtKind::Variable,
+        StmtKind::Constraint,
+        StmtKind::SolveItem,
+    ];
+
+    /// The context label of the parser for statements of this kind, added
+    /// once the statement is recognized.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            StmtKind::Predicate => "Error while parsing predicate",
+            StmtKind::Parameter => "Error while parsing parameter declaration",
+            StmtKind::Variable => "Error while parsing variable declaration",
+            StmtKind::Constraint => "Error while parsing constraint",
+            StmtKind::SolveItem => "Error while parsing solve statement",
+        }
+    }
+
+    /// The kind of statement that was recognized before `error` occurred,
+    /// taken from the labels on its context stack.
+    pub(crate) fn from_context(error: &ContextError) -> Option<StmtKind> {
+        error.context().find_map(|context| match context {
+            StrContext::Label(label) => StmtKind::ALL
+                .into_iter()
+                .find(|kind| kind.label() == *label),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for StmtKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StmtKind::Predicate => write!(f, "predicate"),
+            StmtKind::Parameter => write!(f, "parameter declaration"),
+            StmtKind::Variable => write!(f, "variable declaration"),
+            StmtKind::Constraint => write!(f, "constraint"),
+            StmtKind::SolveItem => write!(f, "solve item"),
+        }
+    }
+}
+
+/// An error produced while parsing FlatZinc statements.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseError {
+    /// Byte offset of the error in the input
+    pub offset: usize,
+    /// Line of the error, starting at 1
+    pub line: usize,
+    /// Column of the error in characters, starting at 1
+    pub column: usize,
+    /// The kind of statement that was being parsed, if it could be determined
+    pub kind: Option<StmtKind>,
+    /// Descriptions of the tokens that would have been accepted
+    pub expected: Vec<String>,
+    /// The input at the error position, up to the end of the line
+    pub snippet: String,
+}
+
+const SNIPPET_LEN: usize = 40;
+
+impl ParseError {
+    pub(crate) fn new(
+        input: &str,
+        offset: usize,
+        kind: Option<StmtKind>,
+        expected: Vec<String>,
+    ) -> ParseError {
+        let (line, column) = line_column(input, offset);
+        let snippet = input[offset..]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim_end()
+            .chars()
+            .take(SNIPPET_LEN)
+            .collect();
+        ParseError {
+            offset,
+            line,
+            column,
+            kind,
+            expected,
+            snippet,
+        }
+    }
+
+    pub(crate) fn from_context_error(
+        input: &str,
+        offset: usize,
+        kind: Option<StmtKind>,
+        error: &ContextError,
+    ) -> ParseError {
+        let mut expected = vec![];
+        for context in error.context() {
+            if let StrContext::Expected(value) = context {
+                let value = value.to_string();
+                if !expected.contains(&value) {
+                    expected.push(value);
+                }
+            }
+        }
+        ParseError::new(input, offset, kind, expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match self.kind {
+            Some(kind) => write!(f, "failed to parse {}", kind)?,
+            None => write!(f, "unknown statement")?,
+        }
+        if let Some((last, rest)) = self.expected.split_last() {
+            write!(f, ", expected ")?;
+            if !rest.is_empty() {
+                write!(f, "{} or ", rest.join(", "))?;
+            }
+            write!(f, "{}", last)?;
+        }
+        if self.snippet.is_empty() {
+            write!(f, ", found end of input")
+        } else {
+            write!(f, ", found `{}`", self.snippet)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Compute the 1-based line and column of a byte offset in `input`.
+pub(crate) fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+#[test]
+fn test_line_column() {
+    let input = "var int: x;\nconstraint foo(x);";
+    assert_eq!(line_column(input, 0), (1, 1));
+    assert_eq!(line_column(input, 12), (2, 1));
+    assert_eq!(line_column(input, 27), (2, 16));
+}
+#[test]
+fn test_stmt_kind() {
+    use winnow::{error::AddContext, stream::Stream};
+    let input = "constraint foo(x);";
+    let error = ContextError::new();
+    assert_eq!(StmtKind::from_context(&error), None);
+    let error = error.add_context(&input, &input.checkpoint(), StrContext::Label("other"));
+    assert_eq!(StmtKind::from_context(&error), None);
+    for kind in StmtKind::ALL {
+        let error =
+            error
+                .clone()
+                .add_context(&input, &input.checkpoint(), StrContext::Label(kind.label()));
+        assert_eq!(StmtKind::from_context(&error), Some(kind));
+    }
+}
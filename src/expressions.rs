@@ -1,6 +1,6 @@
 use winnow::{
     ascii::{multispace0, multispace1},
-    combinator::{alt, delimited, opt, preceded, repeat, separated},
+    combinator::{alt, delimited, opt, preceded, repeat, separated, terminated},
     error::{FromExternalError, ParserError},
     token::{take_till, take_while},
     PResult, Parser,
@@ -9,13 +9,65 @@ use winnow::{
 use crate::{
     comments::space_or_comment0,
     primitive_literals::{
-        bool_literal, float_literal, identifier, int_literal, var_par_identifier,
+        bool_literal, float_literal, identifier, int_literal, var_par_identifier, ParseStr,
     },
+    spans::{spanned, NodeKind},
 };
 
-pub type Annotations = Vec<Annotation>;
+pub type Annotations<S = String> = Vec<Annotation<S>>;
+
+/// The role of a string passed to the callback of `map_str`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum StrRole {
+    /// A declared or referenced variable or parameter
+    Identifier,
+    /// The name of a predicate, an annotation or a predicate parameter,
+    /// a string literal or a comment
+    Name,
+}
+
+pub(crate) fn map_annotations<S, T>(
+    annos: Annotations<S>,
+    f: &mut impl FnMut(S, StrRole) -> T,
+) -> Annotations<T> {
+    annos.into_iter().map(|anno| anno.map_str(f)).collect()
+}
+
+/// Rebuild annotations with `expr` applied to the expressions and `access`
+/// to the array accesses they contain, keeping nested annotations, arrays
+/// and strings as they are.
+pub(crate) fn map_ann_exprs(
+    annos: &[Annotation],
+    expr: &impl Fn(&Expr) -> AnnExpr,
+    access: &impl Fn(&str, &IntExpr) -> AnnExpr,
+) -> Annotations {
+    let map = |e: &AnnExpr| map_ann_expr(e, expr, access);
+    annos
+        .iter()
+        .map(|anno| Annotation {
+            id: anno.id.clone(),
+            expressions: anno.expressions.iter().map(map).collect(),
+        })
+        .collect()
+}
+
+fn map_ann_expr(
+    e: &AnnExpr,
+    expr: &impl Fn(&Expr) -> AnnExpr,
+    access: &impl Fn(&str, &IntExpr) -> AnnExpr,
+) -> AnnExpr {
+    match e {
+        AnnExpr::Annotations(annos) => AnnExpr::Annotations(map_ann_exprs(annos, expr, access)),
+        AnnExpr::String(_) => e.clone(),
+        AnnExpr::Expr(e) => expr(e),
+        AnnExpr::ArrayAccess(id, index) => access(id, index),
+        AnnExpr::Array(v) => {
+            AnnExpr::Array(v.iter().map(|e| map_ann_expr(e, expr, access)).collect())
+        }
+    }
+}
 
-pub fn annotations<'a, E>(input: &mut &'a str) -> PResult<Annotations, E>
+pub fn annotations<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Annotations<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -24,7 +76,7 @@ where
     repeat(0.., annotation1).parse_next(input)
 }
 
-fn annotation1<'a, E>(input: &mut &'a str) -> PResult<Annotation, E>
+fn annotation1<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Annotation<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -32,28 +84,45 @@ where
 {
     "::".parse_next(input)?;
     space_or_comment0(input)?;
-    annotation(input)
+    spanned(NodeKind::Annotation, annotation).parse_next(input)
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub struct Annotation {
-    pub id: String,
-    pub expressions: Vec<AnnExpr>,
+pub struct Annotation<S = String> {
+    pub id: S,
+    pub expressions: Vec<AnnExpr<S>>,
+}
+
+impl<S> Annotation<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> Annotation<T> {
+        Annotation {
+            id: f(self.id, StrRole::Name),
+            expressions: self.expressions.into_iter().map(|e| e.map_str(f)).collect(),
+        }
+    }
+}
+
+impl<S: Into<String>> Annotation<S> {
+    /// Convert into an annotation that owns its strings.
+    pub fn into_owned(self) -> Annotation {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
 // <annotation> ::= <identifier>
 //                | <identifier> "(" <ann-expr> "," ... ")"
-fn annotation<'a, E>(input: &mut &'a str) -> PResult<Annotation, E>
+fn annotation<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Annotation<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
         + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
-    let id = identifier(input)?;
+    let id = spanned(NodeKind::Identifier, identifier).parse_next(input)?;
     let we = opt('(').parse_next(input)?;
     if we.is_some() {
         let expressions_what = separated(1.., ann_expr, ',').parse_next(input)?;
         ')'.parse_next(input)?;
+        space_or_comment0(input)?;
         Ok(Annotation {
             id,
             expressions: expressions_what,
@@ -70,23 +139,57 @@ where
 // <ann_expr> ::= <expr>
 //              | <string_literal>
 //              | "[" <annotation> "," ... "]"
+//              | <var-par-identifier> "[" <int-literal> "]"
+//              | "[" <ann_expr> "," ... "]"
+//
+// The index of an array access may also be an identifier, see `ae_array_access`.
 #[derive(PartialEq, Clone, Debug)]
-pub enum AnnExpr {
-    Annotations(Annotations),
-    String(String),
-    Expr(Expr),
+pub enum AnnExpr<S = String> {
+    Annotations(Annotations<S>),
+    String(S),
+    Expr(Expr<S>),
+    /// An array access like `x[1]`
+    ArrayAccess(S, IntExpr<S>),
+    /// An array that contains elements which are not expressions, like `[x[1],x[2]]`
+    Array(Vec<AnnExpr<S>>),
+}
+
+impl<S> AnnExpr<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> AnnExpr<T> {
+        match self {
+            AnnExpr::Annotations(annos) => AnnExpr::Annotations(map_annotations(annos, f)),
+            AnnExpr::String(s) => AnnExpr::String(f(s, StrRole::Name)),
+            AnnExpr::Expr(e) => AnnExpr::Expr(e.map_str(f)),
+            AnnExpr::ArrayAccess(id, index) => {
+                AnnExpr::ArrayAccess(f(id, StrRole::Identifier), index.map_str(f))
+            }
+            AnnExpr::Array(v) => AnnExpr::Array(v.into_iter().map(|e| e.map_str(f)).collect()),
+        }
+    }
+}
+
+impl<S: Into<String>> AnnExpr<S> {
+    /// Convert into an annotation that owns its strings.
+    pub fn into_owned(self) -> AnnExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
-fn ann_expr<'a, E>(input: &mut &'a str) -> PResult<AnnExpr, E>
+pub(crate) fn ann_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<AnnExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
         + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
-    alt((ann_non_array_expr, ae_annotations)).parse_next(input)
+    alt((
+        ann_non_array_expr,
+        spanned(NodeKind::AnnExpr, ae_annotations),
+        spanned(NodeKind::AnnExpr, ae_array),
+    ))
+    .parse_next(input)
 }
 
-fn ae_annotations<'a, E>(input: &mut &'a str) -> PResult<AnnExpr, E>
+fn ae_annotations<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<AnnExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -94,12 +197,26 @@ where
 {
     '['.parse_next(input)?;
     space_or_comment0(input)?;
-    let res = separated(1.., annotation, ',').parse_next(input)?;
+    let res = separated(1.., spanned(NodeKind::Annotation, annotation), ',').parse_next(input)?;
     space_or_comment0(input)?;
     ']'.parse_next(input)?;
     Ok(AnnExpr::Annotations(res))
 }
 
+fn ae_array<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<AnnExpr<S>, E>
+where
+    E: ParserError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, std::num::ParseFloatError>,
+{
+    space_or_comment0(input)?;
+    '['.parse_next(input)?;
+    let res =
+        separated(1.., terminated(ann_non_array_expr, space_or_comment0), ',').parse_next(input)?;
+    ']'.parse_next(input)?;
+    Ok(AnnExpr::Array(res))
+}
+
 // ann_non_array_expr ::=
 //       FZ_BOOL_LIT
 //     | FZ_INT_LIT
@@ -108,16 +225,64 @@ where
 //     | var_par_id /* variable, possibly array */
 //     | var_par_id '[' ann_non_array_expr ']' /* array access */
 //     | FZ_STRING_LIT
-fn ann_non_array_expr<'a, E>(input: &mut &'a str) -> PResult<AnnExpr, E>
+fn ann_non_array_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<AnnExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
         + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
-    alt((ae_expr, string_lit)).parse_next(input)
+    alt((
+        spanned(NodeKind::AnnExpr, ae_array_access),
+        spanned(NodeKind::AnnExpr, ae_expr),
+        spanned(NodeKind::AnnExpr, string_lit),
+    ))
+    .parse_next(input)
 }
 
-fn ae_expr<'a, E>(input: &mut &'a str) -> PResult<AnnExpr, E>
+// Only integer literals and identifiers are accepted as the index, since
+// any other `ann_non_array_expr` cannot index an array and the index is kept
+// as an `IntExpr`.
+fn ae_array_access<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<AnnExpr<S>, E>
+where
+    E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
+{
+    space_or_comment0(input)?;
+    let id = spanned(NodeKind::Identifier, var_par_identifier).parse_next(input)?;
+    space_or_comment0(input)?;
+    '['.parse_next(input)?;
+    let index = int_expr(input)?;
+    space_or_comment0(input)?;
+    ']'.parse_next(input)?;
+    Ok(AnnExpr::ArrayAccess(id, index))
+}
+#[test]
+fn test_ann_expr_array_access() {
+    use winnow::error::ContextError;
+    let mut input = "x[3]";
+    assert_eq!(
+        ann_expr::<String, ContextError>(&mut input),
+        Ok(AnnExpr::ArrayAccess("x".to_string(), IntExpr::Int(3)))
+    );
+    let mut input = "[x[1], x[ i ], y]";
+    assert_eq!(
+        ann_expr::<String, ContextError>(&mut input),
+        Ok(AnnExpr::Array(vec![
+            AnnExpr::ArrayAccess("x".to_string(), IntExpr::Int(1)),
+            AnnExpr::ArrayAccess("x".to_string(), IntExpr::VarParIdentifier("i".to_string())),
+            AnnExpr::Expr(Expr::VarParIdentifier("y".to_string())),
+        ]))
+    );
+    let mut input = "[x, y]";
+    assert_eq!(
+        ann_expr::<String, ContextError>(&mut input),
+        Ok(AnnExpr::Expr(Expr::ArrayOfBool(vec![
+            BoolExpr::VarParIdentifier("x".to_string()),
+            BoolExpr::VarParIdentifier("y".to_string()),
+        ])))
+    );
+}
+
+fn ae_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<AnnExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -236,27 +401,37 @@ where
 }
 
 /// Parse a string literal, including escaped characters such as `\n` and `\"`.
-pub fn string_lit<'a, E>(input: &mut &'a str) -> PResult<AnnExpr, E>
+pub fn string_lit<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<AnnExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
-    let build_string = repeat(0.., parse_fragment).fold(String::new, |mut string, fragment| {
-        match fragment {
-            StringFragment::Literal(s) => string.push_str(s),
-            StringFragment::EscapedChar(c) => string.push(c),
-            StringFragment::EscapedWS => {}
-        }
-        string
-    });
-    let string = delimited('"', build_string, '"').parse_next(input)?;
-    Ok(AnnExpr::String(string))
+    let mut raw = delimited(
+        '"',
+        repeat::<_, _, (), _, _>(0.., parse_fragment).recognize(),
+        '"',
+    )
+    .parse_next(input)?;
+    if !raw.contains('\\') {
+        return Ok(AnnExpr::String(raw.into()));
+    }
+    let string = repeat(0.., parse_fragment)
+        .fold(String::new, |mut string, fragment| {
+            match fragment {
+                StringFragment::Literal(s) => string.push_str(s),
+                StringFragment::EscapedChar(c) => string.push(c),
+                StringFragment::EscapedWS => {}
+            }
+            string
+        })
+        .parse_next(&mut raw)?;
+    Ok(AnnExpr::String(string.into()))
 }
 #[test]
 fn test_string_lit() {
     use winnow::error::ContextError;
     let mut input = "\"bla\"";
     assert_eq!(
-        string_lit::<ContextError>(&mut input),
+        string_lit::<String, ContextError>(&mut input),
         Ok(AnnExpr::String("bla".to_string()))
     );
 }
@@ -265,31 +440,64 @@ fn test_string_lit_escaped_characters() {
     use winnow::error::ContextError;
     let mut input = r#""escaped\"characters\ntest\u{0021}\u{01c3}""#;
     assert_eq!(
-        string_lit::<ContextError>(&mut input),
+        string_lit::<String, ContextError>(&mut input),
         Ok(AnnExpr::String("escaped\"characters\ntest!ǃ".to_string()))
     );
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum BoolExpr {
+pub enum BoolExpr<S = String> {
     Bool(bool),
-    VarParIdentifier(String),
+    VarParIdentifier(S),
+}
+
+impl<S> BoolExpr<S> {
+    fn identifier(&self) -> Option<&S> {
+        match self {
+            BoolExpr::VarParIdentifier(id) => Some(id),
+            BoolExpr::Bool(_) => None,
+        }
+    }
+
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> BoolExpr<T> {
+        match self {
+            BoolExpr::Bool(b) => BoolExpr::Bool(b),
+            BoolExpr::VarParIdentifier(id) => {
+                BoolExpr::VarParIdentifier(f(id, StrRole::Identifier))
+            }
+        }
+    }
+}
+
+impl<S: Into<String>> BoolExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> BoolExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
-pub fn bool_expr<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<BoolExpr, E> {
-    alt((be_bool_literal, be_var_par_identifier)).parse_next(input)
+pub fn bool_expr<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<BoolExpr<S>, E> {
+    spanned(
+        NodeKind::BoolExpr,
+        alt((be_bool_literal, be_var_par_identifier)),
+    )
+    .parse_next(input)
 }
 #[test]
 fn test_bool_expr() {
     use winnow::error::ContextError;
     let mut input = "true);";
     assert_eq!(
-        bool_expr::<ContextError>(&mut input),
+        bool_expr::<String, ContextError>(&mut input),
         Ok(BoolExpr::Bool(true))
     );
 }
 
-fn be_bool_literal<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<BoolExpr, E> {
+fn be_bool_literal<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<BoolExpr<S>, E> {
     let expr = bool_literal(input)?;
     Ok(BoolExpr::Bool(expr))
 }
@@ -298,21 +506,23 @@ fn test_bool_literal() {
     use winnow::error::ContextError;
     let mut input = "true);";
     assert_eq!(
-        be_bool_literal::<ContextError>(&mut input),
+        be_bool_literal::<String, ContextError>(&mut input),
         Ok(BoolExpr::Bool(true))
     );
     assert_eq!(input, ");");
 }
 
-fn be_var_par_identifier<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<BoolExpr, E> {
+fn be_var_par_identifier<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<BoolExpr<S>, E> {
     let id = var_par_identifier(input)?;
     Ok(BoolExpr::VarParIdentifier(id))
 }
 
-impl TryFrom<Expr> for BoolExpr {
+impl<S> TryFrom<Expr<S>> for BoolExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::Bool(value) => Ok(Self::Bool(value)),
@@ -321,8 +531,8 @@ impl TryFrom<Expr> for BoolExpr {
     }
 }
 
-impl From<BoolExpr> for Expr {
-    fn from(expr: BoolExpr) -> Self {
+impl<S> From<BoolExpr<S>> for Expr<S> {
+    fn from(expr: BoolExpr<S>) -> Self {
         match expr {
             BoolExpr::Bool(value) => Self::Bool(value),
             BoolExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -331,21 +541,48 @@ impl From<BoolExpr> for Expr {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum IntExpr {
+pub enum IntExpr<S = String> {
     Int(i128),
-    VarParIdentifier(String),
+    VarParIdentifier(S),
+}
+
+impl<S> IntExpr<S> {
+    fn identifier(&self) -> Option<&S> {
+        match self {
+            IntExpr::VarParIdentifier(id) => Some(id),
+            IntExpr::Int(_) => None,
+        }
+    }
+
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> IntExpr<T> {
+        match self {
+            IntExpr::Int(i) => IntExpr::Int(i),
+            IntExpr::VarParIdentifier(id) => IntExpr::VarParIdentifier(f(id, StrRole::Identifier)),
+        }
+    }
+}
+
+impl<S: Into<String>> IntExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> IntExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
-pub fn int_expr<'a, E>(input: &mut &'a str) -> PResult<IntExpr, E>
+pub fn int_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<IntExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
     space_or_comment0(input)?;
-    let expr = alt((ie_int_literal, ie_var_par_identifier)).parse_next(input)?;
+    let expr = spanned(
+        NodeKind::IntExpr,
+        alt((ie_int_literal, ie_var_par_identifier)),
+    )
+    .parse_next(input)?;
     Ok(expr)
 }
 
-fn ie_int_literal<'a, E>(input: &mut &'a str) -> PResult<IntExpr, E>
+fn ie_int_literal<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<IntExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -353,15 +590,17 @@ where
     Ok(IntExpr::Int(expr))
 }
 
-fn ie_var_par_identifier<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<IntExpr, E> {
+fn ie_var_par_identifier<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<IntExpr<S>, E> {
     let id = var_par_identifier(input)?;
     Ok(IntExpr::VarParIdentifier(id))
 }
 
-impl TryFrom<Expr> for IntExpr {
+impl<S> TryFrom<Expr<S>> for IntExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::Int(value) => Ok(Self::Int(value)),
@@ -370,8 +609,8 @@ impl TryFrom<Expr> for IntExpr {
     }
 }
 
-impl From<IntExpr> for Expr {
-    fn from(expr: IntExpr) -> Self {
+impl<S> From<IntExpr<S>> for Expr<S> {
+    fn from(expr: IntExpr<S>) -> Self {
         match expr {
             IntExpr::Int(value) => Self::Int(value),
             IntExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -380,19 +619,49 @@ impl From<IntExpr> for Expr {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum FloatExpr {
+pub enum FloatExpr<S = String> {
     Float(f64),
-    VarParIdentifier(String),
+    VarParIdentifier(S),
+}
+
+impl<S> FloatExpr<S> {
+    fn identifier(&self) -> Option<&S> {
+        match self {
+            FloatExpr::VarParIdentifier(id) => Some(id),
+            FloatExpr::Float(_) => None,
+        }
+    }
+
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> FloatExpr<T> {
+        match self {
+            FloatExpr::Float(f) => FloatExpr::Float(f),
+            FloatExpr::VarParIdentifier(id) => {
+                FloatExpr::VarParIdentifier(f(id, StrRole::Identifier))
+            }
+        }
+    }
 }
 
-pub fn float_expr<'a, E>(input: &mut &'a str) -> PResult<FloatExpr, E>
+impl<S: Into<String>> FloatExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> FloatExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+pub fn float_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<FloatExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
-    alt((fe_float_literal, fe_var_par_identifier)).parse_next(input)
+    space_or_comment0(input)?;
+    spanned(
+        NodeKind::FloatExpr,
+        alt((fe_float_literal, fe_var_par_identifier)),
+    )
+    .parse_next(input)
 }
 
-fn fe_float_literal<'a, E>(input: &mut &'a str) -> PResult<FloatExpr, E>
+fn fe_float_literal<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<FloatExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -400,17 +669,17 @@ where
     Ok(FloatExpr::Float(expr))
 }
 
-fn fe_var_par_identifier<'a, E: ParserError<&'a str>>(
+fn fe_var_par_identifier<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
     input: &mut &'a str,
-) -> PResult<FloatExpr, E> {
+) -> PResult<FloatExpr<S>, E> {
     let id = var_par_identifier(input)?;
     Ok(FloatExpr::VarParIdentifier(id))
 }
 
-impl TryFrom<Expr> for FloatExpr {
+impl<S> TryFrom<Expr<S>> for FloatExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::Float(value) => Ok(Self::Float(value)),
@@ -419,8 +688,8 @@ impl TryFrom<Expr> for FloatExpr {
     }
 }
 
-impl From<FloatExpr> for Expr {
-    fn from(expr: FloatExpr) -> Self {
+impl<S> From<FloatExpr<S>> for Expr<S> {
+    fn from(expr: FloatExpr<S>) -> Self {
         match expr {
             FloatExpr::Float(value) => Self::Float(value),
             FloatExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -429,21 +698,49 @@ impl From<FloatExpr> for Expr {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum SetExpr {
-    Set(SetLiteralExpr),
-    VarParIdentifier(String),
+pub enum SetExpr<S = String> {
+    Set(SetLiteralExpr<S>),
+    VarParIdentifier(S),
+}
+
+impl<S> SetExpr<S> {
+    fn for_each_identifier(&self, f: &mut impl FnMut(&S)) {
+        match self {
+            SetExpr::Set(set) => set.for_each_identifier(f),
+            SetExpr::VarParIdentifier(id) => f(id),
+        }
+    }
+
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> SetExpr<T> {
+        match self {
+            SetExpr::Set(set) => SetExpr::Set(set.map_str(f)),
+            SetExpr::VarParIdentifier(id) => SetExpr::VarParIdentifier(f(id, StrRole::Identifier)),
+        }
+    }
 }
 
-pub fn set_expr<'a, E>(input: &mut &'a str) -> PResult<SetExpr, E>
+impl<S: Into<String>> SetExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> SetExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+pub fn set_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SetExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
         + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
-    alt((se_set_literal_expr, se_var_par_identifier)).parse_next(input)
+    space_or_comment0(input)?;
+    alt((
+        spanned(NodeKind::SetExpr, se_set_literal_expr),
+        spanned(NodeKind::SetExpr, se_var_par_identifier),
+    ))
+    .parse_next(input)
 }
 
-fn se_set_literal_expr<'a, E>(input: &mut &'a str) -> PResult<SetExpr, E>
+fn se_set_literal_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SetExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -453,15 +750,17 @@ where
     Ok(SetExpr::Set(sl))
 }
 
-fn se_var_par_identifier<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<SetExpr, E> {
+fn se_var_par_identifier<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<SetExpr<S>, E> {
     let id = var_par_identifier(input)?;
     Ok(SetExpr::VarParIdentifier(id))
 }
 
-impl TryFrom<Expr> for SetExpr {
+impl<S> TryFrom<Expr<S>> for SetExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::Set(value) => Ok(Self::Set(value)),
@@ -470,8 +769,8 @@ impl TryFrom<Expr> for SetExpr {
     }
 }
 
-impl From<SetExpr> for Expr {
-    fn from(expr: SetExpr) -> Self {
+impl<S> From<SetExpr<S>> for Expr<S> {
+    fn from(expr: SetExpr<S>) -> Self {
         match expr {
             SetExpr::Set(value) => Self::Set(value),
             SetExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -480,19 +779,60 @@ impl From<SetExpr> for Expr {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum Expr {
-    VarParIdentifier(String),
+pub enum Expr<S = String> {
+    VarParIdentifier(S),
     Bool(bool),
     Int(i128),
     Float(f64),
-    Set(SetLiteralExpr),
-    ArrayOfBool(Vec<BoolExpr>),
-    ArrayOfInt(Vec<IntExpr>),
-    ArrayOfFloat(Vec<FloatExpr>),
-    ArrayOfSet(Vec<SetExpr>),
+    Set(SetLiteralExpr<S>),
+    ArrayOfBool(Vec<BoolExpr<S>>),
+    ArrayOfInt(Vec<IntExpr<S>>),
+    ArrayOfFloat(Vec<FloatExpr<S>>),
+    ArrayOfSet(Vec<SetExpr<S>>),
+}
+
+impl<S> Expr<S> {
+    /// Call `f` with every identifier in the expression.
+    pub(crate) fn for_each_identifier(&self, f: &mut impl FnMut(&S)) {
+        match self {
+            Expr::VarParIdentifier(id) => f(id),
+            Expr::Bool(_) | Expr::Int(_) | Expr::Float(_) => {}
+            Expr::Set(set) => set.for_each_identifier(f),
+            Expr::ArrayOfBool(v) => v.iter().filter_map(BoolExpr::identifier).for_each(f),
+            Expr::ArrayOfInt(v) => v.iter().filter_map(IntExpr::identifier).for_each(f),
+            Expr::ArrayOfFloat(v) => v.iter().filter_map(FloatExpr::identifier).for_each(f),
+            Expr::ArrayOfSet(v) => v.iter().for_each(|e| e.for_each_identifier(f)),
+        }
+    }
+
+    /// Apply `f` to every string, telling identifiers from other names.
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> Expr<T> {
+        match self {
+            Expr::VarParIdentifier(id) => Expr::VarParIdentifier(f(id, StrRole::Identifier)),
+            Expr::Bool(b) => Expr::Bool(b),
+            Expr::Int(i) => Expr::Int(i),
+            Expr::Float(f) => Expr::Float(f),
+            Expr::Set(set) => Expr::Set(set.map_str(f)),
+            Expr::ArrayOfBool(v) => {
+                Expr::ArrayOfBool(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+            Expr::ArrayOfInt(v) => Expr::ArrayOfInt(v.into_iter().map(|e| e.map_str(f)).collect()),
+            Expr::ArrayOfFloat(v) => {
+                Expr::ArrayOfFloat(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+            Expr::ArrayOfSet(v) => Expr::ArrayOfSet(v.into_iter().map(|e| e.map_str(f)).collect()),
+        }
+    }
+}
+
+impl<S: Into<String>> Expr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> Expr {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
-pub fn expr<'a, E>(input: &mut &'a str) -> PResult<Expr, E>
+pub fn expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Expr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -500,15 +840,15 @@ where
 {
     space_or_comment0(input)?;
     let expr = alt((
-        e_var_par_identifier,
-        e_bool_expr,
-        e_set_expr,
-        e_float_expr,
-        e_int_expr,
-        e_array_of_bool_expr,
-        e_array_of_int_expr,
-        e_array_of_float_expr,
-        e_array_of_set_expr,
+        spanned(NodeKind::Expr, e_var_par_identifier),
+        spanned(NodeKind::Expr, e_bool_expr),
+        spanned(NodeKind::Expr, e_set_expr),
+        spanned(NodeKind::Expr, e_float_expr),
+        spanned(NodeKind::Expr, e_int_expr),
+        spanned(NodeKind::Expr, e_array_of_bool_expr),
+        spanned(NodeKind::Expr, e_array_of_int_expr),
+        spanned(NodeKind::Expr, e_array_of_float_expr),
+        spanned(NodeKind::Expr, e_array_of_set_expr),
     ))
     .parse_next(input)?;
     Ok(expr)
@@ -518,7 +858,7 @@ fn test_expr() {
     use winnow::error::ContextError;
     let mut input = "1..2";
     assert_eq!(
-        expr::<ContextError>(&mut input),
+        expr::<String, ContextError>(&mut input),
         Ok(Expr::Set(SetLiteralExpr::IntInRange(
             IntExpr::Int(1),
             IntExpr::Int(2)
@@ -526,17 +866,21 @@ fn test_expr() {
     );
 }
 
-fn e_var_par_identifier<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<Expr, E> {
+fn e_var_par_identifier<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<Expr<S>, E> {
     let id = var_par_identifier(input)?;
     Ok(Expr::VarParIdentifier(id))
 }
 
-fn e_bool_expr<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<Expr, E> {
+fn e_bool_expr<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<Expr<S>, E> {
     let b = bool_literal(input)?;
     Ok(Expr::Bool(b))
 }
 
-fn e_int_expr<'a, E>(input: &mut &'a str) -> PResult<Expr, E>
+fn e_int_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Expr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -544,7 +888,7 @@ where
     Ok(Expr::Int(int))
 }
 
-fn e_float_expr<'a, E>(input: &mut &'a str) -> PResult<Expr, E>
+fn e_float_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Expr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -552,7 +896,7 @@ where
     Ok(Expr::Float(float))
 }
 
-fn e_set_expr<'a, E>(input: &mut &'a str) -> PResult<Expr, E>
+fn e_set_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Expr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -562,12 +906,14 @@ where
     Ok(Expr::Set(set))
 }
 
-fn e_array_of_bool_expr<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<Expr, E> {
+fn e_array_of_bool_expr<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<Expr<S>, E> {
     let v = array_of_bool_expr_literal(input)?;
     Ok(Expr::ArrayOfBool(v))
 }
 
-fn e_array_of_int_expr<'a, E>(input: &mut &'a str) -> PResult<Expr, E>
+fn e_array_of_int_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Expr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -575,7 +921,7 @@ where
     Ok(Expr::ArrayOfInt(v))
 }
 
-fn e_array_of_float_expr<'a, E>(input: &mut &'a str) -> PResult<Expr, E>
+fn e_array_of_float_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Expr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -583,7 +929,7 @@ where
     Ok(Expr::ArrayOfFloat(v))
 }
 
-fn e_array_of_set_expr<'a, E>(input: &mut &'a str) -> PResult<Expr, E>
+fn e_array_of_set_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Expr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -594,29 +940,70 @@ where
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum SetLiteralExpr {
-    IntInRange(IntExpr, IntExpr),
-    BoundedFloat(FloatExpr, FloatExpr),
-    SetFloats(Vec<FloatExpr>),
-    SetInts(Vec<IntExpr>),
+pub enum SetLiteralExpr<S = String> {
+    IntInRange(IntExpr<S>, IntExpr<S>),
+    BoundedFloat(FloatExpr<S>, FloatExpr<S>),
+    SetFloats(Vec<FloatExpr<S>>),
+    SetInts(Vec<IntExpr<S>>),
+}
+
+impl<S> SetLiteralExpr<S> {
+    fn for_each_identifier(&self, f: &mut impl FnMut(&S)) {
+        match self {
+            SetLiteralExpr::IntInRange(lb, ub) => [lb, ub]
+                .into_iter()
+                .filter_map(IntExpr::identifier)
+                .for_each(f),
+            SetLiteralExpr::BoundedFloat(lb, ub) => [lb, ub]
+                .into_iter()
+                .filter_map(FloatExpr::identifier)
+                .for_each(f),
+            SetLiteralExpr::SetFloats(v) => v.iter().filter_map(FloatExpr::identifier).for_each(f),
+            SetLiteralExpr::SetInts(v) => v.iter().filter_map(IntExpr::identifier).for_each(f),
+        }
+    }
+
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> SetLiteralExpr<T> {
+        match self {
+            SetLiteralExpr::IntInRange(lb, ub) => {
+                SetLiteralExpr::IntInRange(lb.map_str(f), ub.map_str(f))
+            }
+            SetLiteralExpr::BoundedFloat(lb, ub) => {
+                SetLiteralExpr::BoundedFloat(lb.map_str(f), ub.map_str(f))
+            }
+            SetLiteralExpr::SetFloats(v) => {
+                SetLiteralExpr::SetFloats(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+            SetLiteralExpr::SetInts(v) => {
+                SetLiteralExpr::SetInts(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+        }
+    }
 }
 
-fn set_literal_expr<'a, E>(input: &mut &'a str) -> PResult<SetLiteralExpr, E>
+impl<S: Into<String>> SetLiteralExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> SetLiteralExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+fn set_literal_expr<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SetLiteralExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
         + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
     alt((
-        sle_int_in_range,
-        sle_bounded_float,
-        sle_set_of_ints,
-        sle_set_of_floats,
+        spanned(NodeKind::SetLiteralExpr, sle_int_in_range),
+        spanned(NodeKind::SetLiteralExpr, sle_bounded_float),
+        spanned(NodeKind::SetLiteralExpr, sle_set_of_ints),
+        spanned(NodeKind::SetLiteralExpr, sle_set_of_floats),
     ))
     .parse_next(input)
 }
 
-fn sle_int_in_range<'a, E>(input: &mut &'a str) -> PResult<SetLiteralExpr, E>
+fn sle_int_in_range<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SetLiteralExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -628,7 +1015,7 @@ where
     Ok(SetLiteralExpr::IntInRange(lb, ub))
 }
 
-fn sle_bounded_float<'a, E>(input: &mut &'a str) -> PResult<SetLiteralExpr, E>
+fn sle_bounded_float<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SetLiteralExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -641,7 +1028,7 @@ where
 }
 
 // "{" <int-expr> "," ... "}"
-fn sle_set_of_ints<'a, E>(input: &mut &'a str) -> PResult<SetLiteralExpr, E>
+fn sle_set_of_ints<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SetLiteralExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -654,7 +1041,7 @@ where
 }
 
 // "{" <float-expr> "," ... "}"
-fn sle_set_of_floats<'a, E>(input: &mut &'a str) -> PResult<SetLiteralExpr, E>
+fn sle_set_of_floats<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SetLiteralExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -674,12 +1061,32 @@ pub enum SetLiteral {
     SetInts(Vec<i128>),
 }
 
+impl<S> From<SetLiteral> for SetLiteralExpr<S> {
+    fn from(set: SetLiteral) -> Self {
+        match set {
+            SetLiteral::IntRange(lb, ub) => {
+                SetLiteralExpr::IntInRange(IntExpr::Int(lb), IntExpr::Int(ub))
+            }
+            SetLiteral::BoundedFloat(lb, ub) => {
+                SetLiteralExpr::BoundedFloat(FloatExpr::Float(lb), FloatExpr::Float(ub))
+            }
+            SetLiteral::SetFloats(v) => {
+                SetLiteralExpr::SetFloats(v.into_iter().map(FloatExpr::Float).collect())
+            }
+            SetLiteral::SetInts(v) => {
+                SetLiteralExpr::SetInts(v.into_iter().map(IntExpr::Int).collect())
+            }
+        }
+    }
+}
+
 pub fn set_literal<'a, E>(input: &mut &'a str) -> PResult<SetLiteral, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
         + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
+    space_or_comment0(input)?;
     alt((
         sl_int_range,
         sl_bounded_float,
@@ -740,14 +1147,34 @@ where
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum ArrayOfBoolExpr {
-    Array(Vec<BoolExpr>),
-    VarParIdentifier(String),
+pub enum ArrayOfBoolExpr<S = String> {
+    Array(Vec<BoolExpr<S>>),
+    VarParIdentifier(S),
+}
+
+impl<S> ArrayOfBoolExpr<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> ArrayOfBoolExpr<T> {
+        match self {
+            ArrayOfBoolExpr::Array(v) => {
+                ArrayOfBoolExpr::Array(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+            ArrayOfBoolExpr::VarParIdentifier(id) => {
+                ArrayOfBoolExpr::VarParIdentifier(f(id, StrRole::Identifier))
+            }
+        }
+    }
 }
 
-pub fn array_of_bool_expr<'a, E: ParserError<&'a str>>(
+impl<S: Into<String>> ArrayOfBoolExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> ArrayOfBoolExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+pub fn array_of_bool_expr<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
     input: &mut &'a str,
-) -> PResult<ArrayOfBoolExpr, E> {
+) -> PResult<ArrayOfBoolExpr<S>, E> {
     let id = opt(var_par_identifier).parse_next(input)?;
     if let Some(id) = id {
         Ok(ArrayOfBoolExpr::VarParIdentifier(id))
@@ -757,9 +1184,9 @@ pub fn array_of_bool_expr<'a, E: ParserError<&'a str>>(
     }
 }
 
-fn array_of_bool_expr_literal<'a, E: ParserError<&'a str>>(
+fn array_of_bool_expr_literal<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
     input: &mut &'a str,
-) -> PResult<Vec<BoolExpr>, E> {
+) -> PResult<Vec<BoolExpr<S>>, E> {
     '['.parse_next(input)?;
     space_or_comment0(input)?;
     let v = separated(0.., bool_expr, array_separator).parse_next(input)?;
@@ -780,16 +1207,16 @@ pub fn array_of_bool_literal<'a, E: ParserError<&'a str>>(
 ) -> PResult<Vec<bool>, E> {
     '['.parse_next(input)?;
     space_or_comment0(input)?;
-    let al = separated(0.., bool_literal, ',').parse_next(input)?;
+    let al = separated(0.., bool_literal, array_separator).parse_next(input)?;
     space_or_comment0(input)?;
     ']'.parse_next(input)?;
     Ok(al)
 }
 
-impl TryFrom<Expr> for ArrayOfBoolExpr {
+impl<S> TryFrom<Expr<S>> for ArrayOfBoolExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::ArrayOfBool(value) => Ok(Self::Array(value)),
@@ -798,8 +1225,8 @@ impl TryFrom<Expr> for ArrayOfBoolExpr {
     }
 }
 
-impl From<ArrayOfBoolExpr> for Expr {
-    fn from(expr: ArrayOfBoolExpr) -> Self {
+impl<S> From<ArrayOfBoolExpr<S>> for Expr<S> {
+    fn from(expr: ArrayOfBoolExpr<S>) -> Self {
         match expr {
             ArrayOfBoolExpr::Array(value) => Self::ArrayOfBool(value),
             ArrayOfBoolExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -808,12 +1235,34 @@ impl From<ArrayOfBoolExpr> for Expr {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum ArrayOfIntExpr {
-    Array(Vec<IntExpr>),
-    VarParIdentifier(String),
+pub enum ArrayOfIntExpr<S = String> {
+    Array(Vec<IntExpr<S>>),
+    VarParIdentifier(S),
+}
+
+impl<S> ArrayOfIntExpr<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> ArrayOfIntExpr<T> {
+        match self {
+            ArrayOfIntExpr::Array(v) => {
+                ArrayOfIntExpr::Array(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+            ArrayOfIntExpr::VarParIdentifier(id) => {
+                ArrayOfIntExpr::VarParIdentifier(f(id, StrRole::Identifier))
+            }
+        }
+    }
+}
+
+impl<S: Into<String>> ArrayOfIntExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> ArrayOfIntExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
-pub fn array_of_int_expr<'a, E>(input: &mut &'a str) -> PResult<ArrayOfIntExpr, E>
+pub fn array_of_int_expr<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<ArrayOfIntExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -826,7 +1275,9 @@ where
     }
 }
 
-fn array_of_int_expr_literal<'a, E>(input: &mut &'a str) -> PResult<Vec<IntExpr>, E>
+fn array_of_int_expr_literal<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<Vec<IntExpr<S>>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -850,10 +1301,10 @@ where
     Ok(al)
 }
 
-impl TryFrom<Expr> for ArrayOfIntExpr {
+impl<S> TryFrom<Expr<S>> for ArrayOfIntExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::ArrayOfInt(value) => Ok(Self::Array(value)),
@@ -862,8 +1313,8 @@ impl TryFrom<Expr> for ArrayOfIntExpr {
     }
 }
 
-impl From<ArrayOfIntExpr> for Expr {
-    fn from(expr: ArrayOfIntExpr) -> Self {
+impl<S> From<ArrayOfIntExpr<S>> for Expr<S> {
+    fn from(expr: ArrayOfIntExpr<S>) -> Self {
         match expr {
             ArrayOfIntExpr::Array(value) => Self::ArrayOfInt(value),
             ArrayOfIntExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -872,12 +1323,34 @@ impl From<ArrayOfIntExpr> for Expr {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum ArrayOfFloatExpr {
-    Array(Vec<FloatExpr>),
-    VarParIdentifier(String),
+pub enum ArrayOfFloatExpr<S = String> {
+    Array(Vec<FloatExpr<S>>),
+    VarParIdentifier(S),
+}
+
+impl<S> ArrayOfFloatExpr<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> ArrayOfFloatExpr<T> {
+        match self {
+            ArrayOfFloatExpr::Array(v) => {
+                ArrayOfFloatExpr::Array(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+            ArrayOfFloatExpr::VarParIdentifier(id) => {
+                ArrayOfFloatExpr::VarParIdentifier(f(id, StrRole::Identifier))
+            }
+        }
+    }
+}
+
+impl<S: Into<String>> ArrayOfFloatExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> ArrayOfFloatExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
-pub fn array_of_float_expr<'a, E>(input: &mut &'a str) -> PResult<ArrayOfFloatExpr, E>
+pub fn array_of_float_expr<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<ArrayOfFloatExpr<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -890,7 +1363,9 @@ where
     }
 }
 
-fn array_of_float_expr_literal<'a, E>(input: &mut &'a str) -> PResult<Vec<FloatExpr>, E>
+fn array_of_float_expr_literal<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<Vec<FloatExpr<S>>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -914,10 +1389,10 @@ where
     Ok(al)
 }
 
-impl TryFrom<Expr> for ArrayOfFloatExpr {
+impl<S> TryFrom<Expr<S>> for ArrayOfFloatExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::ArrayOfFloat(value) => Ok(Self::Array(value)),
@@ -926,8 +1401,8 @@ impl TryFrom<Expr> for ArrayOfFloatExpr {
     }
 }
 
-impl From<ArrayOfFloatExpr> for Expr {
-    fn from(expr: ArrayOfFloatExpr) -> Self {
+impl<S> From<ArrayOfFloatExpr<S>> for Expr<S> {
+    fn from(expr: ArrayOfFloatExpr<S>) -> Self {
         match expr {
             ArrayOfFloatExpr::Array(value) => Self::ArrayOfFloat(value),
             ArrayOfFloatExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -936,12 +1411,34 @@ impl From<ArrayOfFloatExpr> for Expr {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum ArrayOfSetExpr {
-    Array(Vec<SetExpr>),
-    VarParIdentifier(String),
+pub enum ArrayOfSetExpr<S = String> {
+    Array(Vec<SetExpr<S>>),
+    VarParIdentifier(S),
+}
+
+impl<S> ArrayOfSetExpr<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> ArrayOfSetExpr<T> {
+        match self {
+            ArrayOfSetExpr::Array(v) => {
+                ArrayOfSetExpr::Array(v.into_iter().map(|e| e.map_str(f)).collect())
+            }
+            ArrayOfSetExpr::VarParIdentifier(id) => {
+                ArrayOfSetExpr::VarParIdentifier(f(id, StrRole::Identifier))
+            }
+        }
+    }
+}
+
+impl<S: Into<String>> ArrayOfSetExpr<S> {
+    /// Convert into an expression that owns its strings.
+    pub fn into_owned(self) -> ArrayOfSetExpr {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
-pub fn array_of_set_expr<'a, E>(input: &mut &'a str) -> PResult<ArrayOfSetExpr, E>
+pub fn array_of_set_expr<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<ArrayOfSetExpr<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -956,7 +1453,9 @@ where
     }
 }
 
-fn array_of_set_expr_literal<'a, E>(input: &mut &'a str) -> PResult<Vec<SetExpr>, E>
+fn array_of_set_expr_literal<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<Vec<SetExpr<S>>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -984,10 +1483,10 @@ where
     Ok(al)
 }
 
-impl TryFrom<Expr> for ArrayOfSetExpr {
+impl<S> TryFrom<Expr<S>> for ArrayOfSetExpr<S> {
     type Error = ();
 
-    fn try_from(expr: Expr) -> Result<Self, Self::Error> {
+    fn try_from(expr: Expr<S>) -> Result<Self, Self::Error> {
         match expr {
             Expr::VarParIdentifier(id) => Ok(Self::VarParIdentifier(id)),
             Expr::ArrayOfSet(value) => Ok(Self::Array(value)),
@@ -996,8 +1495,8 @@ impl TryFrom<Expr> for ArrayOfSetExpr {
     }
 }
 
-impl From<ArrayOfSetExpr> for Expr {
-    fn from(expr: ArrayOfSetExpr) -> Self {
+impl<S> From<ArrayOfSetExpr<S>> for Expr<S> {
+    fn from(expr: ArrayOfSetExpr<S>) -> Self {
         match expr {
             ArrayOfSetExpr::Array(value) => Self::ArrayOfSet(value),
             ArrayOfSetExpr::VarParIdentifier(id) => self::Expr::VarParIdentifier(id),
@@ -0,0 +1,305 @@
+//! Replacing references to parameters with their values.
+
+use crate::{
+    constraints::ConstraintItem,
+    expressions::{
+        map_ann_exprs, AnnExpr, Annotation, BoolExpr, Expr, FloatExpr, IntExpr, SetExpr,
+        SetLiteralExpr,
+    },
+    model::Model,
+    parameters::declarations::ParDeclItem,
+    resolve::ResolveError,
+    solve_items::{Goal, SolveItem},
+};
+
+impl Model {
+    /// Replace every reference to a parameter in the arguments and
+    /// annotations of constraints and in the solve item with its value.
+    ///
+    /// The model is resolved first, see [`Model::resolve`], so that
+    /// parameters in array literals are replaced by literals of the right
+    /// type. An array access like `X[2]` in an annotation is replaced by the
+    /// element if `X` is a parameter array. Declarations are left unchanged.
+    /// On error the model is left unchanged.
+    pub fn inline_pars(&mut self) -> Result<(), ResolveError> {
+        self.resolve()?;
+        let constraints = self
+            .constraints
+            .iter()
+            .map(|c| ConstraintItem {
+                id: c.id.clone(),
+                exprs: c.exprs.iter().map(|e| self.inline_expr(e)).collect(),
+                annos: self.inline_annotations(&c.annos),
+            })
+            .collect();
+        let goal = match &self.solve.goal {
+            Goal::Satisfy => Goal::Satisfy,
+            Goal::OptimizeBool(opt_type, e) => Goal::OptimizeBool(opt_type.clone(), self.bool(e)),
+            Goal::OptimizeInt(opt_type, e) => Goal::OptimizeInt(opt_type.clone(), self.int(e)),
+            Goal::OptimizeFloat(opt_type, e) => {
+                Goal::OptimizeFloat(opt_type.clone(), self.float(e))
+            }
+            Goal::OptimizeSet(opt_type, e) => Goal::OptimizeSet(opt_type.clone(), self.set(e)),
+        };
+        self.solve = SolveItem {
+            goal,
+            annotations: self.inline_annotations(&self.solve.annotations),
+        };
+        self.constraints = constraints;
+        Ok(())
+    }
+
+    /// Replace the parameters in a single expression, see [`Model::inline_pars`].
+    ///
+    /// An array literal parsed as [`Expr::ArrayOfBool`] is resolved first if
+    /// possible. Otherwise its elements may have different types, so only its
+    /// bool parameters are inlined; in annotations such an array becomes an
+    /// [`AnnExpr::Array`] of its elements inlined by their declared types.
+    pub fn inline_expr(&self, expr: &Expr) -> Expr {
+        match expr {
+            Expr::VarParIdentifier(id) => match self.par(id) {
+                Some(item) => par_value(item),
+                None => expr.clone(),
+            },
+            Expr::Bool(_) | Expr::Int(_) | Expr::Float(_) => expr.clone(),
+            Expr::Set(set) => Expr::Set(self.set_literal(set)),
+            // arrays of identifiers are resolved here, since annotations are
+            // not resolved by `Model::resolve`
+            Expr::ArrayOfBool(v) => match self.resolve_expr(expr) {
+                Ok(Expr::ArrayOfBool(_)) | Err(_) => {
+                    Expr::ArrayOfBool(v.iter().map(|e| self.bool(e)).collect())
+                }
+                Ok(resolved) => self.inline_expr(&resolved),
+            },
+            Expr::ArrayOfInt(v) => Expr::ArrayOfInt(v.iter().map(|e| self.int(e)).collect()),
+            Expr::ArrayOfFloat(v) => Expr::ArrayOfFloat(v.iter().map(|e| self.float(e)).collect()),
+            Expr::ArrayOfSet(v) => Expr::ArrayOfSet(v.iter().map(|e| self.set(e)).collect()),
+        }
+    }
+
+    /// Like [`Model::inline_expr`], but an array literal that cannot be
+    /// resolved becomes an array of its elements, each inlined by the declared
+    /// type of its identifier.
+    fn inline_ann_expr(&self, expr: &Expr) -> AnnExpr {
+        match expr {
+            Expr::ArrayOfBool(v) if self.resolve_expr(expr).is_err() => AnnExpr::Array(
+                v.iter()
+                    .map(|e| match e {
+                        BoolExpr::VarParIdentifier(id) => {
+                            AnnExpr::Expr(self.inline_expr(&Expr::VarParIdentifier(id.clone())))
+                        }
+                        BoolExpr::Bool(b) => AnnExpr::Expr(Expr::Bool(*b)),
+                    })
+                    .collect(),
+            ),
+            _ => AnnExpr::Expr(self.inline_expr(expr)),
+        }
+    }
+
+    fn inline_annotations(&self, annos: &[Annotation]) -> Vec<Annotation> {
+        map_ann_exprs(annos, &|e| self.inline_ann_expr(e), &|id, index| {
+            let index = self.int(index);
+            let element = match (self.par(id), &index) {
+                (Some(item), IntExpr::Int(i)) => par_element(item, *i),
+                _ => None,
+            };
+            match element {
+                Some(element) => AnnExpr::Expr(element),
+                None => AnnExpr::ArrayAccess(id.to_string(), index),
+            }
+        })
+    }
+
+    fn bool(&self, e: &BoolExpr) -> BoolExpr {
+        match e {
+            BoolExpr::VarParIdentifier(id) => match self.par(id) {
+                Some(ParDeclItem::Bool { bool, .. }) => BoolExpr::Bool(*bool),
+                _ => e.clone(),
+            },
+            BoolExpr::Bool(_) => e.clone(),
+        }
+    }
+
+    fn int(&self, e: &IntExpr) -> IntExpr {
+        match e {
+            IntExpr::VarParIdentifier(id) => match self.par(id) {
+                Some(ParDeclItem::Int { int, .. }) => IntExpr::Int(*int),
+                _ => e.clone(),
+            },
+            IntExpr::Int(_) => e.clone(),
+        }
+    }
+
+    fn float(&self, e: &FloatExpr) -> FloatExpr {
+        match e {
+            FloatExpr::VarParIdentifier(id) => match self.par(id) {
+                Some(ParDeclItem::Float { float, .. }) => FloatExpr::Float(*float),
+                _ => e.clone(),
+            },
+            FloatExpr::Float(_) => e.clone(),
+        }
+    }
+
+    fn set(&self, e: &SetExpr) -> SetExpr {
+        match e {
+            SetExpr::VarParIdentifier(id) => match self.par(id) {
+                Some(ParDeclItem::SetOfInt { set_literal, .. }) => {
+                    SetExpr::Set(set_literal.clone().into())
+                }
+                _ => e.clone(),
+            },
+            SetExpr::Set(set) => SetExpr::Set(self.set_literal(set)),
+        }
+    }
+
+    fn set_literal(&self, set: &SetLiteralExpr) -> SetLiteralExpr {
+        match set {
+            SetLiteralExpr::IntInRange(lb, ub) => {
+                SetLiteralExpr::IntInRange(self.int(lb), self.int(ub))
+            }
+            SetLiteralExpr::BoundedFloat(lb, ub) => {
+                SetLiteralExpr::BoundedFloat(self.float(lb), self.float(ub))
+            }
+            SetLiteralExpr::SetFloats(v) => {
+                SetLiteralExpr::SetFloats(v.iter().map(|e| self.float(e)).collect())
+            }
+            SetLiteralExpr::SetInts(v) => {
+                SetLiteralExpr::SetInts(v.iter().map(|e| self.int(e)).collect())
+            }
+        }
+    }
+}
+
+/// The value of a parameter as an expression.
+fn par_value(item: &ParDeclItem) -> Expr {
+    match item {
+        ParDeclItem::Bool { bool, .. } => Expr::Bool(*bool),
+        ParDeclItem::Int { int, .. } => Expr::Int(*int),
+        ParDeclItem::Float { float, .. } => Expr::Float(*float),
+        ParDeclItem::SetOfInt { set_literal, .. } => Expr::Set(set_literal.clone().into()),
+        ParDeclItem::ArrayOfBool { v, .. } => {
+            Expr::ArrayOfBool(v.iter().map(|b| BoolExpr::Bool(*b)).collect())
+        }
+        ParDeclItem::ArrayOfInt { v, .. } => {
+            Expr::ArrayOfInt(v.iter().map(|i| IntExpr::Int(*i)).collect())
+        }
+        ParDeclItem::ArrayOfFloat { v, .. } => {
+            Expr::ArrayOfFloat(v.iter().map(|f| FloatExpr::Float(*f)).collect())
+        }
+        ParDeclItem::ArrayOfSet { v, .. } => {
+            Expr::ArrayOfSet(v.iter().map(|s| SetExpr::Set(s.clone().into())).collect())
+        }
+    }
+}
+
+/// The element of a parameter array at the 1-based index `i`.
+fn par_element(item: &ParDeclItem, i: i128) -> Option<Expr> {
+    let i = usize::try_from(i).ok()?.checked_sub(1)?;
+    Some(match item {
+        ParDeclItem::ArrayOfBool { v, .. } => Expr::Bool(*v.get(i)?),
+        ParDeclItem::ArrayOfInt { v, .. } => Expr::Int(*v.get(i)?),
+        ParDeclItem::ArrayOfFloat { v, .. } => Expr::Float(*v.get(i)?),
+        ParDeclItem::ArrayOfSet { v, .. } => Expr::Set(v.get(i)?.clone().into()),
+        _ => return None,
+    })
+}
+#[test]
+fn test_inline_pars() {
+    use crate::solve_items::OptimizationType;
+    let mut model: Model = "bool: t = true;\n\
+        int: k = 3;\n\
+        float: w = 0.5;\n\
+        set of int: S = {1,3};\n\
+        array [1..2] of int: c = [1,-1];\n\
+        array [1..2] of set of int: d = [1..2,{}];\n\
+        var int: x;\n\
+        var int: y;\n\
+        var bool: b;\n\
+        var set of 1..3: s;\n\
+        constraint int_lin_le(c,[x,y],k);\n\
+        constraint int_le(k,x) :: foo(c[2], c[3], [k, k], [k, w], [k, u, t]);\n\
+        constraint array_bool_and([b,t],b);\n\
+        constraint set_in(x,S);\n\
+        constraint array_set_element(x,d,s);\n\
+        constraint set_in(x,{k,5});\n\
+        constraint float_le(w,1.0);\n\
+        solve :: int_search([x,y],input_order,indomain_min,complete) maximize k;"
+        .parse()
+        .unwrap();
+    model.inline_pars().unwrap();
+    let int = |v: &[i128]| Expr::ArrayOfInt(v.iter().map(|i| IntExpr::Int(*i)).collect());
+    let id = |id: &str| IntExpr::VarParIdentifier(id.to_string());
+    let c = model.constraints();
+    assert_eq!(
+        c[0].exprs,
+        vec![
+            int(&[1, -1]),
+            Expr::ArrayOfInt(vec![id("x"), id("y")]),
+            Expr::Int(3)
+        ]
+    );
+    assert_eq!(c[1].exprs[0], Expr::Int(3));
+    assert_eq!(
+        c[1].annos[0].expressions,
+        vec![
+            AnnExpr::Expr(Expr::Int(-1)),
+            AnnExpr::ArrayAccess("c".to_string(), IntExpr::Int(3)),
+            AnnExpr::Expr(int(&[3, 3])),
+            AnnExpr::Array(vec![
+                AnnExpr::Expr(Expr::Int(3)),
+                AnnExpr::Expr(Expr::Float(0.5))
+            ]),
+            AnnExpr::Array(vec![
+                AnnExpr::Expr(Expr::Int(3)),
+                AnnExpr::Expr(Expr::VarParIdentifier("u".to_string())),
+                AnnExpr::Expr(Expr::Bool(true))
+            ]),
+        ]
+    );
+    assert_eq!(
+        c[2].exprs[0],
+        Expr::ArrayOfBool(vec![
+            BoolExpr::VarParIdentifier("b".to_string()),
+            BoolExpr::Bool(true)
+        ])
+    );
+    assert_eq!(
+        c[3].exprs[1],
+        Expr::Set(SetLiteralExpr::SetInts(vec![
+            IntExpr::Int(1),
+            IntExpr::Int(3)
+        ]))
+    );
+    assert_eq!(
+        c[4].exprs[1],
+        Expr::ArrayOfSet(vec![
+            SetExpr::Set(SetLiteralExpr::IntInRange(IntExpr::Int(1), IntExpr::Int(2))),
+            SetExpr::Set(SetLiteralExpr::SetInts(vec![])),
+        ])
+    );
+    assert_eq!(
+        c[5].exprs[1],
+        Expr::Set(SetLiteralExpr::SetInts(vec![
+            IntExpr::Int(3),
+            IntExpr::Int(5)
+        ]))
+    );
+    assert_eq!(c[6].exprs[0], Expr::Float(0.5));
+    assert_eq!(
+        model.solve().goal,
+        Goal::OptimizeInt(OptimizationType::Maximize, IntExpr::Int(3))
+    );
+    assert_eq!(
+        model.solve().annotations[0].expressions[0],
+        AnnExpr::Expr(Expr::ArrayOfInt(vec![id("x"), id("y")]))
+    );
+    assert!(model.par("c").is_some());
+
+    let mut model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    model.inline_pars().unwrap();
+    assert!(model
+        .constraints()
+        .iter()
+        .filter(|c| c.id.starts_with("int_lin_le"))
+        .all(|c| c.exprs[0] == int(&[1, -1])));
+}
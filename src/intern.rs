@@ -0,0 +1,178 @@
+use crate::{
+    constraints::ConstraintItem,
+    expressions::StrRole,
+    model::{Decl, Model},
+    parameters::declarations::ParDeclItem,
+    solve_items::SolveItem,
+    variables::declarations::VarDeclItem,
+};
+
+/// Dense id of a variable: its position in [`Model::vars`].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct VarId(usize);
+
+impl VarId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Dense id of a parameter: its position in [`Model::pars`].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct ParId(usize);
+
+impl ParId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A string of an interned AST.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum Symbol {
+    Var(VarId),
+    Par(ParId),
+    /// Any other string, such as the name of a predicate or annotation, or
+    /// an undeclared identifier in an annotation like `input_order`
+    Name(String),
+}
+
+/// A view of a [`Model`] in which the identifiers of declared variables and
+/// parameters are replaced by their ids.
+///
+/// Declarations keep their positions, so `vars[id.index()]` is the
+/// declaration of the variable with id `id`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct InternedModel {
+    pub pars: Vec<ParDeclItem<Symbol>>,
+    pub vars: Vec<VarDeclItem<Symbol>>,
+    pub constraints: Vec<ConstraintItem<Symbol>>,
+    pub solve: SolveItem<Symbol>,
+}
+
+impl Model {
+    /// Look up the id of a declared variable.
+    pub fn var_id(&self, name: &str) -> Option<VarId> {
+        match self.decls.get(name)? {
+            Decl::Var(i) => Some(VarId(*i)),
+            Decl::Par(_) => None,
+        }
+    }
+
+    /// Look up the id of a declared parameter.
+    pub fn par_id(&self, name: &str) -> Option<ParId> {
+        match self.decls.get(name)? {
+            Decl::Par(i) => Some(ParId(*i)),
+            Decl::Var(_) => None,
+        }
+    }
+
+    /// The identifier of a variable. Panics if `id` belongs to another model.
+    pub fn var_name(&self, id: VarId) -> &str {
+        self.vars[id.0].id()
+    }
+
+    /// The identifier of a parameter. Panics if `id` belongs to another model.
+    pub fn par_name(&self, id: ParId) -> &str {
+        self.pars[id.0].id()
+    }
+
+    /// Intern an identifier, falling back to [`Symbol::Name`] if it is not declared.
+    pub fn symbol(&self, name: &str) -> Symbol {
+        match self.decls.get(name) {
+            Some(Decl::Var(i)) => Symbol::Var(VarId(*i)),
+            Some(Decl::Par(i)) => Symbol::Par(ParId(*i)),
+            None => Symbol::Name(name.to_string()),
+        }
+    }
+
+    /// The string a symbol stands for.
+    pub fn name_of<'a>(&'a self, symbol: &'a Symbol) -> &'a str {
+        match symbol {
+            Symbol::Var(id) => self.var_name(*id),
+            Symbol::Par(id) => self.par_name(*id),
+            Symbol::Name(name) => name,
+        }
+    }
+
+    /// Build the interned view of the model.
+    pub fn intern(&self) -> InternedModel {
+        let mut f = |s: String, role| match role {
+            StrRole::Identifier => match self.decls.get(&s) {
+                Some(Decl::Var(i)) => Symbol::Var(VarId(*i)),
+                Some(Decl::Par(i)) => Symbol::Par(ParId(*i)),
+                None => Symbol::Name(s),
+            },
+            StrRole::Name => Symbol::Name(s),
+        };
+        InternedModel {
+            pars: self
+                .pars
+                .iter()
+                .map(|p| p.clone().map_str(&mut f))
+                .collect(),
+            vars: self
+                .vars
+                .iter()
+                .map(|v| v.clone().map_str(&mut f))
+                .collect(),
+            constraints: self
+                .constraints
+                .iter()
+                .map(|c| c.clone().map_str(&mut f))
+                .collect(),
+            solve: self.solve.clone().map_str(&mut f),
+        }
+    }
+}
+#[test]
+fn test_intern_jobshop() {
+    use crate::expressions::{AnnExpr, Expr};
+    use crate::solve_items::Goal;
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    let x0 = model.var_id("X_INTRODUCED_0_").unwrap();
+    assert_eq!(x0.index(), 0);
+    assert_eq!(model.var_name(x0), "X_INTRODUCED_0_");
+    let coeffs = model.par_id("X_INTRODUCED_22_").unwrap();
+    assert_eq!(model.par_name(coeffs), "X_INTRODUCED_22_");
+    assert_eq!(model.var_id("X_INTRODUCED_22_"), None);
+    assert_eq!(
+        model.symbol("nonexistent"),
+        Symbol::Name("nonexistent".to_string())
+    );
+
+    let interned = model.intern();
+    assert_eq!(interned.vars.len(), model.vars().len());
+    let last = interned.constraints.last().unwrap();
+    assert_eq!(last.id, Symbol::Name("int_lin_le_reif".to_string()));
+    assert_eq!(last.exprs[0], Expr::VarParIdentifier(Symbol::Par(coeffs)));
+    let defined = model.var_id("X_INTRODUCED_58_").unwrap();
+    assert_eq!(last.exprs[3], Expr::VarParIdentifier(Symbol::Var(defined)));
+    assert_eq!(last.annos[0].id, Symbol::Name("defines_var".to_string()));
+    assert_eq!(
+        last.annos[0].expressions[0],
+        AnnExpr::Expr(Expr::VarParIdentifier(Symbol::Var(defined)))
+    );
+    assert!(matches!(
+        &interned.vars[defined.index()],
+        VarDeclItem::Bool { id, .. } if *id == Symbol::Var(defined)
+    ));
+
+    let Goal::OptimizeBool(_, objective) = &interned.solve.goal else {
+        panic!("expected an optimization goal");
+    };
+    assert_eq!(
+        *objective,
+        crate::expressions::BoolExpr::VarParIdentifier(model.symbol("X_INTRODUCED_24_"))
+    );
+    let search = &interned.solve.annotations[0].expressions;
+    assert_eq!(
+        search[1],
+        AnnExpr::Expr(Expr::VarParIdentifier(Symbol::Name(
+            "input_order".to_string()
+        )))
+    );
+    for symbol in [Symbol::Var(defined), Symbol::Par(coeffs)] {
+        assert_eq!(model.symbol(model.name_of(&symbol)), symbol);
+    }
+}
@@ -0,0 +1,1404 @@
+//! Conversion between the AST and the FlatZinc JSON format (`.fzn.json`).
+//!
+//! The JSON document has the sections `variables`, `arrays`, `constraints`,
+//! `output` and `solve`. Literals are JSON booleans and numbers, identifiers
+//! are JSON strings, sets are written as `{"set": [[lb, ub], ...]}`, string
+//! literals in annotations as `{"string": "..."}` and array accesses in
+//! annotations as `{"array": "x", "index": 1}`.
+//!
+//! JSON sets consist of ranges only, so a range with equal bounds is read as
+//! a set with a single element, like `{1}` rather than `1..1`. Sets of
+//! several ranges are expanded into their elements. Predicate declarations
+//! and comments have no JSON representation and are skipped.
+//!
+//! # Extensions
+//!
+//! The writer adds fields that are not part of the schema, so that any
+//! statement list converts without loss. MiniZinc neither emits nor reads
+//! them, and they are optional when reading:
+//!
+//! - `"par": true` marks a parameter, since MiniZinc inlines scalar
+//!   parameters instead of declaring them
+//! - `"type"` and `"domain"` on the entries of `arrays` give the element type
+//!   and domain, which MiniZinc drops
+//!
+//! Just like the text parser, reading yields [`Expr::ArrayOfBool`] for arrays
+//! of identifiers and [`Goal::OptimizeBool`] for objective identifiers; use
+//! [`Model::resolve`] to type them.
+
+use std::fmt;
+
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    constraints::ConstraintItem,
+    expressions::{
+        AnnExpr, Annotation, Annotations, ArrayOfBoolExpr, ArrayOfFloatExpr, ArrayOfIntExpr,
+        ArrayOfSetExpr, BoolExpr, Expr, FloatExpr, IntExpr, SetExpr, SetLiteral, SetLiteralExpr,
+    },
+    model::{Model, ModelError},
+    parameters::declarations::ParDeclItem,
+    primitive_literals::IndexSet,
+    solve_items::{Goal, OptimizationType, SolveItem},
+    statements::Stmt,
+    variables::declarations::VarDeclItem,
+};
+
+/// An error produced while converting from or to FlatZinc JSON.
+#[derive(PartialEq, Clone, Debug)]
+pub enum JsonError {
+    /// The JSON document does not follow the FlatZinc JSON schema
+    Schema(String),
+    /// A part of the AST that cannot be expressed in JSON, or a JSON set
+    /// that is too large to be expanded into the AST
+    Unrepresentable(String),
+    /// The converted statements do not form a valid model
+    Model(ModelError),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Schema(msg) => write!(f, "invalid FlatZinc JSON: {}", msg),
+            JsonError::Unrepresentable(msg) => write!(f, "cannot convert to JSON: {}", msg),
+            JsonError::Model(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl From<ModelError> for JsonError {
+    fn from(e: ModelError) -> Self {
+        JsonError::Model(e)
+    }
+}
+
+fn schema<T>(msg: impl Into<String>) -> Result<T, JsonError> {
+    Err(JsonError::Schema(msg.into()))
+}
+
+fn unrepresentable<T>(msg: impl Into<String>) -> Result<T, JsonError> {
+    Err(JsonError::Unrepresentable(msg.into()))
+}
+
+/// Convert statements to a FlatZinc JSON document, using the
+/// [extensions](self#extensions) for parameters and arrays.
+pub fn to_json(stmts: &[Stmt]) -> Result<Value, JsonError> {
+    let mut writer = Writer::default();
+    for stmt in stmts {
+        match stmt {
+            Stmt::Comment(_) | Stmt::Predicate(_) => {}
+            Stmt::Parameter(item) => writer.par(item)?,
+            Stmt::Variable(item) => writer.var(item)?,
+            Stmt::Constraint(item) => writer.constraint(item)?,
+            Stmt::SolveItem(item) => writer.solve(item)?,
+        }
+    }
+    Ok(writer.finish())
+}
+
+/// Read the statements of a FlatZinc JSON document.
+///
+/// Statements are ordered like [`Model::into_stmts`]: parameters, variables,
+/// constraints and the solve item. Declarations from `variables` precede
+/// those from `arrays` of the same kind.
+pub fn from_json(value: &Value) -> Result<Vec<Stmt>, JsonError> {
+    Reader::default().read(value)
+}
+
+impl Model {
+    /// Convert the model to a FlatZinc JSON document.
+    pub fn to_json(&self) -> Result<Value, JsonError> {
+        let mut writer = Writer::default();
+        for item in &self.pars {
+            writer.par(item)?;
+        }
+        for item in &self.vars {
+            writer.var(item)?;
+        }
+        for item in &self.constraints {
+            writer.constraint(item)?;
+        }
+        writer.solve(&self.solve)?;
+        Ok(writer.finish())
+    }
+
+    /// Build a model from a FlatZinc JSON document.
+    pub fn from_json(value: &Value) -> Result<Model, JsonError> {
+        Ok(Model::from_stmts(from_json(value)?)?)
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    variables: Map<String, Value>,
+    arrays: Map<String, Value>,
+    constraints: Vec<Value>,
+    output: Vec<Value>,
+    solve: Option<Value>,
+}
+
+impl Writer {
+    fn finish(self) -> Value {
+        let mut doc = Map::new();
+        doc.insert("variables".to_string(), Value::Object(self.variables));
+        doc.insert("arrays".to_string(), Value::Object(self.arrays));
+        doc.insert("constraints".to_string(), Value::Array(self.constraints));
+        doc.insert("output".to_string(), Value::Array(self.output));
+        if let Some(solve) = self.solve {
+            doc.insert("solve".to_string(), solve);
+        }
+        doc.insert("version".to_string(), Value::from("1.0"));
+        Value::Object(doc)
+    }
+
+    fn par(&mut self, item: &ParDeclItem) -> Result<(), JsonError> {
+        let mut entry = Map::new();
+        let (ty, value, array) = match item {
+            ParDeclItem::Bool { bool, .. } => ("bool", Value::Bool(*bool), None),
+            ParDeclItem::Int { int, .. } => ("int", int_json(*int)?, None),
+            ParDeclItem::Float { float, .. } => ("float", float_json(*float)?, None),
+            ParDeclItem::SetOfInt { set_literal, .. } => {
+                ("set of int", set_literal_json(set_literal)?, None)
+            }
+            ParDeclItem::ArrayOfBool { ix, v, .. } => {
+                let v = v.iter().map(|b| Value::Bool(*b)).collect();
+                ("bool", Value::Array(v), Some(ix))
+            }
+            ParDeclItem::ArrayOfInt { ix, v, .. } => {
+                let v = v.iter().map(|i| int_json(*i)).collect::<Result<_, _>>()?;
+                ("int", Value::Array(v), Some(ix))
+            }
+            ParDeclItem::ArrayOfFloat { ix, v, .. } => {
+                let v = v.iter().map(|f| float_json(*f)).collect::<Result<_, _>>()?;
+                ("float", Value::Array(v), Some(ix))
+            }
+            ParDeclItem::ArrayOfSet { ix, v, .. } => {
+                let v = v.iter().map(set_literal_json).collect::<Result<_, _>>()?;
+                ("set of int", Value::Array(v), Some(ix))
+            }
+        };
+        entry.insert("type".to_string(), Value::from(ty));
+        entry.insert("par".to_string(), Value::Bool(true));
+        if let Some(ix) = array {
+            insert_array(&mut entry, ix, Some(value))?;
+            self.arrays
+                .insert(item.id().to_string(), Value::Object(entry));
+        } else {
+            entry.insert("rhs".to_string(), value);
+            self.variables
+                .insert(item.id().to_string(), Value::Object(entry));
+        }
+        Ok(())
+    }
+
+    fn var(&mut self, item: &VarDeclItem) -> Result<(), JsonError> {
+        let mut entry = Map::new();
+        let (ty, domain) = var_type_json(item)?;
+        entry.insert("type".to_string(), Value::from(ty));
+        if let Some(domain) = domain {
+            entry.insert("domain".to_string(), domain);
+        }
+        let array = match item {
+            VarDeclItem::Bool { expr, .. } => {
+                insert_rhs(&mut entry, expr.as_ref().map(bool_expr_json))?;
+                None
+            }
+            VarDeclItem::Int { expr, .. }
+            | VarDeclItem::IntInRange { expr, .. }
+            | VarDeclItem::IntInSet { expr, .. } => {
+                insert_rhs(&mut entry, expr.as_ref().map(int_expr_json))?;
+                None
+            }
+            VarDeclItem::Float { expr, .. }
+            | VarDeclItem::BoundedFloat { expr, .. }
+            | VarDeclItem::FloatInSet { expr, .. } => {
+                insert_rhs(&mut entry, expr.as_ref().map(float_expr_json))?;
+                None
+            }
+            VarDeclItem::SetOfInt { expr, .. }
+            | VarDeclItem::SubSetOfIntSet { expr, .. }
+            | VarDeclItem::SubSetOfIntRange { expr, .. } => {
+                insert_rhs(&mut entry, expr.as_ref().map(set_expr_json))?;
+                None
+            }
+            VarDeclItem::ArrayOfBool { ix, array_expr, .. } => {
+                let a = match array_expr {
+                    Some(ArrayOfBoolExpr::Array(v)) => Some(array_json(v, bool_expr_json)?),
+                    Some(ArrayOfBoolExpr::VarParIdentifier(id)) => Some(Value::from(id.as_str())),
+                    None => None,
+                };
+                Some((ix, a))
+            }
+            VarDeclItem::ArrayOfInt { ix, array_expr, .. }
+            | VarDeclItem::ArrayOfIntInRange { ix, array_expr, .. }
+            | VarDeclItem::ArrayOfIntInSet { ix, array_expr, .. } => {
+                let a = match array_expr {
+                    Some(ArrayOfIntExpr::Array(v)) => Some(array_json(v, int_expr_json)?),
+                    Some(ArrayOfIntExpr::VarParIdentifier(id)) => Some(Value::from(id.as_str())),
+                    None => None,
+                };
+                Some((ix, a))
+            }
+            VarDeclItem::ArrayOfFloat { ix, array_expr, .. }
+            | VarDeclItem::ArrayOfBoundedFloat { ix, array_expr, .. }
+            | VarDeclItem::ArrayOfFloatInSet { ix, array_expr, .. } => {
+                let a = match array_expr {
+                    Some(ArrayOfFloatExpr::Array(v)) => Some(array_json(v, float_expr_json)?),
+                    Some(ArrayOfFloatExpr::VarParIdentifier(id)) => Some(Value::from(id.as_str())),
+                    None => None,
+                };
+                Some((ix, a))
+            }
+            VarDeclItem::ArrayOfSet { ix, array_expr, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntRange { ix, array_expr, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntSet { ix, array_expr, .. } => {
+                let a = match array_expr {
+                    Some(ArrayOfSetExpr::Array(v)) => Some(array_json(v, set_expr_json)?),
+                    Some(ArrayOfSetExpr::VarParIdentifier(id)) => Some(Value::from(id.as_str())),
+                    None => None,
+                };
+                Some((ix, a))
+            }
+        };
+        let annos = item.annos();
+        if has_annotation(annos, "var_is_introduced") {
+            entry.insert("introduced".to_string(), Value::Bool(true));
+        }
+        if has_annotation(annos, "is_defined_var") {
+            entry.insert("defined".to_string(), Value::Bool(true));
+        }
+        if !annos.is_empty() {
+            entry.insert("ann".to_string(), annotations_json(annos)?);
+        }
+        if has_annotation(annos, "output_var") || has_annotation(annos, "output_array") {
+            self.output.push(Value::from(item.id()));
+        }
+        if let Some((ix, a)) = array {
+            insert_array(&mut entry, ix, a)?;
+            self.arrays
+                .insert(item.id().to_string(), Value::Object(entry));
+        } else {
+            self.variables
+                .insert(item.id().to_string(), Value::Object(entry));
+        }
+        Ok(())
+    }
+
+    fn constraint(&mut self, item: &ConstraintItem) -> Result<(), JsonError> {
+        let mut entry = Map::new();
+        entry.insert("id".to_string(), Value::from(item.id.as_str()));
+        entry.insert("args".to_string(), array_json(&item.exprs, expr_json)?);
+        let defines = item
+            .annos
+            .iter()
+            .find_map(|anno| match &anno.expressions[..] {
+                [AnnExpr::Expr(Expr::VarParIdentifier(id))] if anno.id == "defines_var" => Some(id),
+                _ => None,
+            });
+        if let Some(id) = defines {
+            entry.insert("defines".to_string(), Value::from(id.as_str()));
+        }
+        if !item.annos.is_empty() {
+            entry.insert("ann".to_string(), annotations_json(&item.annos)?);
+        }
+        self.constraints.push(Value::Object(entry));
+        Ok(())
+    }
+
+    fn solve(&mut self, item: &SolveItem) -> Result<(), JsonError> {
+        let mut entry = Map::new();
+        let (method, objective) = match &item.goal {
+            Goal::Satisfy => ("satisfy", None),
+            Goal::OptimizeBool(opt_type, e) => (opt_type_json(opt_type), Some(bool_expr_json(e)?)),
+            Goal::OptimizeInt(opt_type, e) => (opt_type_json(opt_type), Some(int_expr_json(e)?)),
+            Goal::OptimizeFloat(opt_type, e) => {
+                (opt_type_json(opt_type), Some(float_expr_json(e)?))
+            }
+            Goal::OptimizeSet(opt_type, e) => (opt_type_json(opt_type), Some(set_expr_json(e)?)),
+        };
+        entry.insert("method".to_string(), Value::from(method));
+        if let Some(objective) = objective {
+            entry.insert("objective".to_string(), objective);
+        }
+        if !item.annotations.is_empty() {
+            entry.insert("ann".to_string(), annotations_json(&item.annotations)?);
+        }
+        self.solve = Some(Value::Object(entry));
+        Ok(())
+    }
+}
+
+fn opt_type_json(opt_type: &OptimizationType) -> &'static str {
+    match opt_type {
+        OptimizationType::Minimize => "minimize",
+        OptimizationType::Maximize => "maximize",
+    }
+}
+
+fn insert_rhs(
+    entry: &mut Map<String, Value>,
+    rhs: Option<Result<Value, JsonError>>,
+) -> Result<(), JsonError> {
+    if let Some(rhs) = rhs {
+        entry.insert("rhs".to_string(), rhs?);
+    }
+    Ok(())
+}
+
+/// Insert the elements of an array declaration, and its size if the
+/// elements do not determine it.
+fn insert_array(
+    entry: &mut Map<String, Value>,
+    ix: &IndexSet,
+    a: Option<Value>,
+) -> Result<(), JsonError> {
+    let len = match &a {
+        Some(Value::Array(v)) => Some(v.len() as i128),
+        _ => None,
+    };
+    if let Some(a) = a {
+        entry.insert("a".to_string(), a);
+    }
+    if len != Some(ix.0) {
+        entry.insert("size".to_string(), int_json(ix.0)?);
+    }
+    Ok(())
+}
+
+/// The JSON type name and domain of a variable declaration.
+fn var_type_json(item: &VarDeclItem) -> Result<(&'static str, Option<Value>), JsonError> {
+    Ok(match item {
+        VarDeclItem::Bool { .. } | VarDeclItem::ArrayOfBool { .. } => ("bool", None),
+        VarDeclItem::Int { .. } | VarDeclItem::ArrayOfInt { .. } => ("int", None),
+        VarDeclItem::IntInRange { lb, ub, .. } | VarDeclItem::ArrayOfIntInRange { lb, ub, .. } => {
+            ("int", Some(int_ranges_json(&[(*lb, *ub)])?))
+        }
+        VarDeclItem::IntInSet { set, .. } | VarDeclItem::ArrayOfIntInSet { set, .. } => {
+            ("int", Some(int_set_json(set)?))
+        }
+        VarDeclItem::Float { .. } | VarDeclItem::ArrayOfFloat { .. } => ("float", None),
+        VarDeclItem::BoundedFloat { lb, ub, .. }
+        | VarDeclItem::ArrayOfBoundedFloat { lb, ub, .. } => (
+            "float",
+            Some(Value::Array(vec![Value::Array(vec![
+                float_json(*lb)?,
+                float_json(*ub)?,
+            ])])),
+        ),
+        VarDeclItem::FloatInSet { set, .. } | VarDeclItem::ArrayOfFloatInSet { set, .. } => {
+            ("float", Some(float_set_json(set)?))
+        }
+        VarDeclItem::SetOfInt { .. } | VarDeclItem::ArrayOfSet { .. } => ("set of int", None),
+        VarDeclItem::SubSetOfIntRange { lb, ub, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntRange { lb, ub, .. } => {
+            ("set of int", Some(int_ranges_json(&[(*lb, *ub)])?))
+        }
+        VarDeclItem::SubSetOfIntSet { set, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntSet { set, .. } => {
+            ("set of int", Some(int_set_json(set)?))
+        }
+    })
+}
+
+fn has_annotation(annos: &Annotations, id: &str) -> bool {
+    annos.iter().any(|anno| anno.id == id)
+}
+
+fn int_json(int: i128) -> Result<Value, JsonError> {
+    match i64::try_from(int) {
+        Ok(int) => Ok(Value::from(int)),
+        Err(_) => unrepresentable(format!("integer {} does not fit into 64 bits", int)),
+    }
+}
+
+fn float_json(float: f64) -> Result<Value, JsonError> {
+    match Number::from_f64(float) {
+        Some(n) => Ok(Value::Number(n)),
+        None => unrepresentable(format!("float {} is not finite", float)),
+    }
+}
+
+fn array_json<T>(v: &[T], f: impl Fn(&T) -> Result<Value, JsonError>) -> Result<Value, JsonError> {
+    Ok(Value::Array(v.iter().map(f).collect::<Result<_, _>>()?))
+}
+
+fn int_ranges_json(ranges: &[(i128, i128)]) -> Result<Value, JsonError> {
+    let ranges = ranges
+        .iter()
+        .map(|(lb, ub)| Ok(Value::Array(vec![int_json(*lb)?, int_json(*ub)?])))
+        .collect::<Result<_, JsonError>>()?;
+    Ok(Value::Array(ranges))
+}
+
+fn int_set_json(set: &[i128]) -> Result<Value, JsonError> {
+    let ranges: Vec<_> = set.iter().map(|i| (*i, *i)).collect();
+    int_ranges_json(&ranges)
+}
+
+fn float_set_json(set: &[f64]) -> Result<Value, JsonError> {
+    let ranges = set
+        .iter()
+        .map(|f| Ok(Value::Array(vec![float_json(*f)?, float_json(*f)?])))
+        .collect::<Result<_, JsonError>>()?;
+    Ok(Value::Array(ranges))
+}
+
+fn set_json(ranges: Value) -> Value {
+    let mut set = Map::new();
+    set.insert("set".to_string(), ranges);
+    Value::Object(set)
+}
+
+fn set_literal_json(set: &SetLiteral) -> Result<Value, JsonError> {
+    let ranges = match set {
+        SetLiteral::IntRange(lb, ub) => int_ranges_json(&[(*lb, *ub)])?,
+        SetLiteral::SetInts(v) => int_set_json(v)?,
+        SetLiteral::BoundedFloat(lb, ub) => {
+            Value::Array(vec![Value::Array(vec![float_json(*lb)?, float_json(*ub)?])])
+        }
+        SetLiteral::SetFloats(v) => float_set_json(v)?,
+    };
+    Ok(set_json(ranges))
+}
+
+fn set_literal_expr_json(set: &SetLiteralExpr) -> Result<Value, JsonError> {
+    let int = |e: &IntExpr| match e {
+        IntExpr::Int(i) => Ok(*i),
+        IntExpr::VarParIdentifier(id) => {
+            unrepresentable(format!("identifier `{}` in a set literal", id))
+        }
+    };
+    let float = |e: &FloatExpr| match e {
+        FloatExpr::Float(f) => Ok(*f),
+        FloatExpr::VarParIdentifier(id) => {
+            unrepresentable(format!("identifier `{}` in a set literal", id))
+        }
+    };
+    let literal = match set {
+        SetLiteralExpr::IntInRange(lb, ub) => SetLiteral::IntRange(int(lb)?, int(ub)?),
+        SetLiteralExpr::SetInts(v) => {
+            SetLiteral::SetInts(v.iter().map(int).collect::<Result<_, _>>()?)
+        }
+        SetLiteralExpr::BoundedFloat(lb, ub) => SetLiteral::BoundedFloat(float(lb)?, float(ub)?),
+        SetLiteralExpr::SetFloats(v) => {
+            SetLiteral::SetFloats(v.iter().map(float).collect::<Result<_, _>>()?)
+        }
+    };
+    set_literal_json(&literal)
+}
+
+fn bool_expr_json(e: &BoolExpr) -> Result<Value, JsonError> {
+    Ok(match e {
+        BoolExpr::Bool(b) => Value::Bool(*b),
+        BoolExpr::VarParIdentifier(id) => Value::from(id.as_str()),
+    })
+}
+
+fn int_expr_json(e: &IntExpr) -> Result<Value, JsonError> {
+    match e {
+        IntExpr::Int(i) => int_json(*i),
+        IntExpr::VarParIdentifier(id) => Ok(Value::from(id.as_str())),
+    }
+}
+
+fn float_expr_json(e: &FloatExpr) -> Result<Value, JsonError> {
+    match e {
+        FloatExpr::Float(f) => float_json(*f),
+        FloatExpr::VarParIdentifier(id) => Ok(Value::from(id.as_str())),
+    }
+}
+
+fn set_expr_json(e: &SetExpr) -> Result<Value, JsonError> {
+    match e {
+        SetExpr::Set(set) => set_literal_expr_json(set),
+        SetExpr::VarParIdentifier(id) => Ok(Value::from(id.as_str())),
+    }
+}
+
+fn expr_json(e: &Expr) -> Result<Value, JsonError> {
+    match e {
+        Expr::VarParIdentifier(id) => Ok(Value::from(id.as_str())),
+        Expr::Bool(b) => Ok(Value::Bool(*b)),
+        Expr::Int(i) => int_json(*i),
+        Expr::Float(f) => float_json(*f),
+        Expr::Set(set) => set_literal_expr_json(set),
+        Expr::ArrayOfBool(v) => array_json(v, bool_expr_json),
+        Expr::ArrayOfInt(v) => array_json(v, int_expr_json),
+        Expr::ArrayOfFloat(v) => array_json(v, float_expr_json),
+        Expr::ArrayOfSet(v) => array_json(v, set_expr_json),
+    }
+}
+
+fn annotations_json(annos: &[Annotation]) -> Result<Value, JsonError> {
+    array_json(annos, annotation_json)
+}
+
+fn annotation_json(anno: &Annotation) -> Result<Value, JsonError> {
+    if anno.expressions.is_empty() {
+        return Ok(Value::from(anno.id.as_str()));
+    }
+    let mut entry = Map::new();
+    entry.insert("id".to_string(), Value::from(anno.id.as_str()));
+    entry.insert(
+        "args".to_string(),
+        array_json(&anno.expressions, ann_expr_json)?,
+    );
+    Ok(Value::Object(entry))
+}
+
+fn ann_expr_json(e: &AnnExpr) -> Result<Value, JsonError> {
+    match e {
+        AnnExpr::Annotations(annos) => annotations_json(annos),
+        AnnExpr::String(s) => {
+            let mut entry = Map::new();
+            entry.insert("string".to_string(), Value::from(s.as_str()));
+            Ok(Value::Object(entry))
+        }
+        AnnExpr::Expr(e) => expr_json(e),
+        AnnExpr::ArrayAccess(id, index) => {
+            let mut entry = Map::new();
+            entry.insert("array".to_string(), Value::from(id.as_str()));
+            entry.insert("index".to_string(), int_expr_json(index)?);
+            Ok(Value::Object(entry))
+        }
+        AnnExpr::Array(v) => array_json(v, ann_expr_json),
+    }
+}
+
+/// The element type of a declaration in the JSON document.
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum JsonType {
+    Bool,
+    Int,
+    Float,
+    SetOfInt,
+}
+
+#[derive(Default)]
+struct Reader {
+    /// Types of the scalar declarations read so far, to infer array types
+    types: std::collections::HashMap<String, JsonType>,
+    output: Vec<String>,
+}
+
+impl Reader {
+    fn read(mut self, value: &Value) -> Result<Vec<Stmt>, JsonError> {
+        let Some(doc) = value.as_object() else {
+            return schema("document is not an object");
+        };
+        if let Some(output) = doc.get("output") {
+            for id in as_array(output, "output")? {
+                self.output.push(as_str(id, "output")?.to_string());
+            }
+        }
+        let mut decls = vec![];
+        if let Some(variables) = doc.get("variables") {
+            let Some(variables) = variables.as_object() else {
+                return schema("`variables` is not an object");
+            };
+            for (id, entry) in variables {
+                decls.push(self.scalar(id, entry)?);
+            }
+        }
+        if let Some(arrays) = doc.get("arrays") {
+            let Some(arrays) = arrays.as_object() else {
+                return schema("`arrays` is not an object");
+            };
+            for (id, entry) in arrays {
+                decls.push(self.array(id, entry)?);
+            }
+        }
+        let (mut stmts, vars): (Vec<_>, Vec<_>) = decls
+            .into_iter()
+            .partition(|stmt| matches!(stmt, Stmt::Parameter(_)));
+        stmts.extend(vars);
+        if let Some(constraints) = doc.get("constraints") {
+            for entry in as_array(constraints, "constraints")? {
+                stmts.push(Stmt::Constraint(constraint(entry)?));
+            }
+        }
+        if let Some(solve) = doc.get("solve") {
+            stmts.push(Stmt::SolveItem(solve_item(solve)?));
+        }
+        Ok(stmts)
+    }
+
+    fn scalar(&mut self, id: &str, entry: &Value) -> Result<Stmt, JsonError> {
+        let entry = as_object(entry, id)?;
+        let ty = json_type(entry, id)?;
+        self.types.insert(id.to_string(), ty);
+        let rhs = entry.get("rhs");
+        let id = id.to_string();
+        if is_par(entry) {
+            let Some(rhs) = rhs else {
+                return schema(format!("parameter `{}` has no value", id));
+            };
+            let item = match ty {
+                JsonType::Bool => ParDeclItem::Bool {
+                    bool: bool_value(rhs)?,
+                    id,
+                },
+                JsonType::Int => ParDeclItem::Int {
+                    int: int_value(rhs)?,
+                    id,
+                },
+                JsonType::Float => ParDeclItem::Float {
+                    float: float_value(rhs)?,
+                    id,
+                },
+                JsonType::SetOfInt => ParDeclItem::SetOfInt {
+                    set_literal: set_literal(rhs)?,
+                    id,
+                },
+            };
+            return Ok(Stmt::Parameter(item));
+        }
+        let annos = self.var_annotations(&id, entry, false)?;
+        let domain = domain(entry)?;
+        let item = match (ty, domain) {
+            (JsonType::Bool, _) => VarDeclItem::Bool {
+                expr: rhs.map(bool_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::Int, None) => VarDeclItem::Int {
+                expr: rhs.map(int_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::Int, Some(Domain::Range(lb, ub))) => VarDeclItem::IntInRange {
+                lb,
+                ub,
+                expr: rhs.map(int_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::Int, Some(Domain::Set(set))) => VarDeclItem::IntInSet {
+                set,
+                expr: rhs.map(int_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::Float, None) => VarDeclItem::Float {
+                expr: rhs.map(float_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::Float, Some(Domain::Float(lb, ub))) => VarDeclItem::BoundedFloat {
+                lb,
+                ub,
+                expr: rhs.map(float_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::Float, Some(Domain::FloatSet(set))) => VarDeclItem::FloatInSet {
+                set,
+                expr: rhs.map(float_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::SetOfInt, None) => VarDeclItem::SetOfInt {
+                expr: rhs.map(set_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::SetOfInt, Some(Domain::Range(lb, ub))) => VarDeclItem::SubSetOfIntRange {
+                lb,
+                ub,
+                expr: rhs.map(set_expr).transpose()?,
+                id,
+                annos,
+            },
+            (JsonType::SetOfInt, Some(Domain::Set(set))) => VarDeclItem::SubSetOfIntSet {
+                set,
+                expr: rhs.map(set_expr).transpose()?,
+                id,
+                annos,
+            },
+            _ => return schema(format!("invalid domain of `{}`", id)),
+        };
+        Ok(Stmt::Variable(item))
+    }
+
+    fn array(&mut self, id: &str, entry: &Value) -> Result<Stmt, JsonError> {
+        let entry = as_object(entry, id)?;
+        let a = entry.get("a");
+        let elements = match a {
+            Some(Value::Array(v)) => Some(v),
+            Some(Value::String(_)) | None => None,
+            Some(_) => return schema(format!("elements of `{}` are not an array", id)),
+        };
+        let ix = match entry.get("size") {
+            Some(size) => IndexSet(int_value(size)?),
+            None => match elements {
+                Some(v) => IndexSet(v.len() as i128),
+                None => return schema(format!("array `{}` has no elements or size", id)),
+            },
+        };
+        let ty = match entry.get("type") {
+            Some(_) => json_type(entry, id)?,
+            None => self.infer_type(elements.map_or(&[], |v| &v[..])),
+        };
+        // MiniZinc omits the kind of an array, but parameter arrays hold literals only
+        let par = is_par(entry)
+            || (!entry.contains_key("type")
+                && elements.is_some_and(|v| v.iter().all(|e| !e.is_string())));
+        let id = id.to_string();
+        if par {
+            let Some(v) = elements else {
+                return schema(format!("parameter `{}` has no value", id));
+            };
+            let item = match ty {
+                JsonType::Bool => ParDeclItem::ArrayOfBool {
+                    ix,
+                    id,
+                    v: v.iter().map(bool_value).collect::<Result<_, _>>()?,
+                },
+                JsonType::Int => ParDeclItem::ArrayOfInt {
+                    ix,
+                    id,
+                    v: v.iter().map(int_value).collect::<Result<_, _>>()?,
+                },
+                JsonType::Float => ParDeclItem::ArrayOfFloat {
+                    ix,
+                    id,
+                    v: v.iter().map(float_value).collect::<Result<_, _>>()?,
+                },
+                JsonType::SetOfInt => ParDeclItem::ArrayOfSet {
+                    ix,
+                    id,
+                    v: v.iter().map(set_literal).collect::<Result<_, _>>()?,
+                },
+            };
+            return Ok(Stmt::Parameter(item));
+        }
+        let annos = self.var_annotations(&id, entry, true)?;
+        let domain = domain(entry)?;
+        let array_id = a.and_then(|a| a.as_str()).map(|s| s.to_string());
+        let item = match ty {
+            JsonType::Bool => {
+                let array_expr = match (array_id, elements) {
+                    (Some(id), _) => Some(ArrayOfBoolExpr::VarParIdentifier(id)),
+                    (None, Some(v)) => Some(ArrayOfBoolExpr::Array(
+                        v.iter().map(bool_expr).collect::<Result<_, _>>()?,
+                    )),
+                    (None, None) => None,
+                };
+                VarDeclItem::ArrayOfBool {
+                    ix,
+                    id,
+                    annos,
+                    array_expr,
+                }
+            }
+            JsonType::Int => {
+                let array_expr = match (array_id, elements) {
+                    (Some(id), _) => Some(ArrayOfIntExpr::VarParIdentifier(id)),
+                    (None, Some(v)) => Some(ArrayOfIntExpr::Array(
+                        v.iter().map(int_expr).collect::<Result<_, _>>()?,
+                    )),
+                    (None, None) => None,
+                };
+                match domain {
+                    None => VarDeclItem::ArrayOfInt {
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::Range(lb, ub)) => VarDeclItem::ArrayOfIntInRange {
+                        lb,
+                        ub,
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::Set(set)) => VarDeclItem::ArrayOfIntInSet {
+                        set,
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::Float(..) | Domain::FloatSet(_)) => {
+                        return schema(format!("invalid domain of `{}`", id))
+                    }
+                }
+            }
+            JsonType::Float => {
+                let array_expr = match (array_id, elements) {
+                    (Some(id), _) => Some(ArrayOfFloatExpr::VarParIdentifier(id)),
+                    (None, Some(v)) => Some(ArrayOfFloatExpr::Array(
+                        v.iter().map(float_expr).collect::<Result<_, _>>()?,
+                    )),
+                    (None, None) => None,
+                };
+                match domain {
+                    None => VarDeclItem::ArrayOfFloat {
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::Float(lb, ub)) => VarDeclItem::ArrayOfBoundedFloat {
+                        lb,
+                        ub,
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::FloatSet(set)) => VarDeclItem::ArrayOfFloatInSet {
+                        set,
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(_) => return schema(format!("invalid domain of `{}`", id)),
+                }
+            }
+            JsonType::SetOfInt => {
+                let array_expr = match (array_id, elements) {
+                    (Some(id), _) => Some(ArrayOfSetExpr::VarParIdentifier(id)),
+                    (None, Some(v)) => Some(ArrayOfSetExpr::Array(
+                        v.iter().map(set_expr).collect::<Result<_, _>>()?,
+                    )),
+                    (None, None) => None,
+                };
+                match domain {
+                    None => VarDeclItem::ArrayOfSet {
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::Range(lb, ub)) => VarDeclItem::ArrayOfSubSetOfIntRange {
+                        lb,
+                        ub,
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::Set(set)) => VarDeclItem::ArrayOfSubSetOfIntSet {
+                        set,
+                        ix,
+                        id,
+                        annos,
+                        array_expr,
+                    },
+                    Some(Domain::Float(..) | Domain::FloatSet(_)) => {
+                        return schema(format!("invalid domain of `{}`", id))
+                    }
+                }
+            }
+        };
+        Ok(Stmt::Variable(item))
+    }
+
+    /// Infer the element type of an array without a `type` field.
+    fn infer_type(&self, elements: &[Value]) -> JsonType {
+        for e in elements {
+            match e {
+                Value::Bool(_) => return JsonType::Bool,
+                Value::Number(n) if n.is_f64() => return JsonType::Float,
+                Value::Number(_) => return JsonType::Int,
+                Value::Object(_) => return JsonType::SetOfInt,
+                Value::String(id) => {
+                    if let Some(ty) = self.types.get(id) {
+                        return *ty;
+                    }
+                }
+                _ => {}
+            }
+        }
+        JsonType::Int
+    }
+
+    /// Read the annotations of a variable, restoring those that MiniZinc
+    /// expresses as fields of the declaration.
+    fn var_annotations(
+        &self,
+        id: &str,
+        entry: &Map<String, Value>,
+        array: bool,
+    ) -> Result<Annotations, JsonError> {
+        let mut annos = match entry.get("ann") {
+            Some(ann) => annotations(ann)?,
+            None => vec![],
+        };
+        let flag = |key: &str| entry.get(key).and_then(|v| v.as_bool()) == Some(true);
+        if flag("introduced") && !has_annotation(&annos, "var_is_introduced") {
+            annos.push(atom("var_is_introduced"));
+        }
+        if flag("defined") && !has_annotation(&annos, "is_defined_var") {
+            annos.push(atom("is_defined_var"));
+        }
+        if !array && self.output.iter().any(|o| o == id) && !has_annotation(&annos, "output_var") {
+            annos.push(atom("output_var"));
+        }
+        Ok(annos)
+    }
+}
+
+fn atom(id: &str) -> Annotation {
+    Annotation {
+        id: id.to_string(),
+        expressions: vec![],
+    }
+}
+
+fn is_par(entry: &Map<String, Value>) -> bool {
+    entry.get("par").and_then(|v| v.as_bool()) == Some(true)
+}
+
+fn json_type(entry: &Map<String, Value>, id: &str) -> Result<JsonType, JsonError> {
+    match entry.get("type").and_then(|t| t.as_str()) {
+        Some("bool") => Ok(JsonType::Bool),
+        Some("int") => Ok(JsonType::Int),
+        Some("float") => Ok(JsonType::Float),
+        Some("set of int") => Ok(JsonType::SetOfInt),
+        _ => schema(format!("missing or unknown type of `{}`", id)),
+    }
+}
+
+enum Domain {
+    Range(i128, i128),
+    Set(Vec<i128>),
+    Float(f64, f64),
+    FloatSet(Vec<f64>),
+}
+
+fn domain(entry: &Map<String, Value>) -> Result<Option<Domain>, JsonError> {
+    let Some(domain) = entry.get("domain") else {
+        return Ok(None);
+    };
+    Ok(Some(
+        match set_literal_ranges(as_array(domain, "domain")?)? {
+            SetLiteral::IntRange(lb, ub) => Domain::Range(lb, ub),
+            SetLiteral::SetInts(set) => Domain::Set(set),
+            SetLiteral::BoundedFloat(lb, ub) => Domain::Float(lb, ub),
+            SetLiteral::SetFloats(set) => Domain::FloatSet(set),
+        },
+    ))
+}
+
+fn range_bounds(range: &Value) -> Result<[&Value; 2], JsonError> {
+    match as_array(range, "range")?.as_slice() {
+        [lb, ub] => Ok([lb, ub]),
+        _ => schema("a range must have a lower and an upper bound"),
+    }
+}
+
+/// Read ranges with float bounds as a list of floats, or `None` if all bounds are integers.
+fn float_ranges(ranges: &[Value]) -> Result<Option<Vec<f64>>, JsonError> {
+    let is_float = ranges
+        .iter()
+        .any(|r| r.as_array().is_some_and(|r| r.iter().any(|b| b.is_f64())));
+    if !is_float {
+        return Ok(None);
+    }
+    let mut v = vec![];
+    for range in ranges {
+        let [lb, ub] = range_bounds(range)?;
+        let (lb, ub) = (float_value(lb)?, float_value(ub)?);
+        if lb != ub {
+            return schema("float sets can only be a single range or a list of floats");
+        }
+        v.push(lb);
+    }
+    Ok(Some(v))
+}
+
+/// The number of elements up to which sets of several ranges are expanded.
+const MAX_SET_ELEMENTS: i128 = 1 << 20;
+
+/// Expand integer ranges into the list of their elements.
+fn int_ranges(ranges: &[Value]) -> Result<Vec<i128>, JsonError> {
+    let mut bounds = vec![];
+    let mut len: i128 = 0;
+    for range in ranges {
+        let [lb, ub] = range_bounds(range)?;
+        let (lb, ub) = (int_value(lb)?, int_value(ub)?);
+        if lb <= ub {
+            len += ub - lb + 1;
+            if len > MAX_SET_ELEMENTS {
+                return unrepresentable(format!(
+                    "set with more than {} elements",
+                    MAX_SET_ELEMENTS
+                ));
+            }
+            bounds.push((lb, ub));
+        }
+    }
+    Ok(bounds.into_iter().flat_map(|(lb, ub)| lb..=ub).collect())
+}
+
+fn set_literal(value: &Value) -> Result<SetLiteral, JsonError> {
+    let Some(ranges) = value.as_object().and_then(|set| set.get("set")) else {
+        return schema(format!("expected a set, found `{}`", value));
+    };
+    set_literal_ranges(as_array(ranges, "set")?)
+}
+
+/// Read the ranges of a set or a domain. A single range is kept as a range
+/// unless its bounds are equal.
+fn set_literal_ranges(ranges: &[Value]) -> Result<SetLiteral, JsonError> {
+    if let [range] = ranges {
+        let [lb, ub] = range_bounds(range)?;
+        if lb.is_f64() || ub.is_f64() {
+            let (lb, ub) = (float_value(lb)?, float_value(ub)?);
+            if lb == ub {
+                return Ok(SetLiteral::SetFloats(vec![lb]));
+            }
+            return Ok(SetLiteral::BoundedFloat(lb, ub));
+        }
+        let (lb, ub) = (int_value(lb)?, int_value(ub)?);
+        if lb == ub {
+            return Ok(SetLiteral::SetInts(vec![lb]));
+        }
+        return Ok(SetLiteral::IntRange(lb, ub));
+    }
+    if let Some(v) = float_ranges(ranges)? {
+        return Ok(SetLiteral::SetFloats(v));
+    }
+    Ok(SetLiteral::SetInts(int_ranges(ranges)?))
+}
+
+fn set_literal_expr(value: &Value) -> Result<SetLiteralExpr, JsonError> {
+    Ok(match set_literal(value)? {
+        SetLiteral::IntRange(lb, ub) => {
+            SetLiteralExpr::IntInRange(IntExpr::Int(lb), IntExpr::Int(ub))
+        }
+        SetLiteral::BoundedFloat(lb, ub) => {
+            SetLiteralExpr::BoundedFloat(FloatExpr::Float(lb), FloatExpr::Float(ub))
+        }
+        SetLiteral::SetInts(v) => {
+            SetLiteralExpr::SetInts(v.into_iter().map(IntExpr::Int).collect())
+        }
+        SetLiteral::SetFloats(v) => {
+            SetLiteralExpr::SetFloats(v.into_iter().map(FloatExpr::Float).collect())
+        }
+    })
+}
+
+fn as_object<'a>(value: &'a Value, what: &str) -> Result<&'a Map<String, Value>, JsonError> {
+    match value.as_object() {
+        Some(object) => Ok(object),
+        None => schema(format!("`{}` is not an object", what)),
+    }
+}
+
+fn as_array<'a>(value: &'a Value, what: &str) -> Result<&'a Vec<Value>, JsonError> {
+    match value.as_array() {
+        Some(array) => Ok(array),
+        None => schema(format!("`{}` is not an array", what)),
+    }
+}
+
+fn as_str<'a>(value: &'a Value, what: &str) -> Result<&'a str, JsonError> {
+    match value.as_str() {
+        Some(s) => Ok(s),
+        None => schema(format!("`{}` is not a string", what)),
+    }
+}
+
+fn bool_value(value: &Value) -> Result<bool, JsonError> {
+    match value.as_bool() {
+        Some(b) => Ok(b),
+        None => schema(format!("expected a bool, found `{}`", value)),
+    }
+}
+
+fn int_value(value: &Value) -> Result<i128, JsonError> {
+    match value
+        .as_i64()
+        .map(i128::from)
+        .or(value.as_u64().map(i128::from))
+    {
+        Some(i) => Ok(i),
+        None => schema(format!("expected an integer, found `{}`", value)),
+    }
+}
+
+fn float_value(value: &Value) -> Result<f64, JsonError> {
+    match value.as_f64() {
+        Some(f) => Ok(f),
+        None => schema(format!("expected a float, found `{}`", value)),
+    }
+}
+
+fn bool_expr(value: &Value) -> Result<BoolExpr, JsonError> {
+    match value {
+        Value::String(id) => Ok(BoolExpr::VarParIdentifier(id.clone())),
+        _ => Ok(BoolExpr::Bool(bool_value(value)?)),
+    }
+}
+
+fn int_expr(value: &Value) -> Result<IntExpr, JsonError> {
+    match value {
+        Value::String(id) => Ok(IntExpr::VarParIdentifier(id.clone())),
+        _ => Ok(IntExpr::Int(int_value(value)?)),
+    }
+}
+
+fn float_expr(value: &Value) -> Result<FloatExpr, JsonError> {
+    match value {
+        Value::String(id) => Ok(FloatExpr::VarParIdentifier(id.clone())),
+        _ => Ok(FloatExpr::Float(float_value(value)?)),
+    }
+}
+
+fn set_expr(value: &Value) -> Result<SetExpr, JsonError> {
+    match value {
+        Value::String(id) => Ok(SetExpr::VarParIdentifier(id.clone())),
+        _ => Ok(SetExpr::Set(set_literal_expr(value)?)),
+    }
+}
+
+/// Read an expression, choosing the array kind the way the text parser does.
+fn expr(value: &Value) -> Result<Expr, JsonError> {
+    Ok(match value {
+        Value::String(id) => Expr::VarParIdentifier(id.clone()),
+        Value::Bool(b) => Expr::Bool(*b),
+        Value::Number(n) if n.is_f64() => Expr::Float(float_value(value)?),
+        Value::Number(_) => Expr::Int(int_value(value)?),
+        Value::Object(_) => Expr::Set(set_literal_expr(value)?),
+        Value::Array(v) => {
+            if v.iter().all(|e| e.is_boolean() || e.is_string()) {
+                Expr::ArrayOfBool(v.iter().map(bool_expr).collect::<Result<_, _>>()?)
+            } else if v.iter().any(|e| e.is_object()) {
+                Expr::ArrayOfSet(v.iter().map(set_expr).collect::<Result<_, _>>()?)
+            } else if v.iter().any(|e| e.is_f64()) {
+                Expr::ArrayOfFloat(v.iter().map(float_expr).collect::<Result<_, _>>()?)
+            } else {
+                Expr::ArrayOfInt(v.iter().map(int_expr).collect::<Result<_, _>>()?)
+            }
+        }
+        Value::Null => return schema("unexpected `null`"),
+    })
+}
+
+fn annotations(value: &Value) -> Result<Annotations, JsonError> {
+    as_array(value, "ann")?.iter().map(annotation).collect()
+}
+
+fn annotation(value: &Value) -> Result<Annotation, JsonError> {
+    match value {
+        Value::String(id) => Ok(atom(id)),
+        Value::Object(entry) => {
+            let id = as_str(entry.get("id").unwrap_or(&Value::Null), "id")?;
+            let expressions = match entry.get("args") {
+                Some(args) => as_array(args, "args")?
+                    .iter()
+                    .map(ann_expr)
+                    .collect::<Result<_, _>>()?,
+                None => vec![],
+            };
+            Ok(Annotation {
+                id: id.to_string(),
+                expressions,
+            })
+        }
+        _ => schema(format!("expected an annotation, found `{}`", value)),
+    }
+}
+
+fn ann_expr(value: &Value) -> Result<AnnExpr, JsonError> {
+    match value {
+        Value::Object(entry) if entry.contains_key("string") => Ok(AnnExpr::String(
+            as_str(&entry["string"], "string")?.to_string(),
+        )),
+        Value::Object(entry) if entry.contains_key("array") => Ok(AnnExpr::ArrayAccess(
+            as_str(&entry["array"], "array")?.to_string(),
+            int_expr(entry.get("index").unwrap_or(&Value::Null))?,
+        )),
+        Value::Array(v) if v.iter().any(|e| e.is_object() && e.get("id").is_some()) => {
+            Ok(AnnExpr::Annotations(annotations(value)?))
+        }
+        Value::Array(v) if v.iter().any(|e| e.is_object() && e.get("array").is_some()) => Ok(
+            AnnExpr::Array(v.iter().map(ann_expr).collect::<Result<_, _>>()?),
+        ),
+        _ => Ok(AnnExpr::Expr(expr(value)?)),
+    }
+}
+
+fn constraint(value: &Value) -> Result<ConstraintItem, JsonError> {
+    let entry = as_object(value, "constraint")?;
+    let id = as_str(entry.get("id").unwrap_or(&Value::Null), "id")?.to_string();
+    let exprs = match entry.get("args") {
+        Some(args) => as_array(args, "args")?
+            .iter()
+            .map(expr)
+            .collect::<Result<_, _>>()?,
+        None => vec![],
+    };
+    let mut annos = match entry.get("ann") {
+        Some(ann) => annotations(ann)?,
+        None => vec![],
+    };
+    if let Some(defines) = entry.get("defines") {
+        if !has_annotation(&annos, "defines_var") {
+            annos.push(Annotation {
+                id: "defines_var".to_string(),
+                expressions: vec![AnnExpr::Expr(Expr::VarParIdentifier(
+                    as_str(defines, "defines")?.to_string(),
+                ))],
+            });
+        }
+    }
+    Ok(ConstraintItem { id, exprs, annos })
+}
+
+fn solve_item(value: &Value) -> Result<SolveItem, JsonError> {
+    let entry = as_object(value, "solve")?;
+    let opt_type = match entry.get("method").and_then(|m| m.as_str()) {
+        Some("satisfy") => None,
+        Some("minimize") => Some(OptimizationType::Minimize),
+        Some("maximize") => Some(OptimizationType::Maximize),
+        _ => return schema("missing or unknown solve method"),
+    };
+    let goal = match opt_type {
+        None => Goal::Satisfy,
+        Some(opt_type) => match entry.get("objective") {
+            Some(Value::String(id)) => {
+                Goal::OptimizeBool(opt_type, BoolExpr::VarParIdentifier(id.clone()))
+            }
+            Some(objective) => match expr(objective)? {
+                Expr::Bool(b) => Goal::OptimizeBool(opt_type, BoolExpr::Bool(b)),
+                Expr::Int(i) => Goal::OptimizeInt(opt_type, IntExpr::Int(i)),
+                Expr::Float(f) => Goal::OptimizeFloat(opt_type, FloatExpr::Float(f)),
+                Expr::Set(set) => Goal::OptimizeSet(opt_type, SetExpr::Set(set)),
+                _ => return schema("objective must be an identifier or a literal"),
+            },
+            None => return schema("optimization without an objective"),
+        },
+    };
+    let annotations = match entry.get("ann") {
+        Some(ann) => annotations(ann)?,
+        None => vec![],
+    };
+    Ok(SolveItem { goal, annotations })
+}
+#[test]
+fn test_json_round_trip_jobshop() {
+    use crate::statements::parse_model;
+    let stmts = parse_model(include_str!("../jobshop.fzn")).unwrap();
+    let json = to_json(&stmts).unwrap();
+    assert_eq!(from_json(&json).unwrap(), stmts);
+    let model = Model::from_stmts(stmts).unwrap();
+    let json = model.to_json().unwrap();
+    assert_eq!(json["output"], serde_json::json!(["s"]));
+    assert_eq!(
+        json["variables"]["X_INTRODUCED_0_"],
+        serde_json::json!({"type": "int", "domain": [[0, 38]]})
+    );
+    assert_eq!(
+        json["arrays"]["X_INTRODUCED_22_"],
+        serde_json::json!({"type": "int", "par": true, "a": [1, -1]})
+    );
+    let text = serde_json::to_string(&json).unwrap();
+    let reparsed: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(Model::from_json(&reparsed).unwrap(), model);
+}
+#[test]
+fn test_json_round_trip_stmts() {
+    use crate::statements::parse_model;
+    let stmts = parse_model(
+        "bool: b = true;\nfloat: f = 1.5;\nset of int: s = {1,3};\n\
+        array [1..2] of set of int: ss = [1..3,{}];\n\
+        array [1..2] of float: fs = [1.0,2.5];\n\
+        var {1,3,5}: y :: output_var;\n\
+        var 0.0..1.5: z = 1.0;\n\
+        var {0.5,1.5}: u;\n\
+        var set of 1..3: v;\n\
+        array [1..2] of var {1.0,2.5}: us = [u,1.0];\n\
+        array [1..3] of var set of 17..42: h = [{42,17},23..30,{}];\n\
+        array [1..5] of var 0..3: w;\n\
+        constraint foo(1.0..2.0,{1.5,2.5},[1.0,z],[y,3],[true,b]) :: bar(\"str\",[baz(1),qux]);\n\
+        constraint bool_clause([b],[]) :: warm_start(w[3],2) :: warm_start_array([w[1],w[i]]);\n\
+        solve maximize y;",
+    )
+    .unwrap();
+    let json = to_json(&stmts).unwrap();
+    assert_eq!(from_json(&json).unwrap(), stmts);
+}
+#[test]
+fn test_json_minizinc() {
+    let json = serde_json::json!({
+        "variables": {
+            "x": {"type": "int", "domain": [[1, 3]]},
+            "b": {"type": "bool", "introduced": true, "defined": true}
+        },
+        "arrays": {
+            "coeffs": {"a": [1, -1]},
+            "xs": {"a": ["x", "x"]}
+        },
+        "constraints": [
+            {"id": "int_lin_le_reif", "args": ["coeffs", ["x", "x"], 0, "b"], "defines": "b"}
+        ],
+        "output": ["x"],
+        "solve": {"method": "minimize", "objective": "x"},
+        "version": "1.0"
+    });
+    let mut model = Model::from_json(&json).unwrap();
+    model.resolve().unwrap();
+    let expected: Model = "array [1..2] of int: coeffs = [1,-1];\n\
+        var 1..3: x :: output_var;\n\
+        var bool: b :: var_is_introduced :: is_defined_var;\n\
+        array [1..2] of var int: xs = [x,x];\n\
+        constraint int_lin_le_reif(coeffs,[x,x],0,b) :: defines_var(b);\n\
+        solve minimize x;"
+        .parse()
+        .unwrap();
+    let mut expected = expected;
+    expected.resolve().unwrap();
+    assert_eq!(model, expected);
+}
+#[test]
+fn test_json_singleton_sets() {
+    use crate::statements::parse_model;
+    let stmts = parse_model(
+        "set of int: s = {4};\n\
+        array [1..1] of set of int: ss = [{2}];\n\
+        var {5}: x;\n\
+        var {1.5}: y;\n\
+        var set of {3}: v;\n\
+        array [1..2] of var {2}: a;\n\
+        constraint foo({7},{2.5},x);\n\
+        solve satisfy;",
+    )
+    .unwrap();
+    let json = to_json(&stmts).unwrap();
+    assert_eq!(
+        json["variables"]["x"]["domain"],
+        serde_json::json!([[5, 5]])
+    );
+    assert_eq!(from_json(&json).unwrap(), stmts);
+}
+#[test]
+fn test_json_limits() {
+    use crate::statements::parse_model;
+    let json = serde_json::json!({
+        "variables": {"x": {"type": "int", "domain": [[1, 3], [5, 100000000000_i64]]}},
+        "solve": {"method": "satisfy"}
+    });
+    assert!(matches!(
+        from_json(&json),
+        Err(JsonError::Unrepresentable(_))
+    ));
+    let json = serde_json::json!({
+        "variables": {"x": {"type": "int", "domain": [[1, 100000000000_i64]]}},
+        "solve": {"method": "satisfy"}
+    });
+    assert!(from_json(&json).is_ok());
+    let stmts = parse_model("array [1..10000000000000000000] of var int: a;").unwrap();
+    assert!(matches!(
+        to_json(&stmts),
+        Err(JsonError::Unrepresentable(_))
+    ));
+}
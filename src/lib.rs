@@ -1,26 +1,64 @@
+pub use aliases::{AliasError, Aliases};
 pub use basic_types::BasicType;
+pub use builtins::{Builtin, BuiltinError};
+pub use calls::{CallDiagnostic, CallDiagnosticKind};
+pub use checker::{CheckError, CheckReport, Violation};
 pub use constraints::ConstraintItem;
+pub use dzn::{Assignment, DznError, DznValue, Solution, SolverOutput};
+pub use error::{ParseError, StmtKind};
 pub use expressions::{
     AnnExpr, Annotation, ArrayOfBoolExpr, ArrayOfFloatExpr, ArrayOfIntExpr, ArrayOfSetExpr,
-    BoolExpr, Expr, FloatExpr, IntExpr, SetExpr, SetLiteral, SetLiteralExpr,
+    BoolExpr, Expr, FloatExpr, IntExpr, SetExpr, SetLiteral, SetLiteralExpr, StrRole,
 };
+pub use intern::{InternedModel, ParId, Symbol, VarId};
+pub use model::{DeclType, Model, ModelError, ScalarType};
+pub use output::{OutputError, OutputKind, OutputVar};
 pub use parameters::{declarations::ParDeclItem, types::BasicParType};
 pub use predicates::{
     declarations::PredicateItem,
     types::{BasicPredParType, PredIndexSet, PredParType},
 };
 pub use primitive_literals::IndexSet;
+pub use reader::{LineStmt, ReadError, StmtReader};
+pub use resolve::ResolveError;
+pub use search::{Exploration, SearchError, SearchStrategy, ValueChoice, VarChoice, VarSearch};
+pub use solution::{SolutionError, Status, Value, SOLUTION_SEPARATOR};
 pub use solve_items::{Goal, OptimizationType, SolveItem};
-pub use statements::Stmt;
+pub use solver_output::{SolverEvent, SolverOutputError, SolverOutputReader};
+pub use spans::{parse_model_with_spans, NodeKind, Span, SpanTree, SpannedStmt};
+pub use statements::{
+    parse_model, parse_model_borrowed, parse_model_recovering, BorrowedStmt, Stmt,
+};
+pub use validate::{validate, Diagnostic, DiagnosticKind};
 pub use variables::{declarations::VarDeclItem, types::BasicVarType};
 
+pub mod aliases;
 pub mod basic_types;
+pub mod builtins;
+pub mod calls;
+pub mod checker;
 pub mod comments;
 pub mod constraints;
+pub mod dzn;
+pub mod error;
 pub mod expressions;
+pub mod inline;
+pub mod intern;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod model;
+pub mod output;
 pub mod parameters;
 pub mod predicates;
 pub mod primitive_literals;
+pub mod printer;
+pub mod reader;
+pub mod resolve;
+pub mod search;
+pub mod solution;
 pub mod solve_items;
+pub mod solver_output;
+pub mod spans;
 pub mod statements;
+pub mod validate;
 pub mod variables;
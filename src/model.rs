@@ -0,0 +1,328 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    constraints::ConstraintItem, error::ParseError, parameters::declarations::ParDeclItem,
+    predicates::declarations::PredicateItem, solve_items::SolveItem, statements::parse_model,
+    statements::Stmt, variables::declarations::VarDeclItem,
+};
+
+/// A complete FlatZinc model together with a symbol table of its declarations.
+///
+/// Declarations, constraints and predicates are kept in the order in which
+/// they appear in the model.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Model {
+    pub(crate) predicates: Vec<PredicateItem>,
+    pub(crate) pars: Vec<ParDeclItem>,
+    pub(crate) vars: Vec<VarDeclItem>,
+    pub(crate) constraints: Vec<ConstraintItem>,
+    pub(crate) solve: SolveItem,
+    pub(crate) decls: HashMap<String, Decl>,
+}
+
+/// Position of a declaration in the parameter or variable list of a [`Model`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub(crate) enum Decl {
+    Par(usize),
+    Var(usize),
+}
+
+/// The element type of a declared identifier.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ScalarType {
+    Bool,
+    Int,
+    Float,
+    SetOfInt,
+}
+
+impl fmt::Display for ScalarType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScalarType::Bool => write!(f, "bool"),
+            ScalarType::Int => write!(f, "int"),
+            ScalarType::Float => write!(f, "float"),
+            ScalarType::SetOfInt => write!(f, "set of int"),
+        }
+    }
+}
+
+/// The type of a declared identifier as recorded in the symbol table.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct DeclType {
+    pub scalar: ScalarType,
+    pub array: bool,
+    pub var: bool,
+}
+
+impl fmt::Display for DeclType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.array {
+            write!(f, "array of ")?;
+        }
+        if self.var {
+            write!(f, "var ")?;
+        }
+        write!(f, "{}", self.scalar)
+    }
+}
+
+/// An error produced while building a [`Model`].
+#[derive(PartialEq, Clone, Debug)]
+pub enum ModelError {
+    Parse(ParseError),
+    DuplicateIdentifier(String),
+    DuplicatePredicate(String),
+    MissingSolveItem,
+    MultipleSolveItems,
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::Parse(e) => write!(f, "{}", e),
+            ModelError::DuplicateIdentifier(id) => {
+                write!(f, "identifier `{}` is declared more than once", id)
+            }
+            ModelError::DuplicatePredicate(id) => {
+                write!(f, "predicate `{}` is declared more than once", id)
+            }
+            ModelError::MissingSolveItem => write!(f, "model has no solve item"),
+            ModelError::MultipleSolveItems => write!(f, "model has more than one solve item"),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+impl From<ParseError> for ModelError {
+    fn from(e: ParseError) -> Self {
+        ModelError::Parse(e)
+    }
+}
+
+impl Model {
+    /// Build a model from parsed statements, skipping comments.
+    pub fn from_stmts(stmts: impl IntoIterator<Item = Stmt>) -> Result<Model, ModelError> {
+        let mut predicates: Vec<PredicateItem> = vec![];
+        let mut pars = vec![];
+        let mut vars = vec![];
+        let mut constraints = vec![];
+        let mut solve = None;
+        let mut decls = HashMap::new();
+        for stmt in stmts {
+            match stmt {
+                Stmt::Comment(_) => {}
+                Stmt::Predicate(item) => {
+                    if predicates.iter().any(|p| p.id == item.id) {
+                        return Err(ModelError::DuplicatePredicate(item.id));
+                    }
+                    predicates.push(item);
+                }
+                Stmt::Parameter(item) => {
+                    let id = item.id().to_string();
+                    if decls.insert(id.clone(), Decl::Par(pars.len())).is_some() {
+                        return Err(ModelError::DuplicateIdentifier(id));
+                    }
+                    pars.push(item);
+                }
+                Stmt::Variable(item) => {
+                    let id = item.id().to_string();
+                    if decls.insert(id.clone(), Decl::Var(vars.len())).is_some() {
+                        return Err(ModelError::DuplicateIdentifier(id));
+                    }
+                    vars.push(item);
+                }
+                Stmt::Constraint(item) => constraints.push(item),
+                Stmt::SolveItem(item) => {
+                    if solve.replace(item).is_some() {
+                        return Err(ModelError::MultipleSolveItems);
+                    }
+                }
+            }
+        }
+        let solve = solve.ok_or(ModelError::MissingSolveItem)?;
+        Ok(Model {
+            predicates,
+            pars,
+            vars,
+            constraints,
+            solve,
+            decls,
+        })
+    }
+
+    /// Look up a variable declaration by identifier.
+    pub fn var(&self, id: &str) -> Option<&VarDeclItem> {
+        match self.decls.get(id)? {
+            Decl::Var(i) => Some(&self.vars[*i]),
+            Decl::Par(_) => None,
+        }
+    }
+
+    /// Look up a parameter declaration by identifier.
+    pub fn par(&self, id: &str) -> Option<&ParDeclItem> {
+        match self.decls.get(id)? {
+            Decl::Par(i) => Some(&self.pars[*i]),
+            Decl::Var(_) => None,
+        }
+    }
+
+    /// Look up the type of a declared variable or parameter.
+    pub fn type_of(&self, id: &str) -> Option<DeclType> {
+        match self.decls.get(id)? {
+            Decl::Par(i) => Some(par_type(&self.pars[*i])),
+            Decl::Var(i) => Some(var_type(&self.vars[*i])),
+        }
+    }
+
+    /// Look up a predicate declaration by identifier.
+    pub fn predicate(&self, id: &str) -> Option<&PredicateItem> {
+        self.predicates.iter().find(|p| p.id == id)
+    }
+
+    pub fn predicates(&self) -> &[PredicateItem] {
+        &self.predicates
+    }
+
+    pub fn pars(&self) -> &[ParDeclItem] {
+        &self.pars
+    }
+
+    pub fn vars(&self) -> &[VarDeclItem] {
+        &self.vars
+    }
+
+    pub fn constraints(&self) -> &[ConstraintItem] {
+        &self.constraints
+    }
+
+    pub fn solve(&self) -> &SolveItem {
+        &self.solve
+    }
+
+    /// Turn the model back into statements: predicates, parameters,
+    /// variables, constraints and finally the solve item.
+    pub fn into_stmts(self) -> Vec<Stmt> {
+        let mut stmts = Vec::with_capacity(
+            self.predicates.len() + self.pars.len() + self.vars.len() + self.constraints.len() + 1,
+        );
+        stmts.extend(self.predicates.into_iter().map(Stmt::Predicate));
+        stmts.extend(self.pars.into_iter().map(Stmt::Parameter));
+        stmts.extend(self.vars.into_iter().map(Stmt::Variable));
+        stmts.extend(self.constraints.into_iter().map(Stmt::Constraint));
+        stmts.push(Stmt::SolveItem(self.solve));
+        stmts
+    }
+}
+
+fn par_type(item: &ParDeclItem) -> DeclType {
+    let (scalar, array) = match item {
+        ParDeclItem::Bool { .. } => (ScalarType::Bool, false),
+        ParDeclItem::Int { .. } => (ScalarType::Int, false),
+        ParDeclItem::Float { .. } => (ScalarType::Float, false),
+        ParDeclItem::SetOfInt { .. } => (ScalarType::SetOfInt, false),
+        ParDeclItem::ArrayOfBool { .. } => (ScalarType::Bool, true),
+        ParDeclItem::ArrayOfInt { .. } => (ScalarType::Int, true),
+        ParDeclItem::ArrayOfFloat { .. } => (ScalarType::Float, true),
+        ParDeclItem::ArrayOfSet { .. } => (ScalarType::SetOfInt, true),
+    };
+    DeclType {
+        scalar,
+        array,
+        var: false,
+    }
+}
+
+fn var_type(item: &VarDeclItem) -> DeclType {
+    let (scalar, array) = match item {
+        VarDeclItem::Bool { .. } => (ScalarType::Bool, false),
+        VarDeclItem::Int { .. } | VarDeclItem::IntInRange { .. } | VarDeclItem::IntInSet { .. } => {
+            (ScalarType::Int, false)
+        }
+        VarDeclItem::Float { .. }
+        | VarDeclItem::BoundedFloat { .. }
+        | VarDeclItem::FloatInSet { .. } => (ScalarType::Float, false),
+        VarDeclItem::SetOfInt { .. }
+        | VarDeclItem::SubSetOfIntSet { .. }
+        | VarDeclItem::SubSetOfIntRange { .. } => (ScalarType::SetOfInt, false),
+        VarDeclItem::ArrayOfBool { .. } => (ScalarType::Bool, true),
+        VarDeclItem::ArrayOfInt { .. }
+        | VarDeclItem::ArrayOfIntInRange { .. }
+        | VarDeclItem::ArrayOfIntInSet { .. } => (ScalarType::Int, true),
+        VarDeclItem::ArrayOfFloat { .. }
+        | VarDeclItem::ArrayOfBoundedFloat { .. }
+        | VarDeclItem::ArrayOfFloatInSet { .. } => (ScalarType::Float, true),
+        VarDeclItem::ArrayOfSet { .. }
+        | VarDeclItem::ArrayOfSubSetOfIntRange { .. }
+        | VarDeclItem::ArrayOfSubSetOfIntSet { .. } => (ScalarType::SetOfInt, true),
+    };
+    DeclType {
+        scalar,
+        array,
+        var: true,
+    }
+}
+
+impl std::str::FromStr for Model {
+    type Err = ModelError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Model::from_stmts(parse_model(input)?)
+    }
+}
+#[test]
+fn test_model_jobshop() {
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    assert_eq!(
+        model.var("X_INTRODUCED_0_").unwrap().id(),
+        "X_INTRODUCED_0_"
+    );
+    assert_eq!(
+        model.par("X_INTRODUCED_22_").unwrap().id(),
+        "X_INTRODUCED_22_"
+    );
+    assert!(model.var("X_INTRODUCED_22_").is_none());
+    assert!(model.par("X_INTRODUCED_0_").is_none());
+    assert!(model.var("nonexistent").is_none());
+    assert_eq!(
+        model.type_of("X_INTRODUCED_0_"),
+        Some(DeclType {
+            scalar: ScalarType::Int,
+            array: false,
+            var: true
+        })
+    );
+    assert_eq!(
+        model.type_of("X_INTRODUCED_22_"),
+        Some(DeclType {
+            scalar: ScalarType::Int,
+            array: true,
+            var: false
+        })
+    );
+    assert_eq!(
+        model.pars().len() + model.vars().len() + model.constraints().len() + 1,
+        95
+    );
+    assert_eq!(model.clone().into_stmts().len(), 95);
+}
+#[test]
+fn test_model_errors() {
+    let err = "var int: x;\nvar bool: x;\nsolve satisfy;"
+        .parse::<Model>()
+        .unwrap_err();
+    assert_eq!(err, ModelError::DuplicateIdentifier("x".to_string()));
+    let err = "int: x = 1;\nvar int: x;\nsolve satisfy;"
+        .parse::<Model>()
+        .unwrap_err();
+    assert_eq!(err, ModelError::DuplicateIdentifier("x".to_string()));
+    let err = "var int: x;".parse::<Model>().unwrap_err();
+    assert_eq!(err, ModelError::MissingSolveItem);
+    let err = "solve satisfy;\nsolve satisfy;"
+        .parse::<Model>()
+        .unwrap_err();
+    assert_eq!(err, ModelError::MultipleSolveItems);
+    let err = "var int x;".parse::<Model>().unwrap_err();
+    assert!(matches!(err, ModelError::Parse(_)));
+}
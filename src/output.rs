@@ -0,0 +1,220 @@
+//! Output annotations of variable declarations.
+
+use std::fmt;
+
+use crate::{
+    expressions::{AnnExpr, Annotation, Expr, IntExpr, SetExpr, SetLiteralExpr},
+    model::{Model, ScalarType},
+    primitive_literals::dims_len,
+    variables::declarations::VarDeclItem,
+};
+
+/// A variable whose value is part of a solution, marked with `output_var` or `output_array`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct OutputVar {
+    pub id: String,
+    pub scalar: ScalarType,
+    pub kind: OutputKind,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum OutputKind {
+    Var,
+    /// An array with the bounds of each dimension given by `output_array`
+    Array(Vec<(i128, i128)>),
+}
+
+/// An error in the output annotation of a variable.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum OutputError {
+    /// `output_var` on an array or `output_array` on a single variable
+    WrongKind(String),
+    /// The argument of `output_array` is not an array of integer ranges
+    InvalidIndexSets(String),
+    /// The index set of the array declaration, like `1..-1`, is not a valid length
+    InvalidArrayLength(String),
+    /// The dimensions given by `output_array` do not match the array length
+    SizeMismatch {
+        id: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputError::WrongKind(id) => {
+                write!(f, "output annotation of `{}` does not match its type", id)
+            }
+            OutputError::InvalidIndexSets(id) => {
+                write!(f, "invalid index sets in output_array of `{}`", id)
+            }
+            OutputError::InvalidArrayLength(id) => {
+                write!(f, "invalid index set in the declaration of `{}`", id)
+            }
+            OutputError::SizeMismatch {
+                id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "output_array of `{}` has {} elements, but the array has {}",
+                id, found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OutputError {}
+
+impl Model {
+    /// Collect the output variables in declaration order.
+    pub fn output_vars(&self) -> Result<Vec<OutputVar>, OutputError> {
+        let mut output = vec![];
+        for item in &self.vars {
+            let id = item.id();
+            let ty = self.type_of(id).expect("declared variable");
+            for anno in item.annos() {
+                let kind = match anno.id.as_str() {
+                    "output_var" if !ty.array => OutputKind::Var,
+                    "output_array" if ty.array => {
+                        let invalid = || OutputError::InvalidIndexSets(id.to_string());
+                        let dims = index_sets(anno).ok_or_else(invalid)?;
+                        let found = dims_len(&dims).ok_or_else(invalid)?;
+                        let expected = array_len(item)
+                            .ok_or_else(|| OutputError::InvalidArrayLength(id.to_string()))?;
+                        if found != expected {
+                            return Err(OutputError::SizeMismatch {
+                                id: id.to_string(),
+                                expected,
+                                found,
+                            });
+                        }
+                        OutputKind::Array(dims)
+                    }
+                    "output_var" | "output_array" => {
+                        return Err(OutputError::WrongKind(id.to_string()))
+                    }
+                    _ => continue,
+                };
+                output.push(OutputVar {
+                    id: id.to_string(),
+                    scalar: ty.scalar,
+                    kind,
+                });
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Decode the argument of `output_array`, an array of integer ranges.
+fn index_sets(anno: &Annotation) -> Option<Vec<(i128, i128)>> {
+    let [AnnExpr::Expr(Expr::ArrayOfSet(sets))] = &anno.expressions[..] else {
+        return None;
+    };
+    sets.iter()
+        .map(|set| match set {
+            SetExpr::Set(SetLiteralExpr::IntInRange(IntExpr::Int(lb), IntExpr::Int(ub))) => {
+                Some((*lb, *ub))
+            }
+            SetExpr::Set(SetLiteralExpr::SetInts(v)) if v.is_empty() => Some((1, 0)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The length of a declared array, or 1 for a single variable, if it fits into a `usize`.
+pub(crate) fn array_len(item: &VarDeclItem) -> Option<usize> {
+    match item {
+        VarDeclItem::ArrayOfBool { ix, .. }
+        | VarDeclItem::ArrayOfInt { ix, .. }
+        | VarDeclItem::ArrayOfIntInRange { ix, .. }
+        | VarDeclItem::ArrayOfIntInSet { ix, .. }
+        | VarDeclItem::ArrayOfFloat { ix, .. }
+        | VarDeclItem::ArrayOfBoundedFloat { ix, .. }
+        | VarDeclItem::ArrayOfFloatInSet { ix, .. }
+        | VarDeclItem::ArrayOfSet { ix, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntRange { ix, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntSet { ix, .. } => ix.length(),
+        _ => Some(1),
+    }
+}
+#[test]
+fn test_output_vars() {
+    let model: Model = "var 0..3: x :: output_var;\n\
+        var bool: b;\n\
+        array [1..6] of var int: grid :: output_array([1..2,0..2]) = [x,x,x,x,x,x];\n\
+        var set of 1..3: s :: output_var;\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        model.output_vars(),
+        Ok(vec![
+            OutputVar {
+                id: "x".to_string(),
+                scalar: ScalarType::Int,
+                kind: OutputKind::Var,
+            },
+            OutputVar {
+                id: "grid".to_string(),
+                scalar: ScalarType::Int,
+                kind: OutputKind::Array(vec![(1, 2), (0, 2)]),
+            },
+            OutputVar {
+                id: "s".to_string(),
+                scalar: ScalarType::SetOfInt,
+                kind: OutputKind::Var,
+            },
+        ])
+    );
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    assert_eq!(
+        model.output_vars(),
+        Ok(vec![OutputVar {
+            id: "s".to_string(),
+            scalar: ScalarType::Int,
+            kind: OutputKind::Array(vec![(1, 3), (1, 4)]),
+        }])
+    );
+}
+#[test]
+fn test_output_vars_errors() {
+    let output = |input: &str| input.parse::<Model>().unwrap().output_vars();
+    assert_eq!(
+        output("array [1..4] of var int: a :: output_array([1..2,1..3]);\nsolve satisfy;"),
+        Err(OutputError::SizeMismatch {
+            id: "a".to_string(),
+            expected: 4,
+            found: 6
+        })
+    );
+    assert_eq!(
+        output("array [1..2] of var int: a :: output_var;\nsolve satisfy;"),
+        Err(OutputError::WrongKind("a".to_string()))
+    );
+    assert_eq!(
+        output("array [1..2] of var int: a :: output_array(x);\nsolve satisfy;"),
+        Err(OutputError::InvalidIndexSets("a".to_string()))
+    );
+    assert_eq!(
+        output("array [1..-1] of var int: a :: output_array([1..0]);\nsolve satisfy;"),
+        Err(OutputError::InvalidArrayLength("a".to_string()))
+    );
+    for dims in [
+        "1..18446744073709551618",
+        "-170141183460469231731687303715884105727..170141183460469231731687303715884105727",
+        "1..4294967296,1..4294967296",
+    ] {
+        let input = format!(
+            "array [1..2] of var int: a :: output_array([{}]);\nsolve satisfy;",
+            dims
+        );
+        assert_eq!(
+            output(&input),
+            Err(OutputError::InvalidIndexSets("a".to_string()))
+        );
+    }
+}
@@ -1,140 +1,224 @@
 use winnow::{
-    error::{FromExternalError, ParserError},
+    error::{AddContext, FromExternalError, ParserError, StrContext, StrContextValue},
+    stream::Stream,
     PResult, Parser,
 };
 
 use crate::{
     basic_types::BasicType,
     comments::space_or_comment0,
+    error::StmtKind,
+    expressions::StrRole,
     expressions::{
         array_of_bool_literal, array_of_float_literal, array_of_int_literal, array_of_set_literal,
         set_literal, SetLiteral,
     },
     parameters::types::{par_type, BasicParType, ParType},
-    primitive_literals::{bool_literal, float_literal, int_literal, var_par_identifier, IndexSet},
+    primitive_literals::{
+        bool_literal, float_literal, int_literal, var_par_identifier, IndexSet, ParseStr,
+    },
+    spans::{spanned, NodeKind},
 };
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum ParDeclItem {
+pub enum ParDeclItem<S = String> {
     Bool {
-        id: String,
+        id: S,
         bool: bool,
     },
     Int {
-        id: String,
+        id: S,
         int: i128,
     },
     Float {
-        id: String,
+        id: S,
         float: f64,
     },
     SetOfInt {
-        id: String,
+        id: S,
         set_literal: SetLiteral,
     },
     ArrayOfBool {
         ix: IndexSet,
-        id: String,
+        id: S,
         v: Vec<bool>,
     },
     ArrayOfInt {
         ix: IndexSet,
-        id: String,
+        id: S,
         v: Vec<i128>,
     },
     ArrayOfFloat {
         ix: IndexSet,
-        id: String,
+        id: S,
         v: Vec<f64>,
     },
     ArrayOfSet {
         ix: IndexSet,
-        id: String,
+        id: S,
         v: Vec<SetLiteral>,
     },
 }
 
-pub fn par_decl_item<'a, E>(input: &mut &'a str) -> PResult<ParDeclItem, E>
+impl<S> ParDeclItem<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> ParDeclItem<T> {
+        match self {
+            ParDeclItem::Bool { id, bool } => ParDeclItem::Bool {
+                id: f(id, StrRole::Identifier),
+                bool,
+            },
+            ParDeclItem::Int { id, int } => ParDeclItem::Int {
+                id: f(id, StrRole::Identifier),
+                int,
+            },
+            ParDeclItem::Float { id, float } => ParDeclItem::Float {
+                id: f(id, StrRole::Identifier),
+                float,
+            },
+            ParDeclItem::SetOfInt { id, set_literal } => ParDeclItem::SetOfInt {
+                id: f(id, StrRole::Identifier),
+                set_literal,
+            },
+            ParDeclItem::ArrayOfBool { ix, id, v } => ParDeclItem::ArrayOfBool {
+                ix,
+                id: f(id, StrRole::Identifier),
+                v,
+            },
+            ParDeclItem::ArrayOfInt { ix, id, v } => ParDeclItem::ArrayOfInt {
+                ix,
+                id: f(id, StrRole::Identifier),
+                v,
+            },
+            ParDeclItem::ArrayOfFloat { ix, id, v } => ParDeclItem::ArrayOfFloat {
+                ix,
+                id: f(id, StrRole::Identifier),
+                v,
+            },
+            ParDeclItem::ArrayOfSet { ix, id, v } => ParDeclItem::ArrayOfSet {
+                ix,
+                id: f(id, StrRole::Identifier),
+                v,
+            },
+        }
+    }
+}
+
+impl<S: Into<String>> ParDeclItem<S> {
+    /// Convert into a declaration that owns its strings.
+    pub fn into_owned(self) -> ParDeclItem {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+impl<S: AsRef<str>> ParDeclItem<S> {
+    pub fn id(&self) -> &str {
+        match self {
+            ParDeclItem::Bool { id, .. }
+            | ParDeclItem::Int { id, .. }
+            | ParDeclItem::Float { id, .. }
+            | ParDeclItem::SetOfInt { id, .. }
+            | ParDeclItem::ArrayOfBool { id, .. }
+            | ParDeclItem::ArrayOfInt { id, .. }
+            | ParDeclItem::ArrayOfFloat { id, .. }
+            | ParDeclItem::ArrayOfSet { id, .. } => id.as_ref(),
+        }
+    }
+}
+
+pub fn par_decl_item<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<ParDeclItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
     space_or_comment0(input)?;
-    let ptype = par_type(input)?;
+    let start = input.checkpoint();
+    let ptype = spanned(NodeKind::Type, par_type)
+        .context(StrContext::Expected(StrContextValue::Description(
+            "parameter type",
+        )))
+        .parse_next(input)?;
+    // only parameter declarations start with a parameter type
+    par_decl_tail(ptype, input).map_err(|e| {
+        e.cut().add_context(
+            input,
+            &start,
+            StrContext::Label(StmtKind::Parameter.label()),
+        )
+    })
+}
+
+fn par_decl_tail<'a, S: ParseStr<'a>, E>(
+    ptype: ParType,
+    input: &mut &'a str,
+) -> PResult<ParDeclItem<S>, E>
+where
+    E: ParserError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
+{
     space_or_comment0(input)?;
-    ':'.parse_next(input)?;
+    ':'.context(StrContext::Expected(StrContextValue::CharLiteral(':')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
-    let id = var_par_identifier(input)?;
+    let id = spanned(NodeKind::Identifier, var_par_identifier)
+        .context(StrContext::Expected(StrContextValue::Description(
+            "identifier",
+        )))
+        .parse_next(input)?;
     space_or_comment0(input)?;
-    '='.parse_next(input)?;
+    '='.context(StrContext::Expected(StrContextValue::CharLiteral('=')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
-    match ptype {
+    let item = match ptype {
         ParType::BasicParType(bpt) => match bpt {
             BasicParType::BasicType(bt) => match bt {
                 BasicType::Bool => {
-                    let bool = bool_literal(input)?;
-                    space_or_comment0(input)?;
-                    ';'.parse_next(input)?;
-                    space_or_comment0(input)?;
-                    Ok(ParDeclItem::Bool { id, bool })
+                    let bool = spanned(NodeKind::Literal, bool_literal).parse_next(input)?;
+                    ParDeclItem::Bool { id, bool }
                 }
                 BasicType::Int => {
-                    let int = int_literal(input)?;
-                    space_or_comment0(input)?;
-                    ';'.parse_next(input)?;
-                    space_or_comment0(input)?;
-                    Ok(ParDeclItem::Int { id, int })
+                    let int = spanned(NodeKind::Literal, int_literal).parse_next(input)?;
+                    ParDeclItem::Int { id, int }
                 }
                 BasicType::Float => {
-                    let float = float_literal(input)?;
-                    space_or_comment0(input)?;
-                    ';'.parse_next(input)?;
-                    space_or_comment0(input)?;
-                    Ok(ParDeclItem::Float { id, float })
+                    let float = spanned(NodeKind::Literal, float_literal).parse_next(input)?;
+                    ParDeclItem::Float { id, float }
                 }
             },
             BasicParType::SetOfInt => {
-                let set_literal = set_literal(input)?;
-                space_or_comment0(input)?;
-                ';'.parse_next(input)?;
-                space_or_comment0(input)?;
-                Ok(ParDeclItem::SetOfInt { id, set_literal })
+                let set_literal = spanned(NodeKind::Literal, set_literal).parse_next(input)?;
+                ParDeclItem::SetOfInt { id, set_literal }
             }
         },
         ParType::Array { ix, par_type } => match par_type {
             BasicParType::BasicType(bt) => match bt {
                 BasicType::Bool => {
-                    let v = array_of_bool_literal(input)?;
-                    space_or_comment0(input)?;
-                    ';'.parse_next(input)?;
-                    space_or_comment0(input)?;
-                    Ok(ParDeclItem::ArrayOfBool { ix, id, v })
+                    let v = spanned(NodeKind::Literal, array_of_bool_literal).parse_next(input)?;
+                    ParDeclItem::ArrayOfBool { ix, id, v }
                 }
                 BasicType::Int => {
-                    let v = array_of_int_literal(input)?;
-                    space_or_comment0(input)?;
-                    ';'.parse_next(input)?;
-                    space_or_comment0(input)?;
-                    Ok(ParDeclItem::ArrayOfInt { ix, id, v })
+                    let v = spanned(NodeKind::Literal, array_of_int_literal).parse_next(input)?;
+                    ParDeclItem::ArrayOfInt { ix, id, v }
                 }
                 BasicType::Float => {
-                    let v = array_of_float_literal(input)?;
-                    space_or_comment0(input)?;
-                    ';'.parse_next(input)?;
-                    space_or_comment0(input)?;
-                    Ok(ParDeclItem::ArrayOfFloat { ix, id, v })
+                    let v = spanned(NodeKind::Literal, array_of_float_literal).parse_next(input)?;
+                    ParDeclItem::ArrayOfFloat { ix, id, v }
                 }
             },
             BasicParType::SetOfInt => {
-                let v = array_of_set_literal(input)?;
-                space_or_comment0(input)?;
-                ';'.parse_next(input)?;
-                space_or_comment0(input)?;
-                Ok(ParDeclItem::ArrayOfSet { ix, id, v })
+                let v = spanned(NodeKind::Literal, array_of_set_literal).parse_next(input)?;
+                ParDeclItem::ArrayOfSet { ix, id, v }
             }
         },
-    }
+    };
+    space_or_comment0(input)?;
+    ';'.context(StrContext::Expected(StrContextValue::CharLiteral(';')))
+        .parse_next(input)?;
+    space_or_comment0(input)?;
+    Ok(item)
 }
 #[test]
 fn test_par_decl_item_1() {
@@ -142,7 +226,7 @@ fn test_par_decl_item_1() {
     use winnow::error::ContextError;
     let mut input = "array [1..3] of  float: X_139 = [1.0,1.0,1.0];";
     assert_eq!(
-        par_decl_item::<ContextError>(&mut input),
+        par_decl_item::<String, ContextError>(&mut input),
         Ok(ParDeclItem::ArrayOfFloat {
             ix: IndexSet(3),
             id: "X_139".to_string(),
@@ -155,7 +239,7 @@ fn test_par_decl_item_1() {
 fn test_par_decl_item_2() {
     use winnow::error::ContextError;
     let mut input = "bool : b2 = b1;";
-    par_decl_item::<ContextError>(&mut input).unwrap();
+    par_decl_item::<String, ContextError>(&mut input).unwrap();
 }
 #[test]
 fn test_par_decl_item_3() {
@@ -164,7 +248,7 @@ fn test_par_decl_item_3() {
     use winnow::error::ContextError;
     let mut input = "array [1..3] of set of int : h = [{42,17},1..5,{}];";
     assert_eq!(
-        par_decl_item::<ContextError>(&mut input),
+        par_decl_item::<String, ContextError>(&mut input),
         Ok(ParDeclItem::ArrayOfSet {
             ix: IndexSet(3),
             id: "h".to_string(),
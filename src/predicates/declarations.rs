@@ -1,22 +1,45 @@
 use winnow::{
     combinator::{cut_err, separated},
-    error::{AddContext, FromExternalError, ParserError, StrContext},
+    error::{AddContext, FromExternalError, ParserError, StrContext, StrContextValue},
     PResult, Parser,
 };
 
 use crate::{
     comments::{space_or_comment0, space_or_comment1},
+    error::StmtKind,
+    expressions::StrRole,
     predicates::types::{pred_par_type, PredParType},
-    primitive_literals::identifier,
+    primitive_literals::{identifier, ParseStr},
+    spans::{spanned, NodeKind},
 };
 
 #[derive(PartialEq, Clone, Debug)]
-pub struct PredicateItem {
-    pub id: String,
-    pub parameters: Vec<(PredParType, String)>,
+pub struct PredicateItem<S = String> {
+    pub id: S,
+    pub parameters: Vec<(PredParType, S)>,
 }
 
-pub fn predicate_item<'a, E>(input: &mut &'a str) -> PResult<PredicateItem, E>
+impl<S> PredicateItem<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> PredicateItem<T> {
+        PredicateItem {
+            id: f(self.id, StrRole::Name),
+            parameters: self
+                .parameters
+                .into_iter()
+                .map(|(ty, id)| (ty, f(id, StrRole::Name)))
+                .collect(),
+        }
+    }
+}
+
+impl<S: Into<String>> PredicateItem<S> {
+    /// Convert into a predicate declaration that owns its strings.
+    pub fn into_owned(self) -> PredicateItem {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+pub fn predicate_item<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<PredicateItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -25,22 +48,40 @@ where
 {
     space_or_comment0(input)?;
     "predicate".parse_next(input)?;
-    cut_err(predicate_item_tail.context(StrContext::Label("Error while parsing solve statement")))
+    cut_err(predicate_item_tail.context(StrContext::Label(StmtKind::Predicate.label())))
         .parse_next(input)
 }
-pub fn predicate_item_tail<'a, E>(input: &mut &'a str) -> PResult<PredicateItem, E>
+pub fn predicate_item_tail<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<PredicateItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
     space_or_comment1(input)?;
-    let id = identifier(input)?;
-    '('.parse_next(input)?;
-    let parameters = separated(1.., pred_par_type_ident_pair, ",").parse_next(input)?;
-    ')'.parse_next(input)?;
+    let id = spanned(NodeKind::Identifier, identifier)
+        .context(StrContext::Expected(StrContextValue::Description(
+            "identifier",
+        )))
+        .parse_next(input)?;
+    '('.context(StrContext::Expected(StrContextValue::CharLiteral('(')))
+        .parse_next(input)?;
+    let parameters = separated(
+        1..,
+        spanned(NodeKind::PredicateParameter, pred_par_type_ident_pair),
+        ",",
+    )
+    .context(StrContext::Expected(StrContextValue::Description(
+        "predicate parameter",
+    )))
+    .parse_next(input)?;
+    ')'.context(StrContext::Expected(StrContextValue::CharLiteral(')')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
-    ';'.parse_next(input)?;
+    ';'.context(StrContext::Expected(StrContextValue::CharLiteral(';')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
     Ok(PredicateItem { id, parameters })
 }
@@ -50,7 +91,7 @@ fn test_predicate_item() {
     use winnow::error::ContextError;
     let mut input = "predicate float_03({1.0,3.3}:c);";
     assert_eq!(
-        predicate_item::<ContextError>(&mut input),
+        predicate_item::<String, ContextError>(&mut input),
         Ok(PredicateItem {
             id: "float_03".to_string(),
             parameters: vec![(
@@ -66,7 +107,7 @@ fn test_predicate_item2() {
     use winnow::error::ContextError;
     let mut input = "predicate my_pred({1.0,3.3}:c);";
     assert_eq!(
-        predicate_item::<ContextError>(&mut input),
+        predicate_item::<String, ContextError>(&mut input),
         Ok(PredicateItem {
             id: "my_pred".to_string(),
             parameters: vec![(
@@ -81,21 +122,23 @@ fn test_predicate_item2() {
 fn test_predicate_item_3() {
     use winnow::error::ContextError;
     let mut input = "predicate float_01(set of float:c);";
-    predicate_item::<ContextError>(&mut input).unwrap();
+    predicate_item::<String, ContextError>(&mut input).unwrap();
 }
 
-pub fn pred_par_type_ident_pair<'a, E>(input: &mut &'a str) -> PResult<(PredParType, String), E>
+pub fn pred_par_type_ident_pair<'a, S: ParseStr<'a>, E>(
+    input: &mut &'a str,
+) -> PResult<(PredParType, S), E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
         + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
     space_or_comment0(input)?;
-    let pred_par_type = pred_par_type(input)?;
+    let pred_par_type = spanned(NodeKind::Type, pred_par_type).parse_next(input)?;
     space_or_comment0(input)?;
     ':'.parse_next(input)?;
     space_or_comment0(input)?;
-    let ident = identifier(input)?;
+    let ident = spanned(NodeKind::Identifier, identifier).parse_next(input)?;
     space_or_comment0(input)?;
     Ok((pred_par_type, ident))
 }
@@ -106,7 +149,7 @@ fn test_pred_par_type_ident_pair() {
     use winnow::error::ContextError;
     let mut input = "var set of int: g";
     assert_eq!(
-        declarations::pred_par_type_ident_pair::<ContextError>(&mut input),
+        declarations::pred_par_type_ident_pair::<String, ContextError>(&mut input),
         Ok((
             types::PredParType::Basic(types::BasicPredParType::VarSetOfInt),
             "g".to_string()
@@ -7,43 +7,57 @@ use winnow::{
 
 use crate::comments::space_or_comment0;
 
-pub fn identifier<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<String, E> {
+/// The string type of identifiers and string literals in the AST.
+///
+/// The parsers are generic over it: `String` yields an owned AST, while
+/// `Cow<'a, str>` borrows identifiers from the input and only allocates for
+/// string literals containing escape sequences.
+pub trait ParseStr<'a>: From<&'a str> + From<String> {}
+
+impl<'a, S: From<&'a str> + From<String>> ParseStr<'a> for S {}
+
+pub fn identifier<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<S, E> {
     let first = one_of([
         'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
         's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J',
         'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
     ])
-    .parse_next(input)?;
-    let rest = take_while(0.., is_identifier_rest).parse_next(input)?;
-    let combine = format!("{}{}", first, rest);
+    .void();
+    let combine = (first, take_while(0.., is_identifier_rest))
+        .recognize()
+        .parse_next(input)?;
     // check for reserved key words
-    if is_reserved_key_word(&combine) {
+    if is_reserved_key_word(combine) {
         Err(winnow::error::ErrMode::Cut(ParserError::from_error_kind(
             input,
             ErrorKind::Token,
         )))
     } else {
-        Ok(combine)
+        Ok(combine.into())
     }
 }
 
-pub fn var_par_identifier<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<String, E> {
+pub fn var_par_identifier<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<S, E> {
     let first = one_of([
         'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
         's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J',
         'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '_',
     ])
-    .parse_next(input)?;
-
-    let rest = take_while(0.., is_identifier_rest).parse_next(input)?;
-    let combine = format!("{}{}", first, rest);
+    .void();
+    let combine = (first, take_while(0.., is_identifier_rest))
+        .recognize()
+        .parse_next(input)?;
     // check for reserved key words
-    if is_reserved_key_word(&combine) {
+    if is_reserved_key_word(combine) {
         Err(winnow::error::ErrMode::Backtrack(
             ParserError::from_error_kind(input, ErrorKind::Token),
         ))
     } else {
-        Ok(combine)
+        Ok(combine.into())
     }
 }
 
@@ -364,6 +378,44 @@ fn bpart<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<String, E>
 #[derive(PartialEq, Clone, Debug)]
 pub struct IndexSet(pub i128);
 
+impl IndexSet {
+    /// The length of the index set `1..n`, or `None` if `n` is negative or
+    /// does not fit into a `usize`.
+    pub(crate) fn length(&self) -> Option<usize> {
+        match self.0 {
+            n if n < 0 => None,
+            n => range_len(1, n),
+        }
+    }
+}
+
+/// The number of integers from `lb` to `ub`, if it fits into a `usize`.
+pub(crate) fn range_len(lb: i128, ub: i128) -> Option<usize> {
+    if ub < lb {
+        return Some(0);
+    }
+    usize::try_from(ub.checked_sub(lb)?.checked_add(1)?).ok()
+}
+
+/// The number of elements of an array with the given dimensions, if it fits into a `usize`.
+pub(crate) fn dims_len(dims: &[(i128, i128)]) -> Option<usize> {
+    dims.iter().try_fold(1usize, |len, (lb, ub)| {
+        len.checked_mul(range_len(*lb, *ub)?)
+    })
+}
+
+#[test]
+fn test_array_lengths() {
+    assert_eq!(IndexSet(0).length(), Some(0));
+    assert_eq!(IndexSet(3).length(), Some(3));
+    assert_eq!(IndexSet(-1).length(), None);
+    assert_eq!(IndexSet(i128::MAX).length(), None);
+    assert_eq!(range_len(i128::MIN, i128::MAX), None);
+    assert_eq!(dims_len(&[(1, 2), (0, 2), (5, 1)]), Some(0));
+    assert_eq!(dims_len(&[(1, 2), (0, 2)]), Some(6));
+    assert_eq!(dims_len(&[(1, 1 << 32), (1, 1 << 32), (1, 1 << 32)]), None);
+}
+
 pub fn index_set<'a, E>(input: &mut &'a str) -> PResult<IndexSet, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
@@ -0,0 +1,774 @@
+//! FlatZinc output for the AST.
+//!
+//! Every AST type implements [`Display`], printing spec-conformant FlatZinc
+//! that parses back into an equal AST. By default everything is printed on a
+//! single line. With the alternate flag, array literals longer than the
+//! formatter width (80 if none is given) are wrapped over several lines, for
+//! example `format!("{:#}", stmt)` or `format!("{:#100}", stmt)`.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+    basic_types::BasicType,
+    constraints::ConstraintItem,
+    expressions::{
+        AnnExpr, Annotation, ArrayOfBoolExpr, ArrayOfFloatExpr, ArrayOfIntExpr, ArrayOfSetExpr,
+        BoolExpr, Expr, FloatExpr, IntExpr, SetExpr, SetLiteral, SetLiteralExpr,
+    },
+    model::Model,
+    parameters::{
+        declarations::ParDeclItem,
+        types::{BasicParType, ParType},
+    },
+    predicates::{
+        declarations::PredicateItem,
+        types::{BasicPredParType, PredIndexSet, PredParType},
+    },
+    primitive_literals::IndexSet,
+    solve_items::{Goal, OptimizationType, SolveItem},
+    statements::Stmt,
+    variables::{
+        declarations::VarDeclItem,
+        types::{BasicVarType, VarType},
+    },
+};
+
+const DEFAULT_WIDTH: usize = 80;
+const INDENT: &str = "    ";
+
+/// Write an array literal, wrapping it if requested by the formatter.
+fn write_array<T: Display>(f: &mut Formatter<'_>, items: &[T]) -> fmt::Result {
+    let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+    let width = f.width().unwrap_or(DEFAULT_WIDTH);
+    let len = items.iter().map(|item| item.len() + 1).sum::<usize>() + 1;
+    if !f.alternate() || len <= width {
+        return write!(f, "[{}]", items.join(","));
+    }
+    write!(f, "[")?;
+    let mut column = 1;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+            column += 1;
+            if column + item.len() + 1 > width {
+                write!(f, "\n{}", INDENT)?;
+                column = INDENT.len();
+            }
+        }
+        write!(f, "{}", item)?;
+        column += item.len();
+    }
+    write!(f, "]")
+}
+
+/// Write a set literal such as `{1,2,3}`.
+fn write_set<T: Display>(f: &mut Formatter<'_>, items: &[T]) -> fmt::Result {
+    let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+    write!(f, "{{{}}}", items.join(","))
+}
+
+/// Write items separated by `sep`, passing the formatter flags on to each item.
+fn write_separated<T: Display>(f: &mut Formatter<'_>, items: &[T], sep: &str) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", sep)?;
+        }
+        item.fmt(f)?;
+    }
+    Ok(())
+}
+
+/// Write a float so that it is parsed back as a float literal.
+fn write_float(f: &mut Formatter<'_>, float: f64) -> fmt::Result {
+    write!(f, "{:?}", float)
+}
+
+fn write_annotations(f: &mut Formatter<'_>, annos: &[Annotation]) -> fmt::Result {
+    for anno in annos {
+        write!(f, " :: ")?;
+        anno.fmt(f)?;
+    }
+    Ok(())
+}
+
+fn write_assignment<T: Display>(f: &mut Formatter<'_>, expr: &Option<T>) -> fmt::Result {
+    if let Some(expr) = expr {
+        write!(f, " = ")?;
+        expr.fmt(f)?;
+    }
+    Ok(())
+}
+
+impl Display for Stmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Stmt::Comment(comment) => {
+                for (i, line) in comment.lines().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "%{}", line)?;
+                }
+                Ok(())
+            }
+            Stmt::Predicate(item) => item.fmt(f),
+            Stmt::Parameter(item) => item.fmt(f),
+            Stmt::Variable(item) => item.fmt(f),
+            Stmt::Constraint(item) => item.fmt(f),
+            Stmt::SolveItem(item) => item.fmt(f),
+        }
+    }
+}
+
+/// Prints the model with one statement per line.
+impl Display for Model {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let items = self
+            .predicates
+            .iter()
+            .map(|item| item as &dyn Display)
+            .chain(self.pars.iter().map(|item| item as &dyn Display))
+            .chain(self.vars.iter().map(|item| item as &dyn Display))
+            .chain(self.constraints.iter().map(|item| item as &dyn Display));
+        for item in items {
+            item.fmt(f)?;
+            writeln!(f)?;
+        }
+        self.solve.fmt(f)?;
+        writeln!(f)
+    }
+}
+
+impl Display for PredicateItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "predicate {}(", self.id)?;
+        for (i, (ty, id)) in self.parameters.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", ty, id)?;
+        }
+        write!(f, ");")
+    }
+}
+
+impl Display for PredParType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PredParType::Basic(ty) => write!(f, "{}", ty),
+            PredParType::Array { ix, par_type } => write!(f, "array [{}] of {}", ix, par_type),
+        }
+    }
+}
+
+impl Display for PredIndexSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PredIndexSet::IndexSet(ub) => write!(f, "1..{}", ub),
+            PredIndexSet::Int => write!(f, "int"),
+        }
+    }
+}
+
+impl Display for BasicPredParType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BasicPredParType::BasicParType(ty) => write!(f, "{}", ty),
+            BasicPredParType::BasicVarType(ty) => write!(f, "{}", ty),
+            BasicPredParType::VarSetOfInt => write!(f, "var set of int"),
+            BasicPredParType::IntInRange(lb, ub) => write!(f, "{}..{}", lb, ub),
+            BasicPredParType::IntInSet(set) => write_set(f, set),
+            BasicPredParType::BoundedFloat(lb, ub) => {
+                write_float(f, *lb)?;
+                write!(f, "..")?;
+                write_float(f, *ub)
+            }
+            BasicPredParType::FloatInSet(set) => {
+                let set: Vec<_> = set.iter().map(|float| format!("{:?}", float)).collect();
+                write_set(f, &set)
+            }
+            BasicPredParType::SubSetOfIntSet(set) => {
+                write!(f, "set of ")?;
+                write_set(f, set)
+            }
+            BasicPredParType::SubSetOfIntRange(lb, ub) => write!(f, "set of {}..{}", lb, ub),
+        }
+    }
+}
+
+impl Display for BasicType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BasicType::Bool => write!(f, "bool"),
+            BasicType::Int => write!(f, "int"),
+            BasicType::Float => write!(f, "float"),
+        }
+    }
+}
+
+impl Display for IndexSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "1..{}", self.0)
+    }
+}
+
+impl Display for BasicParType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BasicParType::BasicType(ty) => write!(f, "{}", ty),
+            BasicParType::SetOfInt => write!(f, "set of int"),
+        }
+    }
+}
+
+impl Display for ParType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParType::BasicParType(ty) => write!(f, "{}", ty),
+            ParType::Array { ix, par_type } => write!(f, "array [{}] of {}", ix, par_type),
+        }
+    }
+}
+
+impl Display for BasicVarType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "var ")?;
+        match self {
+            BasicVarType::BasicType(ty) => write!(f, "{}", ty),
+            BasicVarType::IntInRange(lb, ub) => write!(f, "{}..{}", lb, ub),
+            BasicVarType::IntInSet(set) => write_set(f, set),
+            BasicVarType::BoundedFloat(lb, ub) => {
+                write_float(f, *lb)?;
+                write!(f, "..")?;
+                write_float(f, *ub)
+            }
+            BasicVarType::FloatInSet(set) => {
+                let set: Vec<_> = set.iter().map(|float| format!("{:?}", float)).collect();
+                write_set(f, &set)
+            }
+            BasicVarType::SubSetOfIntSet(set) => {
+                write!(f, "set of ")?;
+                write_set(f, set)
+            }
+            BasicVarType::SubSetOfIntRange(lb, ub) => write!(f, "set of {}..{}", lb, ub),
+        }
+    }
+}
+
+impl Display for VarType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VarType::BasicVarType(ty) => write!(f, "{}", ty),
+            VarType::Array { ix, var_type } => write!(f, "array [{}] of {}", ix, var_type),
+        }
+    }
+}
+
+impl Display for ParDeclItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParDeclItem::Bool { id, bool } => write!(f, "bool: {} = {};", id, bool),
+            ParDeclItem::Int { id, int } => write!(f, "int: {} = {};", id, int),
+            ParDeclItem::Float { id, float } => {
+                write!(f, "float: {} = ", id)?;
+                write_float(f, *float)?;
+                write!(f, ";")
+            }
+            ParDeclItem::SetOfInt { id, set_literal } => {
+                write!(f, "set of int: {} = {};", id, set_literal)
+            }
+            ParDeclItem::ArrayOfBool { ix, id, v } => {
+                write!(f, "array [{}] of bool: {} = ", ix, id)?;
+                write_array(f, v)?;
+                write!(f, ";")
+            }
+            ParDeclItem::ArrayOfInt { ix, id, v } => {
+                write!(f, "array [{}] of int: {} = ", ix, id)?;
+                write_array(f, v)?;
+                write!(f, ";")
+            }
+            ParDeclItem::ArrayOfFloat { ix, id, v } => {
+                write!(f, "array [{}] of float: {} = ", ix, id)?;
+                let v: Vec<_> = v.iter().map(|float| format!("{:?}", float)).collect();
+                write_array(f, &v)?;
+                write!(f, ";")
+            }
+            ParDeclItem::ArrayOfSet { ix, id, v } => {
+                write!(f, "array [{}] of set of int: {} = ", ix, id)?;
+                write_array(f, v)?;
+                write!(f, ";")
+            }
+        }
+    }
+}
+
+impl Display for VarDeclItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VarDeclItem::Bool { id, expr, annos } => {
+                write!(f, "var bool: {}", id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::Int { id, expr, annos } => {
+                write!(f, "var int: {}", id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::IntInRange {
+                id,
+                lb,
+                ub,
+                expr,
+                annos,
+            } => {
+                write!(f, "{}: {}", BasicVarType::IntInRange(*lb, *ub), id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::IntInSet {
+                id,
+                set,
+                expr,
+                annos,
+            } => {
+                write!(f, "{}: {}", BasicVarType::IntInSet(set.clone()), id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::Float { id, expr, annos } => {
+                write!(f, "var float: {}", id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::BoundedFloat {
+                id,
+                lb,
+                ub,
+                expr,
+                annos,
+            } => {
+                write!(f, "{}: {}", BasicVarType::BoundedFloat(*lb, *ub), id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::FloatInSet {
+                id,
+                set,
+                expr,
+                annos,
+            } => {
+                write!(f, "{}: {}", BasicVarType::FloatInSet(set.clone()), id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::SetOfInt { id, expr, annos } => {
+                write!(f, "var set of int: {}", id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::SubSetOfIntSet {
+                id,
+                set,
+                expr,
+                annos,
+            } => {
+                write!(f, "{}: {}", BasicVarType::SubSetOfIntSet(set.clone()), id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::SubSetOfIntRange {
+                id,
+                lb,
+                ub,
+                expr,
+                annos,
+            } => {
+                write!(f, "{}: {}", BasicVarType::SubSetOfIntRange(*lb, *ub), id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, expr)?;
+            }
+            VarDeclItem::ArrayOfBool {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                write!(f, "array [{}] of var bool: {}", ix, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfInt {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                write!(f, "array [{}] of var int: {}", ix, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfIntInRange {
+                lb,
+                ub,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                let ty = BasicVarType::IntInRange(*lb, *ub);
+                write!(f, "array [{}] of {}: {}", ix, ty, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfIntInSet {
+                set,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                let ty = BasicVarType::IntInSet(set.clone());
+                write!(f, "array [{}] of {}: {}", ix, ty, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfFloat {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                write!(f, "array [{}] of var float: {}", ix, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfBoundedFloat {
+                lb,
+                ub,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                let ty = BasicVarType::BoundedFloat(*lb, *ub);
+                write!(f, "array [{}] of {}: {}", ix, ty, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfFloatInSet {
+                set,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                let ty = BasicVarType::FloatInSet(set.clone());
+                write!(f, "array [{}] of {}: {}", ix, ty, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfSet {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                write!(f, "array [{}] of var set of int: {}", ix, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfSubSetOfIntRange {
+                ub,
+                lb,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                let ty = BasicVarType::SubSetOfIntRange(*lb, *ub);
+                write!(f, "array [{}] of {}: {}", ix, ty, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+            VarDeclItem::ArrayOfSubSetOfIntSet {
+                set,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => {
+                let ty = BasicVarType::SubSetOfIntSet(set.clone());
+                write!(f, "array [{}] of {}: {}", ix, ty, id)?;
+                write_annotations(f, annos)?;
+                write_assignment(f, array_expr)?;
+            }
+        }
+        write!(f, ";")
+    }
+}
+
+impl Display for ConstraintItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "constraint {}(", self.id)?;
+        write_separated(f, &self.exprs, ",")?;
+        write!(f, ")")?;
+        write_annotations(f, &self.annos)?;
+        write!(f, ";")
+    }
+}
+
+impl Display for SolveItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "solve")?;
+        write_annotations(f, &self.annotations)?;
+        write!(f, " {};", self.goal)
+    }
+}
+
+impl Display for Goal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Goal::Satisfy => write!(f, "satisfy"),
+            Goal::OptimizeBool(opt_type, expr) => write!(f, "{} {}", opt_type, expr),
+            Goal::OptimizeInt(opt_type, expr) => write!(f, "{} {}", opt_type, expr),
+            Goal::OptimizeFloat(opt_type, expr) => write!(f, "{} {}", opt_type, expr),
+            Goal::OptimizeSet(opt_type, expr) => write!(f, "{} {}", opt_type, expr),
+        }
+    }
+}
+
+impl Display for OptimizationType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            OptimizationType::Minimize => write!(f, "minimize"),
+            OptimizationType::Maximize => write!(f, "maximize"),
+        }
+    }
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        if !self.expressions.is_empty() {
+            write!(f, "(")?;
+            write_separated(f, &self.expressions, ",")?;
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AnnExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AnnExpr::Annotations(annos) => {
+                write!(f, "[")?;
+                write_separated(f, annos, ",")?;
+                write!(f, "]")
+            }
+            AnnExpr::String(string) => {
+                write!(f, "\"")?;
+                for c in string.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        '\u{08}' => write!(f, "\\b")?,
+                        '\u{0C}' => write!(f, "\\f")?,
+                        c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            AnnExpr::Expr(expr) => expr.fmt(f),
+            AnnExpr::ArrayAccess(id, index) => write!(f, "{}[{}]", id, index),
+            AnnExpr::Array(v) => write_array(f, v),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::VarParIdentifier(id) => write!(f, "{}", id),
+            Expr::Bool(bool) => write!(f, "{}", bool),
+            Expr::Int(int) => write!(f, "{}", int),
+            Expr::Float(float) => write_float(f, *float),
+            Expr::Set(set) => write!(f, "{}", set),
+            Expr::ArrayOfBool(v) => write_array(f, v),
+            Expr::ArrayOfInt(v) => write_array(f, v),
+            Expr::ArrayOfFloat(v) => write_array(f, v),
+            Expr::ArrayOfSet(v) => write_array(f, v),
+        }
+    }
+}
+
+impl Display for BoolExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BoolExpr::Bool(bool) => write!(f, "{}", bool),
+            BoolExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl Display for IntExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IntExpr::Int(int) => write!(f, "{}", int),
+            IntExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl Display for FloatExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FloatExpr::Float(float) => write_float(f, *float),
+            FloatExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl Display for SetExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SetExpr::Set(set) => write!(f, "{}", set),
+            SetExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl Display for SetLiteralExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SetLiteralExpr::IntInRange(lb, ub) => write!(f, "{}..{}", lb, ub),
+            SetLiteralExpr::BoundedFloat(lb, ub) => write!(f, "{}..{}", lb, ub),
+            SetLiteralExpr::SetFloats(v) => write_set(f, v),
+            SetLiteralExpr::SetInts(v) => write_set(f, v),
+        }
+    }
+}
+
+impl Display for SetLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SetLiteral::IntRange(lb, ub) => write!(f, "{}..{}", lb, ub),
+            SetLiteral::BoundedFloat(lb, ub) => {
+                write_float(f, *lb)?;
+                write!(f, "..")?;
+                write_float(f, *ub)
+            }
+            SetLiteral::SetFloats(v) => {
+                let v: Vec<_> = v.iter().map(|float| format!("{:?}", float)).collect();
+                write_set(f, &v)
+            }
+            SetLiteral::SetInts(v) => write_set(f, v),
+        }
+    }
+}
+
+impl Display for ArrayOfBoolExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrayOfBoolExpr::Array(v) => write_array(f, v),
+            ArrayOfBoolExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl Display for ArrayOfIntExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrayOfIntExpr::Array(v) => write_array(f, v),
+            ArrayOfIntExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl Display for ArrayOfFloatExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrayOfFloatExpr::Array(v) => write_array(f, v),
+            ArrayOfFloatExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl Display for ArrayOfSetExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrayOfSetExpr::Array(v) => write_array(f, v),
+            ArrayOfSetExpr::VarParIdentifier(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+#[cfg(test)]
+fn assert_round_trip(input: &str) {
+    use crate::statements::parse_model;
+    let stmts = parse_model(input).unwrap();
+    for stmt in &stmts {
+        for printed in [format!("{}", stmt), format!("{:#30}", stmt)] {
+            let reparsed: Stmt = printed
+                .parse()
+                .unwrap_or_else(|e| panic!("{}\nin\n{}", e, printed));
+            assert_eq!(&reparsed, stmt, "{}", printed);
+        }
+    }
+}
+#[test]
+fn test_print_stmts() {
+    let input = "predicate my_pred(array [int] of var int: xs, var 1..3: y, {1.0,2.5}: z, set of {1,2}: s);\n\
+        bool: b = true;\n\
+        float: f = -1.5e-7;\n\
+        set of int: s = {1,3};\n\
+        array [1..3] of float: fs = [1.0,2.5,3.0];\n\
+        array [1..3] of bool: bs = [true,false,true];\n\
+        array [1..2] of set of int: ss = [1..3,{}];\n\
+        var 0..38: x :: output_var :: is_defined_var;\n\
+        var {1,3,5}: y;\n\
+        var 0.0..1.5: z = 1.0;\n\
+        var set of 1..3: v;\n\
+        array [1..3] of var set of 17..42: h = [{42,17},23..X,{}];\n\
+        constraint int_lin_le([1,-1],[x,y],-3) :: defines_var(x) :: domain;\n\
+        constraint foo(1.0..2.0,{1.5},[1.0,x],X,true) :: bar(\"a \\\"quoted\\\"\\n\\\\ string\");\n\
+        constraint bool_clause([b],[]) :: warm_start(x[3],4);\n\
+        solve :: seq_search([int_search([x,y],input_order,indomain_min,complete),int_search([x[1],x[2]],input_order,indomain_min,complete)]) maximize x;";
+    assert_round_trip(input);
+    let stmt: Stmt = "constraint int_lin_le([1, -1], [x, y], -3):: domain;"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        stmt.to_string(),
+        "constraint int_lin_le([1,-1],[x,y],-3) :: domain;"
+    );
+}
+#[test]
+fn test_print_jobshop() {
+    let input = include_str!("../jobshop.fzn");
+    assert_round_trip(input);
+    let model: Model = input.parse().unwrap();
+    let reparsed: Model = model.to_string().parse().unwrap();
+    assert_eq!(reparsed, model);
+}
+#[test]
+fn test_print_wrapped() {
+    let stmt: Stmt = "array [1..6] of int: a = [100,200,300,400,500,600];"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        format!("{:#20}", stmt),
+        "array [1..6] of int: a = [100,200,300,400,\n    500,600];"
+    );
+    assert_eq!(
+        format!("{:#}", stmt),
+        "array [1..6] of int: a = [100,200,300,400,500,600];"
+    );
+    let stmt: Stmt = "var {1,2}: x;".parse().unwrap();
+    assert_eq!(format!("{:#20}", stmt), "var {1,2}: x;");
+}
@@ -0,0 +1,205 @@
+use std::{fmt, io, io::BufRead};
+
+use winnow::error::ContextError;
+
+use crate::{
+    comments::space_or_comment0,
+    error::ParseError,
+    statements::{next_item, Stmt, StmtScanner},
+};
+
+/// A statement read by a [`StmtReader`] together with the line it starts on.
+#[derive(PartialEq, Clone, Debug)]
+pub struct LineStmt {
+    /// Line of the first character of the statement, starting at 1
+    pub line: usize,
+    pub stmt: Stmt,
+}
+
+/// An error produced while reading statements from a [`StmtReader`].
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> Self {
+        ReadError::Parse(e)
+    }
+}
+
+/// An iterator over the statements of a FlatZinc model that reads its input
+/// incrementally.
+///
+/// Only the statement currently being parsed is kept in memory. Positions in
+/// parse errors refer to the whole input. A malformed statement yields an
+/// error and reading resumes after its `;`, like
+/// [`parse_model_recovering`](crate::parse_model_recovering). After an I/O
+/// error the iterator is exhausted.
+pub struct StmtReader<R> {
+    reader: R,
+    /// Input that has been read but not parsed yet
+    buf: Vec<u8>,
+    /// Number of bytes of `buf` already fed to `scanner`
+    scanned: usize,
+    scanner: StmtScanner,
+    /// Byte offset, line and column of the start of `buf` in the input
+    offset: usize,
+    line: usize,
+    column: usize,
+    eof: bool,
+}
+
+impl<R: BufRead> StmtReader<R> {
+    pub fn new(reader: R) -> StmtReader<R> {
+        StmtReader {
+            reader,
+            buf: vec![],
+            scanned: 0,
+            scanner: StmtScanner::default(),
+            offset: 0,
+            line: 1,
+            column: 1,
+            eof: false,
+        }
+    }
+
+    /// Parse the first `len` bytes of the buffer and advance past them.
+    fn parse(&mut self, len: usize) -> Option<Result<LineStmt, ReadError>> {
+        let chunk: Vec<u8> = self.buf.drain(..len).collect();
+        self.scanned = 0;
+        self.scanner = StmtScanner::default();
+        let text = match std::str::from_utf8(&chunk) {
+            Ok(text) => text,
+            Err(e) => {
+                self.eof = true;
+                return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e).into()));
+            }
+        };
+        let mut rest = text;
+        let _ = space_or_comment0::<ContextError>(&mut rest);
+        let (line, _) = self.locate(text, text.len() - rest.len());
+        let res = match next_item(text, &mut rest) {
+            Ok(Some(stmt)) => Some(Ok(LineStmt { line, stmt })),
+            Ok(None) => None,
+            Err(mut e) => {
+                (e.line, e.column) = self.locate(text, e.offset);
+                e.offset += self.offset;
+                Some(Err(e.into()))
+            }
+        };
+        (self.line, self.column) = self.locate(text, text.len());
+        self.offset += text.len();
+        res
+    }
+
+    /// Line and column in the input of a byte offset in `text`, which starts
+    /// at the beginning of the buffer.
+    fn locate(&self, text: &str, offset: usize) -> (usize, usize) {
+        let before = &text[..offset];
+        match before.rfind('\n') {
+            Some(i) => (
+                self.line + before.matches('\n').count(),
+                before[i + 1..].chars().count() + 1,
+            ),
+            None => (self.line, self.column + before.chars().count()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for StmtReader<R> {
+    type Item = Result<LineStmt, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let end = self.buf[self.scanned..]
+                .iter()
+                .position(|b| self.scanner.is_end(*b));
+            match end {
+                Some(i) => {
+                    let len = self.scanned + i + 1;
+                    if let Some(res) = self.parse(len) {
+                        return Some(res);
+                    }
+                }
+                None => self.scanned = self.buf.len(),
+            }
+            if self.eof {
+                if self.buf.is_empty() {
+                    return None;
+                }
+                // a trailing statement without `;` or only comments
+                return self.parse(self.buf.len());
+            }
+            match self.reader.read_until(b';', &mut self.buf) {
+                Ok(0) => self.eof = true,
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.eof = true;
+                    self.buf.clear();
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+    }
+}
+#[test]
+fn test_stmt_reader_jobshop() {
+    use crate::statements::parse_model;
+    let input = include_str!("../jobshop.fzn");
+    let reader = io::BufReader::with_capacity(7, input.as_bytes());
+    let stmts: Vec<_> = StmtReader::new(reader).map(|s| s.unwrap()).collect();
+    let expected = parse_model(input).unwrap();
+    assert_eq!(stmts.len(), expected.len());
+    for (read, expected) in stmts.iter().zip(expected) {
+        assert_eq!(read.stmt, expected);
+    }
+    let lines: Vec<_> = stmts.iter().map(|s| s.line).collect();
+    assert_eq!(lines[..3], [1, 2, 3]);
+    assert_eq!(lines.last(), Some(&input.lines().count()));
+}
+#[test]
+fn test_stmt_reader_lines() {
+    let input = "% header; with a semicolon\nvar int: x; var int y;\n\nconstraint foo(\n  x\n) :: bar(\"a;b\");\nconstraint int_eq(x, );\nsolve satisfy";
+    let reader = io::BufReader::with_capacity(3, input.as_bytes());
+    let res: Vec<_> = StmtReader::new(reader).collect();
+    assert_eq!(res.len(), 5);
+    assert!(matches!(res[0], Ok(LineStmt { line: 2, .. })));
+    let Err(ReadError::Parse(e)) = &res[1] else {
+        panic!("expected a parse error");
+    };
+    assert_eq!((e.line, e.column, e.offset), (2, 21, 47));
+    assert!(matches!(res[2], Ok(LineStmt { line: 4, .. })));
+    let Err(ReadError::Parse(e)) = &res[3] else {
+        panic!("expected a parse error");
+    };
+    assert_eq!((e.line, e.column), (7, 20));
+    // the last statement is missing its `;`
+    assert!(matches!(res[4], Err(ReadError::Parse(_))));
+}
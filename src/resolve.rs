@@ -0,0 +1,377 @@
+use std::fmt;
+
+use crate::{
+    constraints::ConstraintItem,
+    expressions::{BoolExpr, Expr, FloatExpr, IntExpr, SetExpr, SetLiteralExpr},
+    model::{DeclType, Model, ScalarType},
+    solve_items::{Goal, SolveItem},
+};
+
+/// An error produced while resolving a [`Model`] against its declarations.
+#[derive(PartialEq, Clone, Debug)]
+pub enum ResolveError {
+    /// The identifier is not a declared variable or parameter
+    Undeclared(String),
+    /// The identifier is declared with a type that cannot be used here
+    TypeMismatch {
+        id: String,
+        expected: ScalarType,
+        found: DeclType,
+    },
+    /// An array literal mixes `true` or `false` with elements of another type
+    MixedArray { expected: ScalarType },
+    /// The objective of the solve item is a `var bool`
+    BoolObjective(String),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Undeclared(id) => write!(f, "`{}` is not declared", id),
+            ResolveError::TypeMismatch {
+                id,
+                expected,
+                found,
+            } => write!(f, "`{}` has type {}, expected {}", id, found, expected),
+            ResolveError::MixedArray { expected } => {
+                write!(f, "array literal mixes bool and {} elements", expected)
+            }
+            ResolveError::BoolObjective(id) => {
+                write!(f, "objective `{}` is a var bool", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+impl Model {
+    /// Rewrite the arguments of all constraints into their declared types.
+    ///
+    /// The parser cannot tell an array of int variables from an array of bool
+    /// variables, so such arrays are always parsed as [`Expr::ArrayOfBool`].
+    /// This pass looks up the identifiers and turns the array into
+    /// [`Expr::ArrayOfInt`], [`Expr::ArrayOfFloat`] or [`Expr::ArrayOfSet`],
+    /// and checks that all other identifiers are declared with a fitting type.
+    ///
+    /// Likewise, an objective identifier is always parsed as
+    /// [`Goal::OptimizeBool`]; it is turned into the goal matching its
+    /// declaration. Objectives that are undeclared, arrays or `var bool` are
+    /// rejected. On error the model is left unchanged.
+    pub fn resolve(&mut self) -> Result<(), ResolveError> {
+        let constraints = self
+            .constraints
+            .iter()
+            .map(|c| self.resolve_constraint(c))
+            .collect::<Result<_, _>>()?;
+        let goal = self.resolve_goal(&self.solve.goal)?;
+        self.constraints = constraints;
+        self.solve = SolveItem {
+            goal,
+            annotations: std::mem::take(&mut self.solve.annotations),
+        };
+        Ok(())
+    }
+
+    /// Resolve the objective of a solve item, see [`Model::resolve`].
+    pub fn resolve_goal(&self, goal: &Goal) -> Result<Goal, ResolveError> {
+        let (opt_type, id) = match goal {
+            Goal::OptimizeBool(opt_type, BoolExpr::VarParIdentifier(id)) => (opt_type, id),
+            Goal::OptimizeInt(_, IntExpr::VarParIdentifier(id)) => {
+                self.check_scalar(id, ScalarType::Int)?;
+                return Ok(goal.clone());
+            }
+            Goal::OptimizeFloat(_, FloatExpr::VarParIdentifier(id)) => {
+                self.check_scalar(id, ScalarType::Float)?;
+                return Ok(goal.clone());
+            }
+            Goal::OptimizeSet(_, SetExpr::VarParIdentifier(id)) => {
+                self.check_scalar(id, ScalarType::SetOfInt)?;
+                return Ok(goal.clone());
+            }
+            Goal::OptimizeSet(_, SetExpr::Set(set)) => {
+                self.check_set_literal(set)?;
+                return Ok(goal.clone());
+            }
+            _ => return Ok(goal.clone()),
+        };
+        let ty = self.lookup(id)?;
+        if ty.array {
+            return Err(ResolveError::TypeMismatch {
+                id: id.clone(),
+                expected: ty.scalar,
+                found: ty,
+            });
+        }
+        let opt_type = opt_type.clone();
+        let id = id.clone();
+        Ok(match ty.scalar {
+            ScalarType::Bool if ty.var => return Err(ResolveError::BoolObjective(id)),
+            ScalarType::Bool => Goal::OptimizeBool(opt_type, BoolExpr::VarParIdentifier(id)),
+            ScalarType::Int => Goal::OptimizeInt(opt_type, IntExpr::VarParIdentifier(id)),
+            ScalarType::Float => Goal::OptimizeFloat(opt_type, FloatExpr::VarParIdentifier(id)),
+            ScalarType::SetOfInt => Goal::OptimizeSet(opt_type, SetExpr::VarParIdentifier(id)),
+        })
+    }
+
+    fn resolve_constraint(&self, c: &ConstraintItem) -> Result<ConstraintItem, ResolveError> {
+        let exprs = c
+            .exprs
+            .iter()
+            .map(|e| self.resolve_expr(e))
+            .collect::<Result<_, _>>()?;
+        Ok(ConstraintItem {
+            id: c.id.clone(),
+            exprs,
+            annos: c.annos.clone(),
+        })
+    }
+
+    /// Resolve a single expression, see [`Model::resolve`].
+    pub fn resolve_expr(&self, expr: &Expr) -> Result<Expr, ResolveError> {
+        match expr {
+            Expr::VarParIdentifier(id) => {
+                self.lookup(id)?;
+                Ok(expr.clone())
+            }
+            Expr::Bool(_) | Expr::Int(_) | Expr::Float(_) => Ok(expr.clone()),
+            Expr::Set(set) => {
+                self.check_set_literal(set)?;
+                Ok(expr.clone())
+            }
+            Expr::ArrayOfBool(v) => self.resolve_array_of_bool(v),
+            Expr::ArrayOfInt(v) => {
+                for e in v {
+                    if let IntExpr::VarParIdentifier(id) = e {
+                        self.check_scalar(id, ScalarType::Int)?;
+                    }
+                }
+                Ok(expr.clone())
+            }
+            Expr::ArrayOfFloat(v) => {
+                for e in v {
+                    if let FloatExpr::VarParIdentifier(id) = e {
+                        self.check_scalar(id, ScalarType::Float)?;
+                    }
+                }
+                Ok(expr.clone())
+            }
+            Expr::ArrayOfSet(v) => {
+                for e in v {
+                    match e {
+                        SetExpr::Set(set) => self.check_set_literal(set)?,
+                        SetExpr::VarParIdentifier(id) => {
+                            self.check_scalar(id, ScalarType::SetOfInt)?
+                        }
+                    }
+                }
+                Ok(expr.clone())
+            }
+        }
+    }
+
+    fn resolve_array_of_bool(&self, v: &[BoolExpr]) -> Result<Expr, ResolveError> {
+        // the element type is decided by the first identifier in the array
+        let first_id = v.iter().find_map(|e| match e {
+            BoolExpr::VarParIdentifier(id) => Some(id),
+            BoolExpr::Bool(_) => None,
+        });
+        let Some(first_id) = first_id else {
+            return Ok(Expr::ArrayOfBool(v.to_vec()));
+        };
+        let ty = self.lookup(first_id)?;
+        if ty.array {
+            return Err(ResolveError::TypeMismatch {
+                id: first_id.clone(),
+                expected: ty.scalar,
+                found: ty,
+            });
+        }
+        let mut ids = vec![];
+        for e in v {
+            match e {
+                BoolExpr::VarParIdentifier(id) => {
+                    self.check_scalar(id, ty.scalar)?;
+                    ids.push(id.clone());
+                }
+                BoolExpr::Bool(_) if ty.scalar != ScalarType::Bool => {
+                    return Err(ResolveError::MixedArray {
+                        expected: ty.scalar,
+                    })
+                }
+                BoolExpr::Bool(_) => {}
+            }
+        }
+        let ids = ids.into_iter();
+        Ok(match ty.scalar {
+            ScalarType::Bool => Expr::ArrayOfBool(v.to_vec()),
+            ScalarType::Int => Expr::ArrayOfInt(ids.map(IntExpr::VarParIdentifier).collect()),
+            ScalarType::Float => Expr::ArrayOfFloat(ids.map(FloatExpr::VarParIdentifier).collect()),
+            ScalarType::SetOfInt => Expr::ArrayOfSet(ids.map(SetExpr::VarParIdentifier).collect()),
+        })
+    }
+
+    fn check_set_literal(&self, set: &SetLiteralExpr) -> Result<(), ResolveError> {
+        let mut ints = vec![];
+        let mut floats = vec![];
+        match set {
+            SetLiteralExpr::IntInRange(lb, ub) => ints.extend([lb, ub]),
+            SetLiteralExpr::SetInts(v) => ints.extend(v),
+            SetLiteralExpr::BoundedFloat(lb, ub) => floats.extend([lb, ub]),
+            SetLiteralExpr::SetFloats(v) => floats.extend(v),
+        }
+        for e in ints {
+            if let IntExpr::VarParIdentifier(id) = e {
+                self.check_scalar(id, ScalarType::Int)?;
+            }
+        }
+        for e in floats {
+            if let FloatExpr::VarParIdentifier(id) = e {
+                self.check_scalar(id, ScalarType::Float)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, id: &str) -> Result<DeclType, ResolveError> {
+        self.type_of(id)
+            .ok_or_else(|| ResolveError::Undeclared(id.to_string()))
+    }
+
+    /// Check that `id` is a declared non-array identifier of type `expected`.
+    fn check_scalar(&self, id: &str, expected: ScalarType) -> Result<(), ResolveError> {
+        let found = self.lookup(id)?;
+        if found.array || found.scalar != expected {
+            Err(ResolveError::TypeMismatch {
+                id: id.to_string(),
+                expected,
+                found,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+#[test]
+fn test_resolve_constraints() {
+    let mut model: Model = "var int: INT01;\nint: p = 3;\nvar float: X_27;\nvar float: X_28;\n\
+        var bool: b;\n\
+        constraint int_lin_eq([-1, 1], [INT01, p], -3);\n\
+        constraint float_lin_eq([1.0, 1.0], [X_27, X_28], 1.0);\n\
+        constraint array_bool_or([b, true], true);\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    model.resolve().unwrap();
+    let c = model.constraints();
+    assert_eq!(
+        c[0].exprs[1],
+        Expr::ArrayOfInt(vec![
+            IntExpr::VarParIdentifier("INT01".to_string()),
+            IntExpr::VarParIdentifier("p".to_string())
+        ])
+    );
+    assert_eq!(
+        c[1].exprs[1],
+        Expr::ArrayOfFloat(vec![
+            FloatExpr::VarParIdentifier("X_27".to_string()),
+            FloatExpr::VarParIdentifier("X_28".to_string())
+        ])
+    );
+    assert_eq!(
+        c[2].exprs[0],
+        Expr::ArrayOfBool(vec![
+            BoolExpr::VarParIdentifier("b".to_string()),
+            BoolExpr::Bool(true)
+        ])
+    );
+}
+#[test]
+fn test_resolve_errors() {
+    let parse = |s: &str| s.parse::<Model>().unwrap();
+    let mut model = parse("var int: x;\nconstraint int_lin_eq([1], [x, y], 0);\nsolve satisfy;");
+    let before = model.clone();
+    assert_eq!(
+        model.resolve(),
+        Err(ResolveError::Undeclared("y".to_string()))
+    );
+    assert_eq!(model, before);
+    let mut model = parse("var int: x;\nvar float: y;\nconstraint foo([x, y]);\nsolve satisfy;");
+    assert_eq!(
+        model.resolve(),
+        Err(ResolveError::TypeMismatch {
+            id: "y".to_string(),
+            expected: ScalarType::Int,
+            found: DeclType {
+                scalar: ScalarType::Float,
+                array: false,
+                var: true
+            }
+        })
+    );
+    let mut model = parse("var int: x;\nconstraint foo([x, true]);\nsolve satisfy;");
+    assert_eq!(
+        model.resolve(),
+        Err(ResolveError::MixedArray {
+            expected: ScalarType::Int
+        })
+    );
+}
+#[test]
+fn test_resolve_goal() {
+    use crate::solve_items::OptimizationType;
+    let resolve = |s: &str| {
+        let mut model: Model = s.parse().unwrap();
+        model.resolve().map(|_| model.solve().goal.clone())
+    };
+    assert_eq!(
+        resolve("var 0..10: X_24;\nsolve minimize X_24;"),
+        Ok(Goal::OptimizeInt(
+            OptimizationType::Minimize,
+            IntExpr::VarParIdentifier("X_24".to_string())
+        ))
+    );
+    assert_eq!(
+        resolve("var float: f;\nsolve maximize f;"),
+        Ok(Goal::OptimizeFloat(
+            OptimizationType::Maximize,
+            FloatExpr::VarParIdentifier("f".to_string())
+        ))
+    );
+    assert_eq!(
+        resolve("var set of 1..3: s;\nsolve maximize s;"),
+        Ok(Goal::OptimizeSet(
+            OptimizationType::Maximize,
+            SetExpr::VarParIdentifier("s".to_string())
+        ))
+    );
+    assert_eq!(
+        resolve("bool: b = true;\nsolve maximize b;"),
+        Ok(Goal::OptimizeBool(
+            OptimizationType::Maximize,
+            BoolExpr::VarParIdentifier("b".to_string())
+        ))
+    );
+    assert_eq!(resolve("solve satisfy;"), Ok(Goal::Satisfy));
+    assert_eq!(
+        resolve("var bool: b;\nsolve maximize b;"),
+        Err(ResolveError::BoolObjective("b".to_string()))
+    );
+    assert_eq!(
+        resolve("solve minimize X_24;"),
+        Err(ResolveError::Undeclared("X_24".to_string()))
+    );
+}
+#[test]
+fn test_resolve_jobshop() {
+    let mut model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    model.resolve().unwrap();
+    for c in model.constraints() {
+        if c.id.starts_with("int_lin") {
+            assert!(matches!(c.exprs[1], Expr::ArrayOfInt(_)));
+        } else if c.id == "array_bool_or" {
+            assert!(matches!(c.exprs[0], Expr::ArrayOfBool(_)));
+        }
+    }
+    assert!(matches!(model.solve().goal, Goal::OptimizeInt(..)));
+}
@@ -0,0 +1,383 @@
+//! Typed view of the search annotations of a solve item.
+
+use std::fmt;
+
+use crate::{
+    expressions::{AnnExpr, Annotation, Expr},
+    solve_items::SolveItem,
+};
+
+/// A search strategy given by an `int_search`, `bool_search`, `float_search`,
+/// `set_search` or `seq_search` annotation.
+#[derive(PartialEq, Clone, Debug)]
+pub enum SearchStrategy {
+    Int(VarSearch),
+    Bool(VarSearch),
+    Float {
+        search: VarSearch,
+        precision: f64,
+    },
+    Set(VarSearch),
+    /// Strategies that are applied one after the other
+    Seq(Vec<SearchStrategy>),
+}
+
+/// The arguments shared by all searches over an array of variables.
+#[derive(PartialEq, Clone, Debug)]
+pub struct VarSearch {
+    /// The variables to branch on, either the identifier of an array or an array literal
+    pub vars: AnnExpr,
+    pub var_choice: VarChoice,
+    pub value_choice: ValueChoice,
+    pub exploration: Exploration,
+}
+
+/// How to choose the next variable to branch on.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum VarChoice {
+    InputOrder,
+    FirstFail,
+    AntiFirstFail,
+    Smallest,
+    Largest,
+    Occurrence,
+    MostConstrained,
+    MaxRegret,
+    DomWDeg,
+}
+
+impl VarChoice {
+    pub fn from_name(name: &str) -> Option<VarChoice> {
+        Some(match name {
+            "input_order" => VarChoice::InputOrder,
+            "first_fail" => VarChoice::FirstFail,
+            "anti_first_fail" => VarChoice::AntiFirstFail,
+            "smallest" => VarChoice::Smallest,
+            "largest" => VarChoice::Largest,
+            "occurrence" => VarChoice::Occurrence,
+            "most_constrained" => VarChoice::MostConstrained,
+            "max_regret" => VarChoice::MaxRegret,
+            "dom_w_deg" => VarChoice::DomWDeg,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VarChoice::InputOrder => "input_order",
+            VarChoice::FirstFail => "first_fail",
+            VarChoice::AntiFirstFail => "anti_first_fail",
+            VarChoice::Smallest => "smallest",
+            VarChoice::Largest => "largest",
+            VarChoice::Occurrence => "occurrence",
+            VarChoice::MostConstrained => "most_constrained",
+            VarChoice::MaxRegret => "max_regret",
+            VarChoice::DomWDeg => "dom_w_deg",
+        }
+    }
+}
+
+/// How to constrain the chosen variable.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ValueChoice {
+    IndomainMin,
+    IndomainMax,
+    IndomainMiddle,
+    IndomainMedian,
+    Indomain,
+    IndomainRandom,
+    IndomainSplit,
+    IndomainReverseSplit,
+    IndomainInterval,
+    OutdomainMin,
+    OutdomainMax,
+    OutdomainMedian,
+    OutdomainRandom,
+}
+
+impl ValueChoice {
+    pub fn from_name(name: &str) -> Option<ValueChoice> {
+        Some(match name {
+            "indomain_min" => ValueChoice::IndomainMin,
+            "indomain_max" => ValueChoice::IndomainMax,
+            "indomain_middle" => ValueChoice::IndomainMiddle,
+            "indomain_median" => ValueChoice::IndomainMedian,
+            "indomain" => ValueChoice::Indomain,
+            "indomain_random" => ValueChoice::IndomainRandom,
+            "indomain_split" => ValueChoice::IndomainSplit,
+            "indomain_reverse_split" => ValueChoice::IndomainReverseSplit,
+            "indomain_interval" => ValueChoice::IndomainInterval,
+            "outdomain_min" => ValueChoice::OutdomainMin,
+            "outdomain_max" => ValueChoice::OutdomainMax,
+            "outdomain_median" => ValueChoice::OutdomainMedian,
+            "outdomain_random" => ValueChoice::OutdomainRandom,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueChoice::IndomainMin => "indomain_min",
+            ValueChoice::IndomainMax => "indomain_max",
+            ValueChoice::IndomainMiddle => "indomain_middle",
+            ValueChoice::IndomainMedian => "indomain_median",
+            ValueChoice::Indomain => "indomain",
+            ValueChoice::IndomainRandom => "indomain_random",
+            ValueChoice::IndomainSplit => "indomain_split",
+            ValueChoice::IndomainReverseSplit => "indomain_reverse_split",
+            ValueChoice::IndomainInterval => "indomain_interval",
+            ValueChoice::OutdomainMin => "outdomain_min",
+            ValueChoice::OutdomainMax => "outdomain_max",
+            ValueChoice::OutdomainMedian => "outdomain_median",
+            ValueChoice::OutdomainRandom => "outdomain_random",
+        }
+    }
+}
+
+/// How to explore the search tree. FlatZinc only defines complete search.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Exploration {
+    Complete,
+}
+
+impl Exploration {
+    pub fn from_name(name: &str) -> Option<Exploration> {
+        match name {
+            "complete" => Some(Exploration::Complete),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Exploration::Complete => "complete",
+        }
+    }
+}
+
+/// An error produced while converting an [`Annotation`] into a [`SearchStrategy`].
+#[derive(PartialEq, Clone, Debug)]
+pub enum SearchError {
+    /// The annotation is not a search annotation
+    NotASearch(String),
+    WrongArity {
+        annotation: String,
+        expected: usize,
+        found: usize,
+    },
+    UnknownVarChoice(String),
+    UnknownValueChoice(String),
+    UnknownExploration(String),
+    /// An argument has the wrong kind, like a number where a strategy name is expected
+    InvalidArgument {
+        annotation: String,
+        position: usize,
+    },
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::NotASearch(id) => write!(f, "`{}` is not a search annotation", id),
+            SearchError::WrongArity {
+                annotation,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{}` expects {} arguments, found {}",
+                annotation, expected, found
+            ),
+            SearchError::UnknownVarChoice(name) => {
+                write!(f, "unknown variable choice strategy `{}`", name)
+            }
+            SearchError::UnknownValueChoice(name) => {
+                write!(f, "unknown value choice strategy `{}`", name)
+            }
+            SearchError::UnknownExploration(name) => {
+                write!(f, "unknown exploration strategy `{}`", name)
+            }
+            SearchError::InvalidArgument {
+                annotation,
+                position,
+            } => write!(f, "invalid argument {} of `{}`", position + 1, annotation),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+const SEARCH_ANNOTATIONS: [&str; 5] = [
+    "int_search",
+    "bool_search",
+    "float_search",
+    "set_search",
+    "seq_search",
+];
+
+impl TryFrom<&Annotation> for SearchStrategy {
+    type Error = SearchError;
+
+    fn try_from(anno: &Annotation) -> Result<Self, Self::Error> {
+        let args = &anno.expressions;
+        let arity = match anno.id.as_str() {
+            "seq_search" => 1,
+            "float_search" => 5,
+            "int_search" | "bool_search" | "set_search" => 4,
+            _ => return Err(SearchError::NotASearch(anno.id.clone())),
+        };
+        if args.len() != arity {
+            return Err(SearchError::WrongArity {
+                annotation: anno.id.clone(),
+                expected: arity,
+                found: args.len(),
+            });
+        }
+        let invalid = |position| SearchError::InvalidArgument {
+            annotation: anno.id.clone(),
+            position,
+        };
+        if anno.id == "seq_search" {
+            return match &args[0] {
+                AnnExpr::Annotations(annos) => Ok(SearchStrategy::Seq(
+                    annos
+                        .iter()
+                        .map(SearchStrategy::try_from)
+                        .collect::<Result<_, _>>()?,
+                )),
+                AnnExpr::Expr(Expr::ArrayOfBool(v)) if v.is_empty() => {
+                    Ok(SearchStrategy::Seq(vec![]))
+                }
+                _ => Err(invalid(0)),
+            };
+        }
+        // the precision of float_search comes between the variables and the strategies
+        let names = &args[arity - 3..];
+        let name = |position: usize| match &names[position] {
+            AnnExpr::Expr(Expr::VarParIdentifier(name)) => Ok(name.as_str()),
+            _ => Err(invalid(arity - 3 + position)),
+        };
+        let var_choice = name(0)?;
+        let value_choice = name(1)?;
+        let exploration = name(2)?;
+        let search = VarSearch {
+            vars: args[0].clone(),
+            var_choice: VarChoice::from_name(var_choice)
+                .ok_or_else(|| SearchError::UnknownVarChoice(var_choice.to_string()))?,
+            value_choice: ValueChoice::from_name(value_choice)
+                .ok_or_else(|| SearchError::UnknownValueChoice(value_choice.to_string()))?,
+            exploration: Exploration::from_name(exploration)
+                .ok_or_else(|| SearchError::UnknownExploration(exploration.to_string()))?,
+        };
+        Ok(match anno.id.as_str() {
+            "int_search" => SearchStrategy::Int(search),
+            "bool_search" => SearchStrategy::Bool(search),
+            "set_search" => SearchStrategy::Set(search),
+            _ => {
+                let precision = match &args[1] {
+                    AnnExpr::Expr(Expr::Float(precision)) => *precision,
+                    AnnExpr::Expr(Expr::Int(precision)) => *precision as f64,
+                    _ => return Err(invalid(1)),
+                };
+                SearchStrategy::Float { search, precision }
+            }
+        })
+    }
+}
+
+impl SolveItem {
+    /// Convert the search annotations of the solve item, ignoring all other annotations.
+    pub fn search(&self) -> Result<Vec<SearchStrategy>, SearchError> {
+        self.annotations
+            .iter()
+            .filter(|anno| SEARCH_ANNOTATIONS.contains(&anno.id.as_str()))
+            .map(SearchStrategy::try_from)
+            .collect()
+    }
+}
+#[test]
+fn test_search_jobshop() {
+    use crate::model::Model;
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    assert_eq!(
+        model.solve().search(),
+        Ok(vec![SearchStrategy::Int(VarSearch {
+            vars: AnnExpr::Expr(Expr::VarParIdentifier("X_INTRODUCED_59_".to_string())),
+            var_choice: VarChoice::InputOrder,
+            value_choice: ValueChoice::IndomainMin,
+            exploration: Exploration::Complete,
+        })])
+    );
+}
+#[test]
+fn test_search_seq() {
+    use crate::statements::Stmt;
+    let stmt: Stmt = "solve :: restart_luby(100) :: seq_search([bool_search([b],first_fail,indomain_max,complete),float_search([f],0.01,smallest,indomain_split,complete)]) satisfy;"
+        .parse()
+        .unwrap();
+    let Stmt::SolveItem(solve) = stmt else {
+        panic!("expected a solve item");
+    };
+    let search = solve.search().unwrap();
+    let [SearchStrategy::Seq(seq)] = &search[..] else {
+        panic!("expected a single seq_search");
+    };
+    assert!(matches!(
+        &seq[0],
+        SearchStrategy::Bool(VarSearch {
+            var_choice: VarChoice::FirstFail,
+            value_choice: ValueChoice::IndomainMax,
+            ..
+        })
+    ));
+    assert!(matches!(
+        &seq[1],
+        SearchStrategy::Float {
+            search: VarSearch {
+                var_choice: VarChoice::Smallest,
+                value_choice: ValueChoice::IndomainSplit,
+                ..
+            },
+            precision,
+        } if *precision == 0.01
+    ));
+}
+#[test]
+fn test_search_errors() {
+    use crate::statements::Stmt;
+    let search = |input: &str| {
+        let Ok(Stmt::SolveItem(solve)) = input.parse::<Stmt>() else {
+            panic!("expected a solve item");
+        };
+        SearchStrategy::try_from(&solve.annotations[0])
+    };
+    assert_eq!(
+        search("solve :: int_search(x,input_order,indomain_min) satisfy;"),
+        Err(SearchError::WrongArity {
+            annotation: "int_search".to_string(),
+            expected: 4,
+            found: 3
+        })
+    );
+    let e = search("solve :: int_search(x,worst_fail,indomain_min,complete) satisfy;").unwrap_err();
+    assert_eq!(e, SearchError::UnknownVarChoice("worst_fail".to_string()));
+    assert_eq!(
+        e.to_string(),
+        "unknown variable choice strategy `worst_fail`"
+    );
+    assert_eq!(
+        search("solve :: set_search(x,input_order,indomain_min,lds) satisfy;"),
+        Err(SearchError::UnknownExploration("lds".to_string()))
+    );
+    assert_eq!(
+        search("solve :: int_search(x,1,indomain_min,complete) satisfy;"),
+        Err(SearchError::InvalidArgument {
+            annotation: "int_search".to_string(),
+            position: 1
+        })
+    );
+    assert_eq!(
+        search("solve :: warm_start(x,1) satisfy;"),
+        Err(SearchError::NotASearch("warm_start".to_string()))
+    );
+}
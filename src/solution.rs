@@ -0,0 +1,418 @@
+//! Printing solutions in the standard FlatZinc output format.
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use crate::{
+    expressions::{Expr, IntExpr, SetLiteral, SetLiteralExpr},
+    model::Model,
+    output::{OutputError, OutputKind},
+    parameters::declarations::ParDeclItem,
+    variables::declarations::VarDeclItem,
+};
+
+/// The value of a variable in a solution.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Value {
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    /// A set of integers as sorted, disjoint and non-adjacent ranges
+    /// `(lb, ub)`, see [`Value::set`]
+    Set(Vec<(i128, i128)>),
+}
+
+impl Value {
+    /// The set of integers that is the union of the ranges `(lb, ub)`.
+    ///
+    /// Empty ranges are dropped and overlapping or adjacent ranges merged.
+    pub fn set(ranges: impl IntoIterator<Item = (i128, i128)>) -> Value {
+        Value::Set(set_ranges(ranges))
+    }
+}
+
+/// Sets with more elements are printed as a union of ranges.
+const MAX_LISTED_ELEMENTS: i128 = 64;
+
+/// The number of elements of a set value, if it fits into an `i128`.
+pub(crate) fn set_card(set: &[(i128, i128)]) -> Option<i128> {
+    set.iter().try_fold(0i128, |len, (lb, ub)| {
+        len.checked_add(ub.checked_sub(*lb)?.checked_add(1)?)
+    })
+}
+
+/// The sorted, disjoint and non-adjacent ranges covering the same integers as `ranges`.
+pub(crate) fn set_ranges(ranges: impl IntoIterator<Item = (i128, i128)>) -> Vec<(i128, i128)> {
+    let mut ranges: Vec<_> = ranges.into_iter().filter(|(lb, ub)| lb <= ub).collect();
+    ranges.sort_unstable();
+    let mut set: Vec<(i128, i128)> = Vec::with_capacity(ranges.len());
+    for (lb, ub) in ranges {
+        match set.last_mut() {
+            Some((_, last)) if lb <= last.saturating_add(1) => *last = ub.max(*last),
+            _ => set.push((lb, ub)),
+        }
+    }
+    set
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(float) => write!(f, "{:?}", float),
+            Value::Set(set) => match set[..] {
+                [(lb, ub)] if lb < ub => write!(f, "{}..{}", lb, ub),
+                _ if set_card(set).is_some_and(|len| len <= MAX_LISTED_ELEMENTS) => {
+                    let set: Vec<_> = set
+                        .iter()
+                        .flat_map(|(lb, ub)| *lb..=*ub)
+                        .map(|i| i.to_string())
+                        .collect();
+                    write!(f, "{{{}}}", set.join(", "))
+                }
+                _ => {
+                    let set: Vec<_> = set
+                        .iter()
+                        .map(|(lb, ub)| format!("{}..{}", lb, ub))
+                        .collect();
+                    write!(f, "{}", set.join(" union "))
+                }
+            },
+        }
+    }
+}
+
+/// The final status of a search, printed after the last solution.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Status {
+    /// All solutions have been found or the last solution is optimal
+    Complete,
+    Unsatisfiable,
+    Unbounded,
+    UnsatisfiableOrUnbounded,
+    Unknown,
+    Error,
+}
+
+impl Status {
+    /// The status printed as `marker`, like `==========` for [`Status::Complete`].
+    pub fn from_marker(marker: &str) -> Option<Status> {
+        [
+            Status::Complete,
+            Status::Unsatisfiable,
+            Status::Unbounded,
+            Status::UnsatisfiableOrUnbounded,
+            Status::Unknown,
+            Status::Error,
+        ]
+        .into_iter()
+        .find(|status| status.to_string() == marker)
+    }
+}
+
+/// The line printed after each solution.
+pub const SOLUTION_SEPARATOR: &str = "----------";
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Complete => write!(f, "=========="),
+            Status::Unsatisfiable => write!(f, "=====UNSATISFIABLE====="),
+            Status::Unbounded => write!(f, "=====UNBOUNDED====="),
+            Status::UnsatisfiableOrUnbounded => write!(f, "=====UNSATorUNBOUNDED====="),
+            Status::Unknown => write!(f, "=====UNKNOWN====="),
+            Status::Error => write!(f, "=====ERROR====="),
+        }
+    }
+}
+
+/// An error produced while printing a solution.
+#[derive(PartialEq, Clone, Debug)]
+pub enum SolutionError {
+    Output(OutputError),
+    /// No value was given for the variable
+    MissingValue(String),
+    /// The value cannot be printed, like a set of floats
+    Unsupported(String),
+}
+
+impl fmt::Display for SolutionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SolutionError::Output(e) => write!(f, "{}", e),
+            SolutionError::MissingValue(id) => write!(f, "missing value of `{}`", id),
+            SolutionError::Unsupported(id) => write!(f, "cannot print the value of `{}`", id),
+        }
+    }
+}
+
+impl std::error::Error for SolutionError {}
+
+impl From<OutputError> for SolutionError {
+    fn from(e: OutputError) -> Self {
+        SolutionError::Output(e)
+    }
+}
+
+impl Model {
+    /// Format the output variables of a solution, followed by [`SOLUTION_SEPARATOR`].
+    ///
+    /// `value` is asked for the values of single variables; the elements of
+    /// output arrays are looked up through the array declarations.
+    pub fn format_solution(
+        &self,
+        value: impl Fn(&str) -> Option<Value>,
+    ) -> Result<String, SolutionError> {
+        let mut out = String::new();
+        for output in self.output_vars()? {
+            let id = output.id.as_str();
+            match output.kind {
+                OutputKind::Var => {
+                    let v = self.var_value(id, &value)?;
+                    writeln!(out, "{} = {};", id, v).unwrap();
+                }
+                OutputKind::Array(dims) => {
+                    let elements =
+                        self.array_values(&Expr::VarParIdentifier(id.to_string()), &value)?;
+                    let elements: Vec<_> = elements.iter().map(|v| v.to_string()).collect();
+                    let dims: Vec<_> = dims
+                        .iter()
+                        .map(|(lb, ub)| format!("{}..{}", lb, ub))
+                        .collect();
+                    writeln!(
+                        out,
+                        "{} = array{}d({}, [{}]);",
+                        id,
+                        dims.len(),
+                        dims.join(", "),
+                        elements.join(", ")
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        writeln!(out, "{}", SOLUTION_SEPARATOR).unwrap();
+        Ok(out)
+    }
+
+    /// The values of an array literal or of a declared array.
+    pub(crate) fn array_values(
+        &self,
+        e: &Expr,
+        value: &impl Fn(&str) -> Option<Value>,
+    ) -> Result<Vec<Value>, SolutionError> {
+        let elements: Vec<Expr> = match e {
+            Expr::ArrayOfBool(v) => v.iter().cloned().map(Expr::from).collect(),
+            Expr::ArrayOfInt(v) => v.iter().cloned().map(Expr::from).collect(),
+            Expr::ArrayOfFloat(v) => v.iter().cloned().map(Expr::from).collect(),
+            Expr::ArrayOfSet(v) => v.iter().cloned().map(Expr::from).collect(),
+            Expr::VarParIdentifier(id) => match self.par(id) {
+                Some(par) => return par_array_values(par),
+                None => self.array_elements(id)?,
+            },
+            _ => return Err(SolutionError::Unsupported(e.to_string())),
+        };
+        elements.iter().map(|e| self.value_of(e, value)).collect()
+    }
+
+    /// The elements of a variable array, following references to other arrays.
+    pub(crate) fn array_elements(&self, id: &str) -> Result<Vec<Expr>, SolutionError> {
+        let item = self
+            .var(id)
+            .ok_or_else(|| SolutionError::MissingValue(id.to_string()))?;
+        Ok(match assigned_array(item) {
+            Some(Expr::ArrayOfBool(v)) => v.into_iter().map(Expr::from).collect(),
+            Some(Expr::ArrayOfInt(v)) => v.into_iter().map(Expr::from).collect(),
+            Some(Expr::ArrayOfFloat(v)) => v.into_iter().map(Expr::from).collect(),
+            Some(Expr::ArrayOfSet(v)) => v.into_iter().map(Expr::from).collect(),
+            Some(Expr::VarParIdentifier(other)) => return self.array_elements(&other),
+            _ => return Err(SolutionError::MissingValue(id.to_string())),
+        })
+    }
+
+    /// The value of a literal or of the identifier of a variable or parameter.
+    ///
+    /// A variable without a value falls back to the expression it is assigned.
+    pub(crate) fn value_of(
+        &self,
+        e: &Expr,
+        value: &impl Fn(&str) -> Option<Value>,
+    ) -> Result<Value, SolutionError> {
+        Ok(match e {
+            Expr::Bool(b) => Value::Bool(*b),
+            Expr::Int(i) => Value::Int(*i),
+            Expr::Float(f) => Value::Float(*f),
+            Expr::Set(SetLiteralExpr::IntInRange(IntExpr::Int(lb), IntExpr::Int(ub))) => {
+                Value::set([(*lb, *ub)])
+            }
+            Expr::Set(SetLiteralExpr::SetInts(v)) => Value::set(
+                v.iter()
+                    .map(|i| match i {
+                        IntExpr::Int(i) => Ok((*i, *i)),
+                        IntExpr::VarParIdentifier(id) => {
+                            Err(SolutionError::Unsupported(id.clone()))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Expr::VarParIdentifier(id) => match self.par(id) {
+                Some(par) => par_value(par)?,
+                None => self.var_value(id, value)?,
+            },
+            _ => return Err(SolutionError::Unsupported(e.to_string())),
+        })
+    }
+
+    /// The value of a variable, following its assignment if no value is given.
+    pub(crate) fn var_value(
+        &self,
+        id: &str,
+        value: &impl Fn(&str) -> Option<Value>,
+    ) -> Result<Value, SolutionError> {
+        let mut current = id.to_string();
+        // a chain of aliases is at most as long as the list of variables
+        for _ in 0..=self.vars.len() {
+            if let Some(v) = value(&current) {
+                return Ok(v);
+            }
+            match self.var(&current).and_then(assigned_expr) {
+                Some(Expr::VarParIdentifier(next)) if self.par(&next).is_none() => current = next,
+                Some(e) => return self.value_of(&e, value),
+                None => break,
+            }
+        }
+        Err(SolutionError::MissingValue(id.to_string()))
+    }
+}
+
+/// The expression assigned to a single variable.
+pub(crate) fn assigned_expr(item: &VarDeclItem) -> Option<Expr> {
+    match item {
+        VarDeclItem::Bool { expr, .. } => expr.clone().map(Expr::from),
+        VarDeclItem::Int { expr, .. }
+        | VarDeclItem::IntInRange { expr, .. }
+        | VarDeclItem::IntInSet { expr, .. } => expr.clone().map(Expr::from),
+        VarDeclItem::Float { expr, .. }
+        | VarDeclItem::BoundedFloat { expr, .. }
+        | VarDeclItem::FloatInSet { expr, .. } => expr.clone().map(Expr::from),
+        VarDeclItem::SetOfInt { expr, .. }
+        | VarDeclItem::SubSetOfIntSet { expr, .. }
+        | VarDeclItem::SubSetOfIntRange { expr, .. } => expr.clone().map(Expr::from),
+        _ => None,
+    }
+}
+
+/// The expression assigned to an array of variables.
+pub(crate) fn assigned_array(item: &VarDeclItem) -> Option<Expr> {
+    match item {
+        VarDeclItem::ArrayOfBool { array_expr, .. } => array_expr.clone().map(Expr::from),
+        VarDeclItem::ArrayOfInt { array_expr, .. }
+        | VarDeclItem::ArrayOfIntInRange { array_expr, .. }
+        | VarDeclItem::ArrayOfIntInSet { array_expr, .. } => array_expr.clone().map(Expr::from),
+        VarDeclItem::ArrayOfFloat { array_expr, .. }
+        | VarDeclItem::ArrayOfBoundedFloat { array_expr, .. }
+        | VarDeclItem::ArrayOfFloatInSet { array_expr, .. } => array_expr.clone().map(Expr::from),
+        VarDeclItem::ArrayOfSet { array_expr, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntRange { array_expr, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntSet { array_expr, .. } => {
+            array_expr.clone().map(Expr::from)
+        }
+        _ => None,
+    }
+}
+
+fn par_array_values(par: &ParDeclItem) -> Result<Vec<Value>, SolutionError> {
+    Ok(match par {
+        ParDeclItem::ArrayOfBool { v, .. } => v.iter().map(|b| Value::Bool(*b)).collect(),
+        ParDeclItem::ArrayOfInt { v, .. } => v.iter().map(|i| Value::Int(*i)).collect(),
+        ParDeclItem::ArrayOfFloat { v, .. } => v.iter().map(|f| Value::Float(*f)).collect(),
+        ParDeclItem::ArrayOfSet { v, .. } => v
+            .iter()
+            .map(|set| {
+                set_value(set).ok_or_else(|| SolutionError::Unsupported(par.id().to_string()))
+            })
+            .collect::<Result<_, _>>()?,
+        _ => return Err(SolutionError::Unsupported(par.id().to_string())),
+    })
+}
+
+fn set_value(set: &SetLiteral) -> Option<Value> {
+    match set {
+        SetLiteral::IntRange(lb, ub) => Some(Value::set([(*lb, *ub)])),
+        SetLiteral::SetInts(v) => Some(Value::set(v.iter().map(|i| (*i, *i)))),
+        _ => None,
+    }
+}
+
+fn par_value(par: &ParDeclItem) -> Result<Value, SolutionError> {
+    Ok(match par {
+        ParDeclItem::Bool { bool, .. } => Value::Bool(*bool),
+        ParDeclItem::Int { int, .. } => Value::Int(*int),
+        ParDeclItem::Float { float, .. } => Value::Float(*float),
+        ParDeclItem::SetOfInt { set_literal, .. } => set_value(set_literal)
+            .ok_or_else(|| SolutionError::Unsupported(par.id().to_string()))?,
+        _ => return Err(SolutionError::Unsupported(par.id().to_string())),
+    })
+}
+#[test]
+fn test_format_solution() {
+    let model: Model = "int: two = 2;\n\
+        var 0..3: x :: output_var;\n\
+        var 0..3: y;\n\
+        var bool: b :: output_var;\n\
+        var 0.0..1.0: f :: output_var;\n\
+        var set of 1..5: s :: output_var;\n\
+        var set of 1..5: t :: output_var;\n\
+        array [1..4] of var int: grid :: output_array([1..2,1..2]) = [x,y,two,5];\n\
+        array [1..2] of var int: row :: output_array([1..2]) = [y,x];\n\
+        solve satisfy;"
+        .parse()
+        .unwrap();
+    let solution = model
+        .format_solution(|id| match id {
+            "x" => Some(Value::Int(1)),
+            "y" => Some(Value::Int(-3)),
+            "b" => Some(Value::Bool(true)),
+            "f" => Some(Value::Float(0.5)),
+            "s" => Some(Value::set([(2, 4)])),
+            "t" => Some(Value::set([(5, 5), (1, 1)])),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        solution,
+        "x = 1;\n\
+        b = true;\n\
+        f = 0.5;\n\
+        s = 2..4;\n\
+        t = {1, 5};\n\
+        grid = array2d(1..2, 1..2, [1, -3, 2, 5]);\n\
+        row = array1d(1..2, [-3, 1]);\n\
+        ----------\n"
+    );
+    assert_eq!(
+        model.format_solution(|_| None),
+        Err(SolutionError::MissingValue("x".to_string()))
+    );
+    assert_eq!(Status::Unsatisfiable.to_string(), "=====UNSATISFIABLE=====");
+    assert_eq!(Value::set([]).to_string(), "{}");
+    assert_eq!(Value::set([(3, 2)]), Value::Set(vec![]));
+    assert_eq!(
+        Value::set([(4, 6), (1, 3), (8, 9), (9, 12)]),
+        Value::Set(vec![(1, 6), (8, 12)])
+    );
+    assert_eq!(Value::set([(7, 7)]).to_string(), "{7}");
+    assert_eq!(
+        Value::set([(1, 100000000000)]).to_string(),
+        "1..100000000000"
+    );
+    assert_eq!(
+        Value::set([(1, 1), (3, 100000000000)]).to_string(),
+        "1..1 union 3..100000000000"
+    );
+    assert_eq!(
+        Value::set([(i128::MIN, 0), (2, i128::MAX)]).to_string(),
+        format!("{}..0 union 2..{}", i128::MIN, i128::MAX)
+    );
+    assert_eq!(Value::Float(2.0).to_string(), "2.0");
+}
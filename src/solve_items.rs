@@ -1,24 +1,43 @@
 use winnow::{
     combinator::{alt, cut_err},
-    error::{AddContext, FromExternalError, ParserError, StrContext},
+    error::{AddContext, FromExternalError, ParserError, StrContext, StrContextValue},
     PResult, Parser,
 };
 
 use crate::{
     comments::{space_or_comment0, space_or_comment1},
+    error::StmtKind,
     expressions::{
-        annotations, bool_expr, float_expr, int_expr, set_expr, Annotations, BoolExpr, FloatExpr,
-        IntExpr, SetExpr,
+        annotations, bool_expr, float_expr, int_expr, map_annotations, set_expr, Annotations,
+        BoolExpr, FloatExpr, IntExpr, SetExpr, StrRole,
     },
+    primitive_literals::ParseStr,
+    spans::{spanned, NodeKind},
 };
 
 #[derive(PartialEq, Clone, Debug)]
-pub struct SolveItem {
-    pub goal: Goal,
-    pub annotations: Annotations,
+pub struct SolveItem<S = String> {
+    pub goal: Goal<S>,
+    pub annotations: Annotations<S>,
 }
 
-pub fn solve_item<'a, E>(input: &mut &'a str) -> PResult<SolveItem, E>
+impl<S> SolveItem<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> SolveItem<T> {
+        SolveItem {
+            goal: self.goal.map_str(f),
+            annotations: map_annotations(self.annotations, f),
+        }
+    }
+}
+
+impl<S: Into<String>> SolveItem<S> {
+    /// Convert into a solve item that owns its strings.
+    pub fn into_owned(self) -> SolveItem {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+pub fn solve_item<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SolveItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -27,28 +46,39 @@ where
 {
     space_or_comment0(input)?;
     "solve".parse_next(input)?;
-    cut_err(solve_item_tail.context(StrContext::Label("Error while parsing solve statement")))
+    cut_err(solve_item_tail.context(StrContext::Label(StmtKind::SolveItem.label())))
         .parse_next(input)
 }
-pub fn solve_item_tail<'a, E>(input: &mut &'a str) -> PResult<SolveItem, E>
+pub fn solve_item_tail<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<SolveItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
     space_or_comment1(input)?;
     let annotations = annotations(input)?;
     space_or_comment0(input)?;
     let goal = alt((
-        satisfy,
-        optimize_bool,
-        optimize_int,
-        optimize_float,
-        optimize_set,
+        spanned(NodeKind::Goal, satisfy),
+        spanned(NodeKind::Goal, optimize_bool),
+        spanned(NodeKind::Goal, optimize_int),
+        spanned(NodeKind::Goal, optimize_float),
+        spanned(NodeKind::Goal, optimize_set),
     ))
+    .context(StrContext::Expected(StrContextValue::StringLiteral(
+        "satisfy",
+    )))
+    .context(StrContext::Expected(StrContextValue::StringLiteral(
+        "minimize",
+    )))
+    .context(StrContext::Expected(StrContextValue::StringLiteral(
+        "maximize",
+    )))
     .parse_next(input)?;
     space_or_comment0(input)?;
-    ';'.parse_next(input)?;
+    ';'.context(StrContext::Expected(StrContextValue::CharLiteral(';')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
     Ok(SolveItem { goal, annotations })
 }
@@ -59,7 +89,7 @@ fn test_solve_item() {
     use winnow::error::ContextError;
     let mut input = "solve :: int_search(X_59,input_order,indomain_min,complete) minimize X_24;";
     assert_eq!(
-        solve_item::<ContextError>(&mut input),
+        solve_item::<String, ContextError>(&mut input),
         Ok(SolveItem {
             goal: Goal::OptimizeBool(
                 OptimizationType::Minimize,
@@ -79,12 +109,31 @@ fn test_solve_item() {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum Goal {
+pub enum Goal<S = String> {
     Satisfy,
-    OptimizeBool(OptimizationType, BoolExpr),
-    OptimizeInt(OptimizationType, IntExpr),
-    OptimizeFloat(OptimizationType, FloatExpr),
-    OptimizeSet(OptimizationType, SetExpr),
+    OptimizeBool(OptimizationType, BoolExpr<S>),
+    OptimizeInt(OptimizationType, IntExpr<S>),
+    OptimizeFloat(OptimizationType, FloatExpr<S>),
+    OptimizeSet(OptimizationType, SetExpr<S>),
+}
+
+impl<S> Goal<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> Goal<T> {
+        match self {
+            Goal::Satisfy => Goal::Satisfy,
+            Goal::OptimizeBool(opt_type, e) => Goal::OptimizeBool(opt_type, e.map_str(f)),
+            Goal::OptimizeInt(opt_type, e) => Goal::OptimizeInt(opt_type, e.map_str(f)),
+            Goal::OptimizeFloat(opt_type, e) => Goal::OptimizeFloat(opt_type, e.map_str(f)),
+            Goal::OptimizeSet(opt_type, e) => Goal::OptimizeSet(opt_type, e.map_str(f)),
+        }
+    }
+}
+
+impl<S: Into<String>> Goal<S> {
+    /// Convert into a goal that owns its strings.
+    pub fn into_owned(self) -> Goal {
+        self.map_str(&mut |s, _| s.into())
+    }
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -93,7 +142,9 @@ pub enum OptimizationType {
     Maximize,
 }
 
-pub fn satisfy<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<Goal, E> {
+pub fn satisfy<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<Goal<S>, E> {
     "satisfy".parse_next(input)?;
     Ok(Goal::Satisfy)
 }
@@ -112,14 +163,16 @@ fn maximize<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<Optimiz
     Ok(OptimizationType::Maximize)
 }
 
-pub fn optimize_bool<'a, E: ParserError<&'a str>>(input: &mut &'a str) -> PResult<Goal, E> {
+pub fn optimize_bool<'a, S: ParseStr<'a>, E: ParserError<&'a str>>(
+    input: &mut &'a str,
+) -> PResult<Goal<S>, E> {
     let opt_type = opt_type(input)?;
     space_or_comment1(input)?;
     let be = bool_expr(input)?;
     Ok(Goal::OptimizeBool(opt_type, be))
 }
 
-pub fn optimize_int<'a, E>(input: &mut &'a str) -> PResult<Goal, E>
+pub fn optimize_int<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Goal<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,
 {
@@ -129,7 +182,7 @@ where
     Ok(Goal::OptimizeInt(opt_type, be))
 }
 
-pub fn optimize_float<'a, E>(input: &mut &'a str) -> PResult<Goal, E>
+pub fn optimize_float<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Goal<S>, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
 {
@@ -139,7 +192,7 @@ where
     Ok(Goal::OptimizeFloat(opt_type, be))
 }
 
-pub fn optimize_set<'a, E>(input: &mut &'a str) -> PResult<Goal, E>
+pub fn optimize_set<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Goal<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -0,0 +1,239 @@
+//! Reading the output of a solver incrementally.
+
+use std::{fmt, io, io::BufRead};
+
+use crate::{
+    dzn::{DznError, Solution},
+    model::Model,
+    solution::{Status, SOLUTION_SEPARATOR},
+};
+
+/// An event in the output of a solver.
+#[derive(PartialEq, Clone, Debug)]
+pub enum SolverEvent {
+    Solution(Solution),
+    /// The final status, such as [`Status::Complete`] after the last solution
+    Status(Status),
+    /// A `%%%mzn-stat: name=value` line
+    Statistic {
+        name: String,
+        value: String,
+    },
+    /// Any other line starting with `%`, without the `%`
+    Comment(String),
+}
+
+/// An error produced while reading the output of a solver.
+#[derive(Debug)]
+pub enum SolverOutputError {
+    Io(io::Error),
+    Dzn(DznError),
+}
+
+impl fmt::Display for SolverOutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverOutputError::Io(e) => write!(f, "{}", e),
+            SolverOutputError::Dzn(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SolverOutputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolverOutputError::Io(e) => Some(e),
+            SolverOutputError::Dzn(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for SolverOutputError {
+    fn from(e: io::Error) -> Self {
+        SolverOutputError::Io(e)
+    }
+}
+
+impl From<DznError> for SolverOutputError {
+    fn from(e: DznError) -> Self {
+        SolverOutputError::Dzn(e)
+    }
+}
+
+/// An iterator over the events in the output of a solver that reads its
+/// input line by line.
+///
+/// Assignments are typed against the declarations of `model` once the
+/// [`SOLUTION_SEPARATOR`] of their solution has been read. Assignments that
+/// are not followed by a separator are dropped, since the solver may have
+/// been stopped while printing them. A malformed solution yields an error and
+/// reading resumes with the next one.
+pub struct SolverOutputReader<'a, R> {
+    model: &'a Model,
+    reader: R,
+    /// Assignments of the solution being read
+    buf: String,
+    /// Line of the first line in `buf`, starting at 1
+    buf_line: usize,
+    /// Number of lines read
+    line: usize,
+    eof: bool,
+}
+
+impl<'a, R: BufRead> SolverOutputReader<'a, R> {
+    pub fn new(model: &'a Model, reader: R) -> SolverOutputReader<'a, R> {
+        SolverOutputReader {
+            model,
+            reader,
+            buf: String::new(),
+            buf_line: 1,
+            line: 0,
+            eof: false,
+        }
+    }
+
+    /// Type the buffered assignments, with error positions relative to the whole input.
+    fn solution(&mut self) -> Result<SolverEvent, SolverOutputError> {
+        let text = std::mem::take(&mut self.buf);
+        match self.model.parse_dzn(&text) {
+            Ok(solution) => Ok(SolverEvent::Solution(solution)),
+            Err(DznError::Syntax { line, column }) => Err(DznError::Syntax {
+                line: line + self.buf_line - 1,
+                column,
+            }
+            .into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SolverOutputReader<'_, R> {
+    type Item = Result<SolverEvent, SolverOutputError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+        while !self.eof {
+            line.clear();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => {
+                    self.eof = true;
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.eof = true;
+                    return Some(Err(e.into()));
+                }
+            }
+            self.line += 1;
+            let trimmed = line.trim();
+            if trimmed == SOLUTION_SEPARATOR {
+                return Some(self.solution());
+            }
+            if let Some(status) = Status::from_marker(trimmed) {
+                self.buf.clear();
+                return Some(Ok(SolverEvent::Status(status)));
+            }
+            let event = match trimmed.strip_prefix('%') {
+                Some(comment) => match comment.strip_prefix("%%mzn-stat:") {
+                    Some(stat) => {
+                        stat.split_once('=')
+                            .map(|(name, value)| SolverEvent::Statistic {
+                                name: name.trim().to_string(),
+                                value: value.trim().to_string(),
+                            })
+                    }
+                    None if comment == "%%mzn-stat-end" => None,
+                    None => Some(SolverEvent::Comment(comment.to_string())),
+                },
+                None => {
+                    if self.buf.is_empty() {
+                        self.buf_line = self.line;
+                    }
+                    self.buf.push_str(&line);
+                    continue;
+                }
+            };
+            // keep the line numbers of the buffered assignments
+            if !self.buf.is_empty() {
+                self.buf.push('\n');
+            }
+            if let Some(event) = event {
+                return Some(Ok(event));
+            }
+        }
+        None
+    }
+}
+#[test]
+fn test_solver_output_reader_jobshop() {
+    use crate::{dzn::DznValue, solution::Value};
+    let model: Model = include_str!("../jobshop.fzn").parse().unwrap();
+    let reader = io::BufReader::with_capacity(5, include_str!("../jobshop.out").as_bytes());
+    let events: Vec<_> = SolverOutputReader::new(&model, reader)
+        .map(|e| e.unwrap())
+        .collect();
+    let stat = |name: &str, value: &str| SolverEvent::Statistic {
+        name: name.to_string(),
+        value: value.to_string(),
+    };
+    assert_eq!(events.len(), 8);
+    assert_eq!(
+        events[0],
+        SolverEvent::Comment(" solving jobshop.fzn".to_string())
+    );
+    assert!(matches!(events[1], SolverEvent::Solution(_)));
+    assert_eq!(events[2], stat("objective", "38"));
+    let SolverEvent::Solution(solution) = &events[3] else {
+        panic!("expected a solution");
+    };
+    let starts = [0, 3, 6, 10, 3, 10, 13, 15, 0, 6, 9, 13];
+    assert_eq!(
+        solution.get("s"),
+        Some(&DznValue::Array {
+            dims: vec![(1, 3), (1, 4)],
+            values: starts.map(Value::Int).to_vec(),
+        })
+    );
+    assert_eq!(
+        events[4..],
+        [
+            stat("objective", "17"),
+            SolverEvent::Status(Status::Complete),
+            stat("nodes", "152"),
+            stat("solveTime", "0.004"),
+        ]
+    );
+}
+#[test]
+fn test_solver_output_reader_errors() {
+    let model: Model = "var 0..3: x :: output_var;\nsolve satisfy;"
+        .parse()
+        .unwrap();
+    let input =
+        "x = 1;\n----------\n% a comment\nx =\n  true;\n----------\nx = 2;\n----------\nx = 3;";
+    let events: Vec<_> = SolverOutputReader::new(&model, input.as_bytes()).collect();
+    assert_eq!(events.len(), 4);
+    assert!(matches!(events[0], Ok(SolverEvent::Solution(_))));
+    assert!(matches!(events[1], Ok(SolverEvent::Comment(_))));
+    assert!(matches!(
+        events[2],
+        Err(SolverOutputError::Dzn(DznError::TypeMismatch { .. }))
+    ));
+    assert!(matches!(events[3], Ok(SolverEvent::Solution(_))));
+
+    let input = "x = 1;\n% a comment\nx == 2;\n----------\n=====UNKNOWN=====\n";
+    let events: Vec<_> = SolverOutputReader::new(&model, input.as_bytes()).collect();
+    assert!(matches!(
+        events[1],
+        Err(SolverOutputError::Dzn(DznError::Syntax {
+            line: 3,
+            column: 4
+        }))
+    ));
+    assert!(matches!(
+        events[2],
+        Ok(SolverEvent::Status(Status::Unknown))
+    ));
+}
@@ -0,0 +1,384 @@
+use std::cell::RefCell;
+
+use winnow::{PResult, Parser};
+
+use crate::{
+    error::{ParseError, StmtKind},
+    statements::{next_item, Stmt},
+};
+
+/// A range of bytes in the input together with the line and column it starts at.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The kind of syntax node a [`SpanTree`] locates.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum NodeKind {
+    /// A whole statement including the terminating `;`
+    Stmt(StmtKind),
+    /// The identifier of a declaration, constraint, predicate, predicate
+    /// parameter, annotation or array access
+    Identifier,
+    /// The type of a declaration or of a predicate parameter
+    Type,
+    /// A parameter of a predicate declaration
+    PredicateParameter,
+    /// The value of a parameter declaration
+    Literal,
+    Expr,
+    BoolExpr,
+    IntExpr,
+    FloatExpr,
+    SetExpr,
+    SetLiteralExpr,
+    /// The assigned value of an array of variables
+    ArrayExpr,
+    Goal,
+    Annotation,
+    AnnExpr,
+}
+
+/// The source location of a syntax node and of the nodes it contains.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SpanTree {
+    pub kind: NodeKind,
+    pub span: Span,
+    pub children: Vec<SpanTree>,
+}
+
+impl SpanTree {
+    /// The direct children of the given kind.
+    pub fn children_of(&self, kind: NodeKind) -> impl Iterator<Item = &SpanTree> {
+        self.children.iter().filter(move |child| child.kind == kind)
+    }
+}
+
+/// A statement together with the source locations of its parts.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SpannedStmt {
+    pub stmt: Stmt,
+    pub spans: SpanTree,
+}
+
+/// Parse a complete FlatZinc model like [`parse_model`](crate::parse_model)
+/// and record where each statement and its parts are located in `input`.
+pub fn parse_model_with_spans(input: &str) -> Result<Vec<SpannedStmt>, ParseError> {
+    let _recording = Recording::start();
+    let lines = LineStarts::new(input);
+    let mut rest = input;
+    let mut stmts = vec![];
+    while let Some(stmt) = next_item(input, &mut rest)? {
+        let recorded = FRAMES.with_borrow_mut(|frames| match frames {
+            Some(frames) => frames.first_mut().and_then(|root| root.pop()),
+            None => None,
+        });
+        let recorded = recorded.expect("statement parsers record their span");
+        stmts.push(SpannedStmt {
+            stmt,
+            spans: lines.tree(input, recorded),
+        });
+    }
+    Ok(stmts)
+}
+#[test]
+fn test_parse_model_with_spans() {
+    let input =
+        "var 0..3: x :: output_var;\nconstraint int_lin_le([1,-1],\n    [x, y], 2) :: domain;\n";
+    let text = |tree: &SpanTree| &input[tree.span.start..tree.span.end];
+    let stmts = parse_model_with_spans(input).unwrap();
+    assert_eq!(stmts.len(), 2);
+    let var = &stmts[0].spans;
+    assert_eq!(var.kind, NodeKind::Stmt(StmtKind::Variable));
+    assert_eq!(text(var), "var 0..3: x :: output_var;");
+    let kinds: Vec<_> = var.children.iter().map(|child| child.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![NodeKind::Type, NodeKind::Identifier, NodeKind::Annotation]
+    );
+    assert_eq!(text(&var.children[0]), "var 0..3");
+    assert_eq!(text(&var.children[1]), "x");
+    assert_eq!(text(&var.children[2]), "output_var");
+    let constraint = &stmts[1].spans;
+    assert_eq!((constraint.span.line, constraint.span.column), (2, 1));
+    assert_eq!(text(&constraint.children[0]), "int_lin_le");
+    let exprs: Vec<_> = constraint.children_of(NodeKind::Expr).collect();
+    assert_eq!(exprs.len(), 3);
+    assert_eq!(text(&exprs[0].children[1]), "-1");
+    let y = &exprs[1].children[1];
+    assert_eq!(y.kind, NodeKind::BoolExpr);
+    assert_eq!(text(y), "y");
+    assert_eq!((y.span.line, y.span.column), (3, 9));
+    assert_eq!(text(exprs[2]), "2");
+    let anno = constraint.children_of(NodeKind::Annotation).next().unwrap();
+    assert_eq!(text(anno), "domain");
+}
+#[test]
+fn test_parse_model_with_spans_2() {
+    let input = "predicate my_pred(var int: a, array [int] of int: b);\narray [1..2] of int: c = [1, 2];\nsolve :: int_search(c, input_order, indomain_min, complete) minimize x;";
+    let text = |tree: &SpanTree| &input[tree.span.start..tree.span.end];
+    let stmts = parse_model_with_spans(input).unwrap();
+    let predicate = &stmts[0].spans;
+    let parameters: Vec<_> = predicate
+        .children_of(NodeKind::PredicateParameter)
+        .collect();
+    assert_eq!(parameters.len(), 2);
+    assert_eq!(text(parameters[1]), "array [int] of int: b");
+    assert_eq!(text(&parameters[1].children[0]), "array [int] of int");
+    assert_eq!(text(&parameters[1].children[1]), "b");
+    let par = &stmts[1].spans;
+    assert_eq!(text(&par.children[0]), "array [1..2] of int");
+    let value = par.children_of(NodeKind::Literal).next().unwrap();
+    assert_eq!(text(value), "[1, 2]");
+    let solve = &stmts[2].spans;
+    assert_eq!(solve.span.line, 3);
+    let anno = &solve.children[0];
+    assert_eq!(text(&anno.children[0]), "int_search");
+    assert_eq!(anno.children_of(NodeKind::AnnExpr).count(), 4);
+    let goal = solve.children_of(NodeKind::Goal).next().unwrap();
+    assert_eq!(text(goal), "minimize x");
+    assert_eq!(text(&goal.children[0]), "x");
+}
+#[test]
+fn test_parse_model_with_spans_nested() {
+    let input =
+        "var set of 1..5: s = {1, % one\n k};\nconstraint foo(s) :: bar([baz(\"a;b\"),qux], x[2]);";
+    let text = |tree: &SpanTree| &input[tree.span.start..tree.span.end];
+    let stmts = parse_model_with_spans(input).unwrap();
+    let set = &stmts[0].spans.children[2];
+    assert_eq!(set.kind, NodeKind::SetExpr);
+    let literal = &set.children[0];
+    assert_eq!(literal.kind, NodeKind::SetLiteralExpr);
+    assert_eq!(text(literal), "{1, % one\n k}");
+    assert_eq!(text(&literal.children[1]), "k");
+    let anno = &stmts[1].spans.children[2];
+    assert_eq!(text(anno), "bar([baz(\"a;b\"),qux], x[2])");
+    let annos = &anno.children[1];
+    assert_eq!(text(annos), "[baz(\"a;b\"),qux]");
+    let baz = &annos.children[0];
+    assert_eq!(baz.kind, NodeKind::Annotation);
+    assert_eq!(text(&baz.children[1]), "\"a;b\"");
+    let access = &anno.children[2];
+    assert_eq!(text(&access.children[0]), "x");
+    assert_eq!(access.children[1].kind, NodeKind::IntExpr);
+}
+#[test]
+fn test_parse_model_with_spans_jobshop() {
+    let input = include_str!("../jobshop.fzn");
+    let stmts = parse_model_with_spans(input).unwrap();
+    let plain = crate::parse_model(input).unwrap();
+    assert_eq!(stmts.len(), plain.len());
+    for (spanned, stmt) in stmts.iter().zip(plain) {
+        assert_eq!(spanned.stmt, stmt);
+        assert!(input[spanned.spans.span.start..spanned.spans.span.end].ends_with(';'));
+    }
+    // spans are only recorded on request
+    assert!(FRAMES.with_borrow(Option::is_none));
+}
+#[test]
+fn test_parse_model_with_spans_after_error() {
+    let input = "var int: x;\nconstraint int_le(x, [1, {2);\nsolve satisfy;";
+    assert!(parse_model_with_spans(input).is_err());
+    assert!(FRAMES.with_borrow(Option::is_none));
+    let input = "var int: x;\nsolve satisfy;";
+    let stmts = parse_model_with_spans(input).unwrap();
+    let spans: Vec<_> = stmts.iter().map(|stmt| stmt.spans.span).collect();
+    assert_eq!(
+        spans,
+        vec![
+            Span {
+                start: 0,
+                end: 11,
+                line: 1,
+                column: 1
+            },
+            Span {
+                start: 12,
+                end: 26,
+                line: 2,
+                column: 1
+            },
+        ]
+    );
+    assert_eq!(stmts[0].spans.children.len(), 2);
+    assert_eq!(stmts[1].spans.children_of(NodeKind::Goal).count(), 1);
+}
+
+/// A node that was parsed while recording, located by the addresses of its
+/// first and last byte.
+struct Recorded {
+    kind: NodeKind,
+    start: usize,
+    end: usize,
+    children: Vec<Recorded>,
+}
+
+thread_local! {
+    /// The nodes recorded so far, one frame for each node that is being
+    /// parsed; `None` unless [`parse_model_with_spans`] is running.
+    static FRAMES: RefCell<Option<Vec<Vec<Recorded>>>> = const { RefCell::new(None) };
+}
+
+/// Enables recording until dropped.
+struct Recording(Option<Vec<Vec<Recorded>>>);
+
+impl Recording {
+    fn start() -> Recording {
+        Recording(FRAMES.replace(Some(vec![vec![]])))
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        FRAMES.set(self.0.take());
+    }
+}
+
+/// Run `parser` and, while spans are recorded, record the text it consumed
+/// as a node of the given kind. Nodes recorded by a parser that fails are
+/// discarded, so alternatives that backtrack leave no trace.
+pub(crate) fn spanned<'a, O, E>(
+    kind: NodeKind,
+    mut parser: impl Parser<&'a str, O, E>,
+) -> impl Parser<&'a str, O, E> {
+    move |input: &mut &'a str| -> PResult<O, E> {
+        let Some(frame) = Frame::push() else {
+            return parser.parse_next(input);
+        };
+        let start = *input;
+        let res = parser.parse_next(input);
+        if res.is_ok() {
+            frame.record(kind, &start[..start.len() - input.len()]);
+        }
+        res
+    }
+}
+
+/// The frame of a node that is being parsed. Dropping it removes the frame
+/// and everything recorded into it, however the parser returned.
+struct Frame {
+    depth: usize,
+}
+
+impl Frame {
+    /// Push a frame, unless spans are not recorded.
+    fn push() -> Option<Frame> {
+        FRAMES.with_borrow_mut(|frames| {
+            let frames = frames.as_mut()?;
+            frames.push(vec![]);
+            Some(Frame {
+                depth: frames.len() - 1,
+            })
+        })
+    }
+
+    /// Move the nodes of this frame into a node for `consumed`, which is
+    /// recorded in the parent frame.
+    fn record(self, kind: NodeKind, consumed: &str) {
+        FRAMES.with_borrow_mut(|frames| {
+            let Some(frames) = frames else {
+                return;
+            };
+            // frames above this one belong to parsers that have returned
+            frames.truncate(self.depth + 1);
+            let children = frames.pop().unwrap_or_default();
+            let (first, last) = trim(consumed);
+            let base = consumed.as_ptr() as usize;
+            if let Some(parent) = frames.last_mut() {
+                parent.push(Recorded {
+                    kind,
+                    start: base + first,
+                    end: base + last,
+                    children,
+                });
+            }
+        });
+    }
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        FRAMES.with_borrow_mut(|frames| {
+            if let Some(frames) = frames {
+                frames.truncate(self.depth);
+            }
+        });
+    }
+}
+
+/// The range of `text` without leading and trailing whitespace and comments.
+fn trim(text: &str) -> (usize, usize) {
+    let (mut first, mut last) = (None, 0);
+    let (mut in_string, mut in_comment, mut escaped) = (false, false, false);
+    for (i, b) in text.bytes().enumerate() {
+        if in_comment {
+            in_comment = b != b'\n';
+            continue;
+        }
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+        } else if b == b'%' {
+            in_comment = true;
+            continue;
+        } else if b.is_ascii_whitespace() {
+            continue;
+        } else if b == b'"' {
+            in_string = true;
+        }
+        first.get_or_insert(i);
+        last = i + 1;
+    }
+    let first = first.unwrap_or(0);
+    (first, last.max(first))
+}
+#[test]
+fn test_trim() {
+    assert_eq!(trim("  x  "), (2, 3));
+    assert_eq!(trim("% a\n x % b;\n"), (5, 6));
+    assert_eq!(trim("\"a % b\" "), (0, 7));
+    assert_eq!(trim(" "), (0, 0));
+}
+
+/// The byte offsets at which the lines of the input start.
+struct LineStarts(Vec<usize>);
+
+impl LineStarts {
+    fn new(input: &str) -> LineStarts {
+        let starts = input.match_indices('\n').map(|(i, _)| i + 1);
+        LineStarts(std::iter::once(0).chain(starts).collect())
+    }
+
+    fn span(&self, input: &str, start: usize, end: usize) -> Span {
+        let line = self.0.partition_point(|&line_start| line_start <= start);
+        let column = input[self.0[line - 1]..start].chars().count() + 1;
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    fn tree(&self, input: &str, recorded: Recorded) -> SpanTree {
+        let base = input.as_ptr() as usize;
+        SpanTree {
+            kind: recorded.kind,
+            span: self.span(input, recorded.start - base, recorded.end - base),
+            children: recorded
+                .children
+                .into_iter()
+                .map(|child| self.tree(input, child))
+                .collect(),
+        }
+    }
+}
@@ -1,59 +1,334 @@
+use std::borrow::Cow;
+
 use winnow::{
     combinator::alt,
-    error::{AddContext, FromExternalError, ParserError, StrContext, TreeError},
+    error::{AddContext, ContextError, FromExternalError, ParserError, StrContext},
     PResult, Parser,
 };
 
 use crate::{
-    comments::space_or_comment,
+    comments::{space_or_comment, space_or_comment0},
     constraints::{constraint_item, ConstraintItem},
+    error::{ParseError, StmtKind},
+    expressions::StrRole,
     parameters::declarations as parameter_declarations,
     parameters::declarations::ParDeclItem,
     predicates::declarations as predicate_declarations,
     predicates::declarations::PredicateItem,
+    primitive_literals::ParseStr,
     solve_items::{solve_item, SolveItem},
+    spans::{spanned, NodeKind},
     variables::declarations as variable_declarations,
     variables::declarations::VarDeclItem,
 };
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum Stmt {
-    Comment(String),
-    Predicate(PredicateItem),
-    Parameter(ParDeclItem),
-    Variable(VarDeclItem),
-    Constraint(ConstraintItem),
-    SolveItem(SolveItem),
+pub enum Stmt<S = String> {
+    Comment(S),
+    Predicate(PredicateItem<S>),
+    Parameter(ParDeclItem<S>),
+    Variable(VarDeclItem<S>),
+    Constraint(ConstraintItem<S>),
+    SolveItem(SolveItem<S>),
+}
+
+impl<S> Stmt<S> {
+    /// Apply `f` to every string of the statement, for example to intern
+    /// identifiers. The [`StrRole`] tells identifiers of variables and
+    /// parameters apart from other names.
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> Stmt<T> {
+        match self {
+            Stmt::Comment(s) => Stmt::Comment(f(s, StrRole::Name)),
+            Stmt::Predicate(item) => Stmt::Predicate(item.map_str(f)),
+            Stmt::Parameter(item) => Stmt::Parameter(item.map_str(f)),
+            Stmt::Variable(item) => Stmt::Variable(item.map_str(f)),
+            Stmt::Constraint(item) => Stmt::Constraint(item.map_str(f)),
+            Stmt::SolveItem(item) => Stmt::SolveItem(item.map_str(f)),
+        }
+    }
 }
+
+impl<S: Into<String>> Stmt<S> {
+    /// Convert into a statement that owns its strings.
+    pub fn into_owned(self) -> Stmt {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+/// A statement whose identifiers borrow from the parsed input.
+///
+/// Only string literals in annotations that contain escape sequences are
+/// allocated.
+pub type BorrowedStmt<'a> = Stmt<Cow<'a, str>>;
 impl std::str::FromStr for Stmt {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        statement::<TreeError<&str>>()
-            .context(StrContext::Label("Error while parsing statement"))
-            .parse(input)
-            .map_err(|e| e.to_string())
+        let mut rest = input;
+        let Some(stmt) = next_item(input, &mut rest)? else {
+            // only whitespace and comments
+            let mut rest = input;
+            return space_or_comment::<String, ContextError>(&mut rest)
+                .map_err(|_| ParseError::new(input, 0, None, vec![]));
+        };
+        if rest.is_empty() {
+            Ok(stmt)
+        } else {
+            Err(ParseError::new(
+                input,
+                input.len() - rest.len(),
+                None,
+                vec!["end of input".to_string()],
+            ))
+        }
     }
 }
 
-fn statement<'a, E>() -> impl Parser<&'a str, Stmt, E>
-where
-    E: ParserError<&'a str>
-        + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>
-        + AddContext<&'a str, StrContext>,
-{
-    alt((
-        stmt_predicate,
-        stmt_parameter,
-        stmt_variable,
-        stmt_constraint,
-        stmt_solve_item,
-        space_or_comment,
+/// Parse a complete FlatZinc model into its statements.
+///
+/// Statements are split at their terminating `;` rather than at line breaks,
+/// so items spanning several lines are supported. Comments and blank lines
+/// between statements are skipped.
+pub fn parse_model(input: &str) -> Result<Vec<Stmt>, ParseError> {
+    let mut rest = input;
+    let mut stmts = vec![];
+    while let Some(stmt) = next_item(input, &mut rest)? {
+        stmts.push(stmt);
+    }
+    Ok(stmts)
+}
+
+/// Parse a complete FlatZinc model like [`parse_model`] without copying
+/// identifiers out of `input`.
+pub fn parse_model_borrowed(input: &str) -> Result<Vec<BorrowedStmt<'_>>, ParseError> {
+    let mut rest = input;
+    let mut stmts = vec![];
+    while let Some(stmt) = next_item(input, &mut rest)? {
+        stmts.push(stmt);
+    }
+    Ok(stmts)
+}
+#[test]
+fn test_parse_model_borrowed() {
+    use crate::expressions::AnnExpr;
+    let input = include_str!("../../jobshop.fzn");
+    let stmts = parse_model_borrowed(input).unwrap();
+    let Stmt::Constraint(constraint) = &stmts[stmts.len() - 2] else {
+        panic!("expected a constraint");
+    };
+    assert!(matches!(constraint.id, Cow::Borrowed(_)));
+    let owned: Vec<_> = stmts.into_iter().map(Stmt::into_owned).collect();
+    assert_eq!(owned, parse_model(input).unwrap());
+    let stmts = parse_model_borrowed("solve :: foo(\"plain\",\"esc\\\"aped\") satisfy;").unwrap();
+    let Stmt::SolveItem(solve) = &stmts[0] else {
+        panic!("expected a solve item");
+    };
+    let strings: Vec<_> = solve.annotations[0]
+        .expressions
+        .iter()
+        .map(|e| match e {
+            AnnExpr::String(s) => s,
+            _ => panic!("expected a string"),
+        })
+        .collect();
+    assert!(matches!(strings[0], Cow::Borrowed("plain")));
+    assert!(matches!(strings[1], Cow::Owned(s) if s == "esc\"aped"));
+}
+#[test]
+fn test_parse_model() {
+    use crate::{BoolExpr, Expr, IntExpr};
+    let input = "var 0..3: x;\n% a comment\n\nconstraint int_lin_le([1,\n  -1],\n  [x, y], 2);\nsolve satisfy;\n";
+    let stmts = parse_model(input).unwrap();
+    assert_eq!(stmts.len(), 3);
+    assert_eq!(
+        stmts[1],
+        Stmt::Constraint(ConstraintItem {
+            id: "int_lin_le".to_string(),
+            exprs: vec![
+                Expr::ArrayOfInt(vec![IntExpr::Int(1), IntExpr::Int(-1)]),
+                Expr::ArrayOfBool(vec![
+                    BoolExpr::VarParIdentifier("x".to_string()),
+                    BoolExpr::VarParIdentifier("y".to_string())
+                ]),
+                Expr::Int(2)
+            ],
+            annos: vec![]
+        })
+    );
+}
+#[test]
+fn test_parse_model_jobshop() {
+    let stmts = parse_model(include_str!("../../jobshop.fzn")).unwrap();
+    assert_eq!(stmts.len(), 95);
+    assert!(matches!(stmts[94], Stmt::SolveItem(_)));
+}
+#[test]
+fn test_parse_model_error() {
+    use crate::error::StmtKind;
+    let err = parse_model("var int: x;\nconstraint int_eq(x, );\n").unwrap_err();
+    assert_eq!(err.line, 2);
+    assert_eq!(err.column, 20);
+    assert_eq!(err.offset, 31);
+    assert_eq!(err.kind, Some(StmtKind::Constraint));
+    assert_eq!(err.expected, vec!["`)`".to_string()]);
+    assert_eq!(err.snippet, ", );");
+    assert_eq!(
+        err.to_string(),
+        "2:20: failed to parse constraint, expected `)`, found `, );`"
+    );
+}
+#[test]
+fn test_stmt_from_str_error() {
+    use crate::error::StmtKind;
+    let err = <Stmt as std::str::FromStr>::from_str("array [1..2] of var int x;").unwrap_err();
+    assert_eq!(err.kind, Some(StmtKind::Variable));
+    assert_eq!(err.column, 25);
+    assert_eq!(err.expected, vec!["`:`".to_string()]);
+    let err = <Stmt as std::str::FromStr>::from_str("solve minimize;").unwrap_err();
+    assert_eq!(err.kind, Some(StmtKind::SolveItem));
+    let err = <Stmt as std::str::FromStr>::from_str("array [1..2] of int x = [1,2];").unwrap_err();
+    assert_eq!(err.kind, Some(StmtKind::Parameter));
+    assert_eq!(err.expected, vec!["`:`".to_string()]);
+    let err = <Stmt as std::str::FromStr>::from_str("predicate p(var int x);").unwrap_err();
+    assert_eq!(err.kind, Some(StmtKind::Predicate));
+    let err = <Stmt as std::str::FromStr>::from_str("foo bar;").unwrap_err();
+    assert_eq!(err.kind, None);
+    assert_eq!(err.column, 1);
+}
+
+/// Parse a complete FlatZinc model, recovering from malformed statements.
+///
+/// When a statement fails to parse, the error is recorded and parsing resumes
+/// after the next `;`. Returns the successfully parsed statements together
+/// with the errors of all malformed ones.
+pub fn parse_model_recovering(input: &str) -> (Vec<Stmt>, Vec<ParseError>) {
+    let mut rest = input;
+    let mut stmts = vec![];
+    let mut errors = vec![];
+    loop {
+        match next_item(input, &mut rest) {
+            Ok(Some(stmt)) => stmts.push(stmt),
+            Ok(None) => return (stmts, errors),
+            Err(e) => {
+                errors.push(e);
+                skip_statement(&mut rest);
+            }
+        }
+    }
+}
+#[test]
+fn test_parse_model_recovering() {
+    let input = "var int: x;\nvar int y;\nconstraint int_eq(x, );\n% a comment; with a semicolon\nconstraint int_le(x, 3) :: foo(\"a;b\" 1);\nconstraint int_le(x, 5);\nsolve satisfy;";
+    let (stmts, errors) = parse_model_recovering(input);
+    assert_eq!(stmts.len(), 3);
+    assert!(matches!(stmts[0], Stmt::Variable(_)));
+    assert!(matches!(stmts[1], Stmt::Constraint(_)));
+    assert!(matches!(stmts[2], Stmt::SolveItem(_)));
+    let lines: Vec<_> = errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![2, 3, 5]);
+}
+
+/// Skip the rest of a malformed statement up to and including its `;`,
+/// ignoring semicolons inside comments and string literals.
+fn skip_statement(rest: &mut &str) {
+    let mut scanner = StmtScanner::default();
+    match rest.bytes().position(|b| scanner.is_end(b)) {
+        Some(i) => *rest = &rest[i + 1..],
+        None => *rest = "",
+    }
+}
+
+/// Finds the `;` terminating a statement, byte by byte, ignoring semicolons
+/// inside comments and string literals.
+#[derive(Default)]
+pub(crate) struct StmtScanner {
+    in_string: bool,
+    in_comment: bool,
+    escaped: bool,
+}
+
+impl StmtScanner {
+    /// Feed the next byte and report whether it ends the statement.
+    pub(crate) fn is_end(&mut self, b: u8) -> bool {
+        if self.in_comment {
+            self.in_comment = b != b'\n';
+        } else if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+            }
+        } else {
+            match b {
+                b'%' => self.in_comment = true,
+                b'"' => self.in_string = true,
+                b';' => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+/// Skip whitespace and comments and parse the statement that follows, if any.
+pub(crate) fn next_item<'a, S: ParseStr<'a>>(
+    input: &str,
+    rest: &mut &'a str,
+) -> Result<Option<Stmt<S>>, ParseError> {
+    let _ = space_or_comment0::<ContextError>(rest);
+    if rest.is_empty() {
+        return Ok(None);
+    }
+    let start = input.len() - rest.len();
+    let res = alt((
+        spanned(
+            NodeKind::Stmt(StmtKind::Constraint),
+            stmt_constraint::<S, ContextError>,
+        ),
+        spanned(NodeKind::Stmt(StmtKind::Variable), stmt_variable),
+        spanned(NodeKind::Stmt(StmtKind::Parameter), stmt_parameter),
+        spanned(NodeKind::Stmt(StmtKind::Predicate), stmt_predicate),
+        spanned(NodeKind::Stmt(StmtKind::SolveItem), stmt_solve_item),
     ))
+    .parse_next(rest);
+    let e = match res {
+        Ok(stmt) => return Ok(Some(stmt)),
+        Err(e) => e.into_inner().unwrap_or_default(),
+    };
+    match StmtKind::from_context(&e) {
+        Some(kind) => {
+            let offset = input.len() - rest.len();
+            Err(ParseError::from_context_error(
+                input,
+                offset,
+                Some(kind),
+                &e,
+            ))
+        }
+        // no statement parser got past its leading keyword or type
+        None => {
+            let expected = [
+                "predicate",
+                "var",
+                "array",
+                "bool",
+                "int",
+                "float",
+                "set",
+                "constraint",
+                "solve",
+            ]
+            .iter()
+            .map(|keyword| format!("`{}`", keyword))
+            .collect();
+            Err(ParseError::new(input, start, None, expected))
+        }
+    }
 }
 
-fn stmt_predicate<'a, E>(input: &mut &'a str) -> PResult<Stmt, E>
+fn stmt_predicate<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Stmt<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -64,27 +339,29 @@ where
     Ok(Stmt::Predicate(item))
 }
 
-fn stmt_parameter<'a, E>(input: &mut &'a str) -> PResult<Stmt, E>
+fn stmt_parameter<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Stmt<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
     let item = parameter_declarations::par_decl_item(input)?;
     Ok(Stmt::Parameter(item))
 }
 
-fn stmt_variable<'a, E>(input: &mut &'a str) -> PResult<Stmt, E>
+fn stmt_variable<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Stmt<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
     let item = variable_declarations::var_decl_item(input)?;
     Ok(Stmt::Variable(item))
 }
 
-fn stmt_constraint<'a, E>(input: &mut &'a str) -> PResult<Stmt, E>
+fn stmt_constraint<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Stmt<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -95,7 +372,7 @@ where
     Ok(Stmt::Constraint(item))
 }
 
-fn stmt_solve_item<'a, E>(input: &mut &'a str) -> PResult<Stmt, E>
+fn stmt_solve_item<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<Stmt<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
@@ -0,0 +1,361 @@
+//! Semantic checks of parsed statements that the grammar does not enforce.
+
+use std::{borrow::Cow, collections::HashSet, fmt};
+
+use crate::{
+    error::StmtKind,
+    expressions::Expr,
+    parameters::declarations::ParDeclItem,
+    primitive_literals::IndexSet,
+    solution::{assigned_array, assigned_expr},
+    solve_items::Goal,
+    statements::Stmt,
+    variables::declarations::VarDeclItem,
+};
+
+/// A problem found by [`validate`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct Diagnostic {
+    /// Position of the statement in the validated slice, or `None` for
+    /// problems of the model as a whole
+    pub stmt: Option<usize>,
+    pub kind: DiagnosticKind,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum DiagnosticKind {
+    /// The identifier is not declared before it is used
+    Undeclared(String),
+    /// The array literal of a declaration does not match its index set
+    ArrayLength {
+        id: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The index set of an array, like `1..-1`, is not a valid length
+    InvalidArrayLength(String),
+    /// The domain of a variable, like `5..3`, contains no values
+    EmptyDomain(String),
+    /// A variable, parameter or predicate is declared more than once
+    Duplicate(String),
+    MissingSolveItem,
+    MultipleSolveItems,
+    /// An item appears after an item of a kind that has to follow it, such
+    /// as a parameter after a constraint
+    ItemOrder {
+        kind: StmtKind,
+        after: StmtKind,
+    },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(stmt) = self.stmt {
+            write!(f, "statement {}: ", stmt)?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::Undeclared(id) => write!(f, "`{}` is not declared", id),
+            DiagnosticKind::ArrayLength {
+                id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "array `{}` has {} elements, but its index set has {}",
+                id, found, expected
+            ),
+            DiagnosticKind::InvalidArrayLength(id) => {
+                write!(f, "index set of `{}` is not a valid length", id)
+            }
+            DiagnosticKind::EmptyDomain(id) => write!(f, "domain of `{}` is empty", id),
+            DiagnosticKind::Duplicate(id) => write!(f, "`{}` is declared more than once", id),
+            DiagnosticKind::MissingSolveItem => write!(f, "model has no solve item"),
+            DiagnosticKind::MultipleSolveItems => {
+                write!(f, "model has more than one solve item")
+            }
+            DiagnosticKind::ItemOrder { kind, after } => {
+                write!(f, "{} after {}", kind, after)
+            }
+        }
+    }
+}
+
+/// Check statements for problems that make them invalid FlatZinc, in the
+/// order in which the statements are given.
+///
+/// Identifiers in annotations are not checked, since annotations may
+/// contain names such as `input_order`.
+pub fn validate(stmts: &[Stmt]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut declared = HashSet::new();
+    let mut predicates = HashSet::new();
+    let mut solve_items = 0;
+    let mut last: Option<StmtKind> = None;
+    for (i, stmt) in stmts.iter().enumerate() {
+        let mut report = |kind| {
+            diagnostics.push(Diagnostic {
+                stmt: Some(i),
+                kind,
+            })
+        };
+        let (kind, used): (_, Vec<Cow<Expr>>) = match stmt {
+            Stmt::Comment(_) => continue,
+            Stmt::Predicate(item) => {
+                if !predicates.insert(item.id.as_str()) {
+                    report(DiagnosticKind::Duplicate(item.id.clone()));
+                }
+                (StmtKind::Predicate, vec![])
+            }
+            Stmt::Parameter(item) => {
+                if let Some((ix, found)) = par_array_len(item) {
+                    if let Some(kind) = array_length(item.id(), ix, Some(found)) {
+                        report(kind);
+                    }
+                }
+                (StmtKind::Parameter, vec![])
+            }
+            Stmt::Variable(item) => {
+                if empty_domain(item) {
+                    report(DiagnosticKind::EmptyDomain(item.id().to_string()));
+                }
+                let assigned = assigned_expr(item).or_else(|| assigned_array(item));
+                if let Some(ix) = index_set(item) {
+                    let found = assigned.as_ref().and_then(array_len);
+                    if let Some(kind) = array_length(item.id(), ix, found) {
+                        report(kind);
+                    }
+                }
+                (
+                    StmtKind::Variable,
+                    assigned.into_iter().map(Cow::Owned).collect(),
+                )
+            }
+            // constraints are only borrowed, since they make up most of a model
+            Stmt::Constraint(item) => (
+                StmtKind::Constraint,
+                item.exprs.iter().map(Cow::Borrowed).collect(),
+            ),
+            Stmt::SolveItem(item) => {
+                solve_items += 1;
+                if solve_items > 1 {
+                    report(DiagnosticKind::MultipleSolveItems);
+                }
+                let objective: Option<Expr> = match &item.goal {
+                    Goal::Satisfy => None,
+                    Goal::OptimizeBool(_, e) => Some(e.clone().into()),
+                    Goal::OptimizeInt(_, e) => Some(e.clone().into()),
+                    Goal::OptimizeFloat(_, e) => Some(e.clone().into()),
+                    Goal::OptimizeSet(_, e) => Some(e.clone().into()),
+                };
+                (
+                    StmtKind::SolveItem,
+                    objective.into_iter().map(Cow::Owned).collect(),
+                )
+            }
+        };
+        for e in used {
+            e.for_each_identifier(&mut |id| {
+                if !declared.contains(id) {
+                    report(DiagnosticKind::Undeclared(id.clone()));
+                }
+            });
+        }
+        match last {
+            Some(after) if order(kind) < order(after) => {
+                report(DiagnosticKind::ItemOrder { kind, after })
+            }
+            _ => last = Some(kind),
+        }
+        let id = match stmt {
+            Stmt::Parameter(item) => item.id(),
+            Stmt::Variable(item) => item.id(),
+            _ => continue,
+        };
+        if !declared.insert(id.to_string()) {
+            report(DiagnosticKind::Duplicate(id.to_string()));
+        }
+    }
+    if solve_items == 0 {
+        diagnostics.push(Diagnostic {
+            stmt: None,
+            kind: DiagnosticKind::MissingSolveItem,
+        });
+    }
+    diagnostics
+}
+
+/// The position of a kind of item in a FlatZinc model.
+fn order(kind: StmtKind) -> u8 {
+    match kind {
+        StmtKind::Predicate => 0,
+        StmtKind::Parameter => 1,
+        StmtKind::Variable => 2,
+        StmtKind::Constraint => 3,
+        StmtKind::SolveItem => 4,
+    }
+}
+
+/// The problem with the length of an array, if its index set is not a valid
+/// length or does not match the length `found` of the assigned array literal.
+fn array_length(id: &str, ix: &IndexSet, found: Option<usize>) -> Option<DiagnosticKind> {
+    let Some(expected) = ix.length() else {
+        return Some(DiagnosticKind::InvalidArrayLength(id.to_string()));
+    };
+    match found {
+        Some(found) if found != expected => Some(DiagnosticKind::ArrayLength {
+            id: id.to_string(),
+            expected,
+            found,
+        }),
+        _ => None,
+    }
+}
+
+/// The index set and the length of the array literal of a parameter array.
+fn par_array_len(item: &ParDeclItem) -> Option<(&IndexSet, usize)> {
+    match item {
+        ParDeclItem::ArrayOfBool { ix, v, .. } => Some((ix, v.len())),
+        ParDeclItem::ArrayOfInt { ix, v, .. } => Some((ix, v.len())),
+        ParDeclItem::ArrayOfFloat { ix, v, .. } => Some((ix, v.len())),
+        ParDeclItem::ArrayOfSet { ix, v, .. } => Some((ix, v.len())),
+        _ => None,
+    }
+}
+
+fn index_set(item: &VarDeclItem) -> Option<&IndexSet> {
+    match item {
+        VarDeclItem::ArrayOfBool { ix, .. }
+        | VarDeclItem::ArrayOfInt { ix, .. }
+        | VarDeclItem::ArrayOfIntInRange { ix, .. }
+        | VarDeclItem::ArrayOfIntInSet { ix, .. }
+        | VarDeclItem::ArrayOfFloat { ix, .. }
+        | VarDeclItem::ArrayOfBoundedFloat { ix, .. }
+        | VarDeclItem::ArrayOfFloatInSet { ix, .. }
+        | VarDeclItem::ArrayOfSet { ix, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntRange { ix, .. }
+        | VarDeclItem::ArrayOfSubSetOfIntSet { ix, .. } => Some(ix),
+        _ => None,
+    }
+}
+
+/// The length of an array literal.
+fn array_len(e: &Expr) -> Option<usize> {
+    match e {
+        Expr::ArrayOfBool(v) => Some(v.len()),
+        Expr::ArrayOfInt(v) => Some(v.len()),
+        Expr::ArrayOfFloat(v) => Some(v.len()),
+        Expr::ArrayOfSet(v) => Some(v.len()),
+        _ => None,
+    }
+}
+
+/// Whether the domain of a variable, or of the elements of a non-empty array, is empty.
+fn empty_domain(item: &VarDeclItem) -> bool {
+    match item {
+        VarDeclItem::IntInRange { lb, ub, .. } => lb > ub,
+        VarDeclItem::IntInSet { set, .. } => set.is_empty(),
+        VarDeclItem::BoundedFloat { lb, ub, .. } => lb > ub,
+        VarDeclItem::FloatInSet { set, .. } => set.is_empty(),
+        VarDeclItem::ArrayOfIntInRange { lb, ub, ix, .. } => ix.0 > 0 && lb > ub,
+        VarDeclItem::ArrayOfIntInSet { set, ix, .. } => ix.0 > 0 && set.is_empty(),
+        VarDeclItem::ArrayOfBoundedFloat { lb, ub, ix, .. } => ix.0 > 0 && lb > ub,
+        VarDeclItem::ArrayOfFloatInSet { set, ix, .. } => ix.0 > 0 && set.is_empty(),
+        _ => false,
+    }
+}
+#[test]
+fn test_validate_jobshop() {
+    let stmts = crate::statements::parse_model(include_str!("../jobshop.fzn")).unwrap();
+    assert_eq!(validate(&stmts), vec![]);
+}
+#[test]
+fn test_validate() {
+    let stmts = crate::statements::parse_model(
+        "array [1..3] of int: c = [1,2];\n\
+        var 5..3: x;\n\
+        var {}: e;\n\
+        array [1..0] of var 1..0: a;\n\
+        array [1..2] of var int: b = [x,z];\n\
+        var int: y = x;\n\
+        constraint int_lin_le(c,[x,w],0) :: foo(u);\n\
+        var bool: y;\n\
+        predicate p(var int: x);\n\
+        predicate p(var int: x);\n\
+        solve minimize v;\n\
+        solve satisfy;",
+    )
+    .unwrap();
+    let diagnostic = |stmt, kind| Diagnostic {
+        stmt: Some(stmt),
+        kind,
+    };
+    assert_eq!(
+        validate(&stmts),
+        vec![
+            diagnostic(
+                0,
+                DiagnosticKind::ArrayLength {
+                    id: "c".to_string(),
+                    expected: 3,
+                    found: 2
+                }
+            ),
+            diagnostic(1, DiagnosticKind::EmptyDomain("x".to_string())),
+            diagnostic(2, DiagnosticKind::EmptyDomain("e".to_string())),
+            diagnostic(4, DiagnosticKind::Undeclared("z".to_string())),
+            diagnostic(6, DiagnosticKind::Undeclared("w".to_string())),
+            diagnostic(
+                7,
+                DiagnosticKind::ItemOrder {
+                    kind: StmtKind::Variable,
+                    after: StmtKind::Constraint
+                }
+            ),
+            diagnostic(7, DiagnosticKind::Duplicate("y".to_string())),
+            diagnostic(
+                8,
+                DiagnosticKind::ItemOrder {
+                    kind: StmtKind::Predicate,
+                    after: StmtKind::Constraint
+                }
+            ),
+            diagnostic(9, DiagnosticKind::Duplicate("p".to_string())),
+            diagnostic(
+                9,
+                DiagnosticKind::ItemOrder {
+                    kind: StmtKind::Predicate,
+                    after: StmtKind::Constraint
+                }
+            ),
+            diagnostic(10, DiagnosticKind::Undeclared("v".to_string())),
+            diagnostic(11, DiagnosticKind::MultipleSolveItems),
+        ]
+    );
+    let stmts = crate::statements::parse_model(
+        "array [1..-1] of int: c = [];\n\
+        array [1..-2] of var int: a;\n\
+        solve satisfy;",
+    )
+    .unwrap();
+    assert_eq!(
+        validate(&stmts),
+        vec![
+            diagnostic(0, DiagnosticKind::InvalidArrayLength("c".to_string())),
+            diagnostic(1, DiagnosticKind::InvalidArrayLength("a".to_string())),
+        ]
+    );
+    let stmts = crate::statements::parse_model("var int: x;").unwrap();
+    assert_eq!(
+        validate(&stmts),
+        vec![Diagnostic {
+            stmt: None,
+            kind: DiagnosticKind::MissingSolveItem
+        }]
+    );
+}
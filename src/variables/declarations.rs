@@ -1,152 +1,480 @@
 use winnow::{
     combinator::opt,
-    error::{FromExternalError, ParserError},
+    error::{AddContext, FromExternalError, ParserError, StrContext, StrContextValue},
+    stream::Stream,
     PResult, Parser,
 };
 
 use crate::{
     basic_types::BasicType,
     comments::space_or_comment0,
+    error::StmtKind,
     expressions::{
         annotations, array_of_bool_expr, array_of_float_expr, array_of_int_expr, array_of_set_expr,
-        bool_expr, float_expr, int_expr, set_expr, Annotations, ArrayOfBoolExpr, ArrayOfFloatExpr,
-        ArrayOfIntExpr, ArrayOfSetExpr, BoolExpr, FloatExpr, IntExpr, SetExpr,
+        bool_expr, float_expr, int_expr, map_annotations, set_expr, Annotations, ArrayOfBoolExpr,
+        ArrayOfFloatExpr, ArrayOfIntExpr, ArrayOfSetExpr, BoolExpr, FloatExpr, IntExpr, SetExpr,
+        StrRole,
     },
-    primitive_literals::{var_par_identifier, IndexSet},
+    primitive_literals::{var_par_identifier, IndexSet, ParseStr},
+    spans::{spanned, NodeKind},
     variables::types::{var_type, BasicVarType, VarType},
 };
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum VarDeclItem {
+pub enum VarDeclItem<S = String> {
     Bool {
-        id: String,
-        expr: Option<BoolExpr>,
-        annos: Annotations,
+        id: S,
+        expr: Option<BoolExpr<S>>,
+        annos: Annotations<S>,
     },
     Int {
-        id: String,
-        expr: Option<IntExpr>,
-        annos: Annotations,
+        id: S,
+        expr: Option<IntExpr<S>>,
+        annos: Annotations<S>,
     },
     IntInRange {
-        id: String,
+        id: S,
         lb: i128,
         ub: i128,
-        expr: Option<IntExpr>,
-        annos: Annotations,
+        expr: Option<IntExpr<S>>,
+        annos: Annotations<S>,
     },
     IntInSet {
-        id: String,
+        id: S,
         set: Vec<i128>,
-        expr: Option<IntExpr>,
-        annos: Annotations,
+        expr: Option<IntExpr<S>>,
+        annos: Annotations<S>,
     },
     Float {
-        id: String,
-        expr: Option<FloatExpr>,
-        annos: Annotations,
+        id: S,
+        expr: Option<FloatExpr<S>>,
+        annos: Annotations<S>,
     },
     BoundedFloat {
-        id: String,
+        id: S,
         lb: f64,
         ub: f64,
-        expr: Option<FloatExpr>,
-        annos: Annotations,
+        expr: Option<FloatExpr<S>>,
+        annos: Annotations<S>,
+    },
+    FloatInSet {
+        id: S,
+        set: Vec<f64>,
+        expr: Option<FloatExpr<S>>,
+        annos: Annotations<S>,
     },
     SetOfInt {
-        id: String,
-        expr: Option<SetExpr>,
-        annos: Annotations,
+        id: S,
+        expr: Option<SetExpr<S>>,
+        annos: Annotations<S>,
     },
     SubSetOfIntSet {
-        id: String,
+        id: S,
         set: Vec<i128>,
-        expr: Option<SetExpr>,
-        annos: Annotations,
+        expr: Option<SetExpr<S>>,
+        annos: Annotations<S>,
     },
     SubSetOfIntRange {
-        id: String,
+        id: S,
         lb: i128,
         ub: i128,
-        expr: Option<SetExpr>,
-        annos: Annotations,
+        expr: Option<SetExpr<S>>,
+        annos: Annotations<S>,
     },
     ArrayOfBool {
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfBoolExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfBoolExpr<S>>,
     },
     ArrayOfInt {
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfIntExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfIntExpr<S>>,
     },
     ArrayOfIntInRange {
         lb: i128,
         ub: i128,
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfIntExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfIntExpr<S>>,
     },
     ArrayOfIntInSet {
         set: Vec<i128>,
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfIntExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfIntExpr<S>>,
     },
     ArrayOfFloat {
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfFloatExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfFloatExpr<S>>,
     },
     ArrayOfBoundedFloat {
         lb: f64,
         ub: f64,
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfFloatExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfFloatExpr<S>>,
+    },
+    ArrayOfFloatInSet {
+        set: Vec<f64>,
+        ix: IndexSet,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfFloatExpr<S>>,
     },
     ArrayOfSet {
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfSetExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfSetExpr<S>>,
     },
     // array [int] of set of 1..3
     ArrayOfSubSetOfIntRange {
         ub: i128,
         lb: i128,
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfSetExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfSetExpr<S>>,
     },
     // array [int] of set of {1,2,3} //TODO: not in the specs
     ArrayOfSubSetOfIntSet {
         set: Vec<i128>,
         ix: IndexSet,
-        id: String,
-        annos: Annotations,
-        array_expr: Option<ArrayOfSetExpr>,
+        id: S,
+        annos: Annotations<S>,
+        array_expr: Option<ArrayOfSetExpr<S>>,
     },
 }
 
-pub fn var_decl_item<'a, E>(input: &mut &'a str) -> PResult<VarDeclItem, E>
+impl<S> VarDeclItem<S> {
+    pub fn map_str<T>(self, f: &mut impl FnMut(S, StrRole) -> T) -> VarDeclItem<T> {
+        match self {
+            VarDeclItem::Bool { id, expr, annos } => VarDeclItem::Bool {
+                id: f(id, StrRole::Identifier),
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::Int { id, expr, annos } => VarDeclItem::Int {
+                id: f(id, StrRole::Identifier),
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::IntInRange {
+                id,
+                lb,
+                ub,
+                expr,
+                annos,
+            } => VarDeclItem::IntInRange {
+                id: f(id, StrRole::Identifier),
+                lb,
+                ub,
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::IntInSet {
+                id,
+                set,
+                expr,
+                annos,
+            } => VarDeclItem::IntInSet {
+                id: f(id, StrRole::Identifier),
+                set,
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::Float { id, expr, annos } => VarDeclItem::Float {
+                id: f(id, StrRole::Identifier),
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::BoundedFloat {
+                id,
+                lb,
+                ub,
+                expr,
+                annos,
+            } => VarDeclItem::BoundedFloat {
+                id: f(id, StrRole::Identifier),
+                lb,
+                ub,
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::FloatInSet {
+                id,
+                set,
+                expr,
+                annos,
+            } => VarDeclItem::FloatInSet {
+                id: f(id, StrRole::Identifier),
+                set,
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::SetOfInt { id, expr, annos } => VarDeclItem::SetOfInt {
+                id: f(id, StrRole::Identifier),
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::SubSetOfIntSet {
+                id,
+                set,
+                expr,
+                annos,
+            } => VarDeclItem::SubSetOfIntSet {
+                id: f(id, StrRole::Identifier),
+                set,
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::SubSetOfIntRange {
+                id,
+                lb,
+                ub,
+                expr,
+                annos,
+            } => VarDeclItem::SubSetOfIntRange {
+                id: f(id, StrRole::Identifier),
+                lb,
+                ub,
+                expr: expr.map(|e| e.map_str(f)),
+                annos: map_annotations(annos, f),
+            },
+            VarDeclItem::ArrayOfBool {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfBool {
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfInt {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfInt {
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfIntInRange {
+                lb,
+                ub,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfIntInRange {
+                lb,
+                ub,
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfIntInSet {
+                set,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfIntInSet {
+                set,
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfFloat {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfFloat {
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfBoundedFloat {
+                lb,
+                ub,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfBoundedFloat {
+                lb,
+                ub,
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfFloatInSet {
+                set,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfFloatInSet {
+                set,
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfSet {
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfSet {
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfSubSetOfIntRange {
+                ub,
+                lb,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfSubSetOfIntRange {
+                ub,
+                lb,
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+            VarDeclItem::ArrayOfSubSetOfIntSet {
+                set,
+                ix,
+                id,
+                annos,
+                array_expr,
+            } => VarDeclItem::ArrayOfSubSetOfIntSet {
+                set,
+                ix,
+                id: f(id, StrRole::Identifier),
+                annos: map_annotations(annos, f),
+                array_expr: array_expr.map(|e| e.map_str(f)),
+            },
+        }
+    }
+}
+
+impl<S: Into<String>> VarDeclItem<S> {
+    /// Convert into a declaration that owns its strings.
+    pub fn into_owned(self) -> VarDeclItem {
+        self.map_str(&mut |s, _| s.into())
+    }
+}
+
+impl<S: AsRef<str>> VarDeclItem<S> {
+    pub fn id(&self) -> &str {
+        match self {
+            VarDeclItem::Bool { id, .. }
+            | VarDeclItem::Int { id, .. }
+            | VarDeclItem::IntInRange { id, .. }
+            | VarDeclItem::IntInSet { id, .. }
+            | VarDeclItem::Float { id, .. }
+            | VarDeclItem::BoundedFloat { id, .. }
+            | VarDeclItem::FloatInSet { id, .. }
+            | VarDeclItem::SetOfInt { id, .. }
+            | VarDeclItem::SubSetOfIntSet { id, .. }
+            | VarDeclItem::SubSetOfIntRange { id, .. }
+            | VarDeclItem::ArrayOfBool { id, .. }
+            | VarDeclItem::ArrayOfInt { id, .. }
+            | VarDeclItem::ArrayOfIntInRange { id, .. }
+            | VarDeclItem::ArrayOfIntInSet { id, .. }
+            | VarDeclItem::ArrayOfFloat { id, .. }
+            | VarDeclItem::ArrayOfBoundedFloat { id, .. }
+            | VarDeclItem::ArrayOfFloatInSet { id, .. }
+            | VarDeclItem::ArrayOfSet { id, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntRange { id, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntSet { id, .. } => id.as_ref(),
+        }
+    }
+
+    pub fn annos(&self) -> &Annotations<S> {
+        match self {
+            VarDeclItem::Bool { annos, .. }
+            | VarDeclItem::Int { annos, .. }
+            | VarDeclItem::IntInRange { annos, .. }
+            | VarDeclItem::IntInSet { annos, .. }
+            | VarDeclItem::Float { annos, .. }
+            | VarDeclItem::BoundedFloat { annos, .. }
+            | VarDeclItem::FloatInSet { annos, .. }
+            | VarDeclItem::SetOfInt { annos, .. }
+            | VarDeclItem::SubSetOfIntSet { annos, .. }
+            | VarDeclItem::SubSetOfIntRange { annos, .. }
+            | VarDeclItem::ArrayOfBool { annos, .. }
+            | VarDeclItem::ArrayOfInt { annos, .. }
+            | VarDeclItem::ArrayOfIntInRange { annos, .. }
+            | VarDeclItem::ArrayOfIntInSet { annos, .. }
+            | VarDeclItem::ArrayOfFloat { annos, .. }
+            | VarDeclItem::ArrayOfBoundedFloat { annos, .. }
+            | VarDeclItem::ArrayOfFloatInSet { annos, .. }
+            | VarDeclItem::ArrayOfSet { annos, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntRange { annos, .. }
+            | VarDeclItem::ArrayOfSubSetOfIntSet { annos, .. } => annos,
+        }
+    }
+}
+
+pub fn var_decl_item<'a, S: ParseStr<'a>, E>(input: &mut &'a str) -> PResult<VarDeclItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
     space_or_comment0(input)?;
-    let item = vdi_var(input)?;
+    let start = input.checkpoint();
+    let vt = spanned(NodeKind::Type, var_type)
+        .context(StrContext::Expected(StrContextValue::Description(
+            "variable type",
+        )))
+        .parse_next(input)?;
+    // only variable declarations start with a variable type
+    var_decl_tail(vt, input).map_err(|e| {
+        e.cut()
+            .add_context(input, &start, StrContext::Label(StmtKind::Variable.label()))
+    })
+}
+
+fn var_decl_tail<'a, S: ParseStr<'a>, E>(
+    vt: VarType,
+    input: &mut &'a str,
+) -> PResult<VarDeclItem<S>, E>
+where
+    E: ParserError<&'a str>
+        + FromExternalError<&'a str, std::num::ParseIntError>
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
+{
+    let item = vdi_var(vt, input)?;
     space_or_comment0(input)?;
-    ';'.parse_next(input)?;
+    ';'.context(StrContext::Expected(StrContextValue::CharLiteral(';')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
     Ok(item)
 }
@@ -156,7 +484,7 @@ fn test_var_decl_item_1() {
     use winnow::error::ContextError;
     let mut input = "array [1..1] of var set of 1..10: sets:: output_array([1..1]) = [X_0];";
     assert_eq!(
-        var_decl_item::<ContextError>(&mut input),
+        var_decl_item::<String, ContextError>(&mut input),
         Ok(VarDeclItem::ArrayOfSubSetOfIntRange {
             ix: IndexSet(1),
             id: "sets".to_string(),
@@ -179,7 +507,7 @@ fn test_var_decl_item_2() {
     use winnow::error::ContextError;
     let mut input = "array [1..5] of var 0..3: w =X_32;";
     assert_eq!(
-        var_decl_item::<ContextError>(&mut input),
+        var_decl_item::<String, ContextError>(&mut input),
         Ok(VarDeclItem::ArrayOfIntInRange {
             id: "w".to_string(),
             ix: IndexSet(5),
@@ -195,7 +523,7 @@ fn test_var_decl_item_3() {
     use winnow::error::ContextError;
     let mut input = "array [1..5] of var {1,2,3}: w;";
     assert_eq!(
-        var_decl_item::<ContextError>(&mut input),
+        var_decl_item::<String, ContextError>(&mut input),
         Ok(VarDeclItem::ArrayOfIntInSet {
             id: "w".to_string(),
             ix: IndexSet(5),
@@ -211,7 +539,7 @@ fn test_var_decl_item_4() {
     use winnow::error::ContextError;
     let mut input = "array [1..5] of var 0..3: w;";
     assert_eq!(
-        var_decl_item::<ContextError>(&mut input),
+        var_decl_item::<String, ContextError>(&mut input),
         Ok(VarDeclItem::ArrayOfIntInRange {
             id: "w".to_string(),
             ix: IndexSet(5),
@@ -223,7 +551,7 @@ fn test_var_decl_item_4() {
     );
     let mut input = "var 1..101: objective :: output_var = X_2586;";
     assert_eq!(
-        var_decl_item::<ContextError>(&mut input),
+        var_decl_item::<String, ContextError>(&mut input),
         Ok(VarDeclItem::IntInRange {
             id: "objective".to_string(),
             lb: 1,
@@ -242,7 +570,7 @@ fn test_var_decl_item_5() {
     use winnow::error::ContextError;
     let mut input = "array [1..3] of var set of 17..42: h = [{42,17},23..X,{}];";
     assert_eq!(
-        var_decl_item::<ContextError>(&mut input),
+        var_decl_item::<String, ContextError>(&mut input),
         Ok(VarDeclItem::ArrayOfSubSetOfIntRange {
             lb: 17,
             ub: 42,
@@ -269,7 +597,7 @@ fn test_var_decl_item_6() {
     use winnow::error::ContextError;
     let mut input = "array [1..2] of var bool: bools:: output_array([1..2]) = [x1, x2];";
     assert_eq!(
-        var_decl_item::<ContextError>(&mut input),
+        var_decl_item::<String, ContextError>(&mut input),
         Ok(VarDeclItem::ArrayOfBool {
             ix: IndexSet(2),
             id: "bools".to_string(),
@@ -286,18 +614,70 @@ fn test_var_decl_item_6() {
         })
     );
 }
+#[test]
+fn test_var_decl_item_7() {
+    use winnow::error::ContextError;
+    let mut input = "var {1.0, 2.5, 3.0}: x = 2.5;";
+    let item = var_decl_item::<String, ContextError>(&mut input).unwrap();
+    assert_eq!(
+        item,
+        VarDeclItem::FloatInSet {
+            id: "x".to_string(),
+            set: vec![1.0, 2.5, 3.0],
+            expr: Some(FloatExpr::Float(2.5)),
+            annos: vec![],
+        }
+    );
+    let printed = item.to_string();
+    assert_eq!(printed, "var {1.0,2.5,3.0}: x = 2.5;");
+    assert_eq!(
+        var_decl_item::<String, ContextError>(&mut printed.as_str()),
+        Ok(item)
+    );
+    let mut input = "array [1..2] of var {-1.5,2.0}: xs = [x, 2.0];";
+    let item = var_decl_item::<String, ContextError>(&mut input).unwrap();
+    assert_eq!(
+        item,
+        VarDeclItem::ArrayOfFloatInSet {
+            set: vec![-1.5, 2.0],
+            ix: IndexSet(2),
+            id: "xs".to_string(),
+            annos: vec![],
+            array_expr: Some(ArrayOfFloatExpr::Array(vec![
+                FloatExpr::VarParIdentifier("x".to_string()),
+                FloatExpr::Float(2.0)
+            ])),
+        }
+    );
+    let printed = item.to_string();
+    assert_eq!(printed, "array [1..2] of var {-1.5,2.0}: xs = [x,2.0];");
+    assert_eq!(
+        var_decl_item::<String, ContextError>(&mut printed.as_str()),
+        Ok(item)
+    );
+    let mut input = "var {1,3}: y;";
+    assert!(matches!(
+        var_decl_item::<String, ContextError>(&mut input),
+        Ok(VarDeclItem::IntInSet { .. })
+    ));
+}
 
-fn vdi_var<'a, E>(input: &mut &'a str) -> PResult<VarDeclItem, E>
+fn vdi_var<'a, S: ParseStr<'a>, E>(vt: VarType, input: &mut &'a str) -> PResult<VarDeclItem<S>, E>
 where
     E: ParserError<&'a str>
         + FromExternalError<&'a str, std::num::ParseIntError>
-        + FromExternalError<&'a str, std::num::ParseFloatError>,
+        + FromExternalError<&'a str, std::num::ParseFloatError>
+        + AddContext<&'a str, StrContext>,
 {
-    let vt = var_type(input)?;
     space_or_comment0(input)?;
-    ':'.parse_next(input)?;
+    ':'.context(StrContext::Expected(StrContextValue::CharLiteral(':')))
+        .parse_next(input)?;
     space_or_comment0(input)?;
-    let id = var_par_identifier(input)?;
+    let id = spanned(NodeKind::Identifier, var_par_identifier)
+        .context(StrContext::Expected(StrContextValue::Description(
+            "identifier",
+        )))
+        .parse_next(input)?;
     space_or_comment0(input)?;
     let annos = annotations(input)?;
     space_or_comment0(input)?;
@@ -347,6 +727,15 @@ where
                     annos,
                 })
             }
+            BasicVarType::FloatInSet(set) => {
+                let expr = parse_rhs(assign, float_expr, input)?;
+                Ok(VarDeclItem::FloatInSet {
+                    id,
+                    set,
+                    expr,
+                    annos,
+                })
+            }
             BasicVarType::SubSetOfIntRange(lb, ub) => {
                 let expr = parse_rhs(assign, set_expr, input)?;
                 Ok(VarDeclItem::SubSetOfIntRange {
@@ -370,7 +759,11 @@ where
         VarType::Array { ix, var_type } => match var_type {
             BasicVarType::BasicType(bt) => match bt {
                 BasicType::Bool => {
-                    let array_expr = parse_rhs(assign, array_of_bool_expr, input)?;
+                    let array_expr = parse_rhs(
+                        assign,
+                        spanned(NodeKind::ArrayExpr, array_of_bool_expr),
+                        input,
+                    )?;
                     Ok(VarDeclItem::ArrayOfBool {
                         ix,
                         id,
@@ -379,7 +772,11 @@ where
                     })
                 }
                 BasicType::Int => {
-                    let array_expr = parse_rhs(assign, array_of_int_expr, input)?;
+                    let array_expr = parse_rhs(
+                        assign,
+                        spanned(NodeKind::ArrayExpr, array_of_int_expr),
+                        input,
+                    )?;
                     Ok(VarDeclItem::ArrayOfInt {
                         ix,
                         id,
@@ -388,7 +785,11 @@ where
                     })
                 }
                 BasicType::Float => {
-                    let array_expr = parse_rhs(assign, array_of_float_expr, input)?;
+                    let array_expr = parse_rhs(
+                        assign,
+                        spanned(NodeKind::ArrayExpr, array_of_float_expr),
+                        input,
+                    )?;
                     Ok(VarDeclItem::ArrayOfFloat {
                         ix,
                         id,
@@ -398,7 +799,11 @@ where
                 }
             },
             BasicVarType::IntInRange(lb, ub) => {
-                let array_expr = parse_rhs(assign, array_of_int_expr, input)?;
+                let array_expr = parse_rhs(
+                    assign,
+                    spanned(NodeKind::ArrayExpr, array_of_int_expr),
+                    input,
+                )?;
                 Ok(VarDeclItem::ArrayOfIntInRange {
                     lb,
                     ub,
@@ -409,7 +814,11 @@ where
                 })
             }
             BasicVarType::IntInSet(set) => {
-                let array_expr = parse_rhs(assign, array_of_int_expr, input)?;
+                let array_expr = parse_rhs(
+                    assign,
+                    spanned(NodeKind::ArrayExpr, array_of_int_expr),
+                    input,
+                )?;
                 Ok(VarDeclItem::ArrayOfIntInSet {
                     set,
                     ix,
@@ -419,7 +828,11 @@ where
                 })
             }
             BasicVarType::BoundedFloat(lb, ub) => {
-                let array_expr = parse_rhs(assign, array_of_float_expr, input)?;
+                let array_expr = parse_rhs(
+                    assign,
+                    spanned(NodeKind::ArrayExpr, array_of_float_expr),
+                    input,
+                )?;
                 Ok(VarDeclItem::ArrayOfBoundedFloat {
                     lb,
                     ub,
@@ -429,8 +842,26 @@ where
                     array_expr,
                 })
             }
+            BasicVarType::FloatInSet(set) => {
+                let array_expr = parse_rhs(
+                    assign,
+                    spanned(NodeKind::ArrayExpr, array_of_float_expr),
+                    input,
+                )?;
+                Ok(VarDeclItem::ArrayOfFloatInSet {
+                    set,
+                    ix,
+                    id,
+                    annos,
+                    array_expr,
+                })
+            }
             BasicVarType::SubSetOfIntRange(lb, ub) => {
-                let array_expr = parse_rhs(assign, array_of_set_expr, input)?;
+                let array_expr = parse_rhs(
+                    assign,
+                    spanned(NodeKind::ArrayExpr, array_of_set_expr),
+                    input,
+                )?;
                 Ok(VarDeclItem::ArrayOfSubSetOfIntRange {
                     lb,
                     ub,
@@ -441,7 +872,11 @@ where
                 })
             }
             BasicVarType::SubSetOfIntSet(set) => {
-                let array_expr = parse_rhs(assign, array_of_set_expr, input)?;
+                let array_expr = parse_rhs(
+                    assign,
+                    spanned(NodeKind::ArrayExpr, array_of_set_expr),
+                    input,
+                )?;
                 Ok(VarDeclItem::ArrayOfSubSetOfIntSet {
                     set,
                     ix,
@@ -457,11 +892,11 @@ where
 /// Parse the right hand side of a variable declaration if there is an assignment
 fn parse_rhs<'a, O, E>(
     assign: bool,
-    parser: impl Fn(&mut &'a str) -> PResult<O, E>,
+    mut parser: impl Parser<&'a str, O, E>,
     input: &mut &'a str,
 ) -> PResult<Option<O>, E> {
     Ok(if assign {
-        let expr = parser(input)?;
+        let expr = parser.parse_next(input)?;
         Some(expr)
     } else {
         None
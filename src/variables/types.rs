@@ -65,6 +65,7 @@ pub enum BasicVarType {
     IntInRange(i128, i128),
     IntInSet(Vec<i128>),
     BoundedFloat(f64, f64),
+    FloatInSet(Vec<f64>),
     SubSetOfIntSet(Vec<i128>),
     SubSetOfIntRange(i128, i128),
 }
@@ -83,6 +84,7 @@ where
         bvt_int_in_range,
         bvt_int_in_set,
         bvt_bounded_float,
+        bvt_float_in_set,
         bvt_subset_of_int_set,
         bvt_subset_of_int_range,
     ))
@@ -119,6 +121,14 @@ where
     Ok(BasicVarType::BoundedFloat(lb, ub))
 }
 
+fn bvt_float_in_set<'a, E>(input: &mut &'a str) -> PResult<BasicVarType, E>
+where
+    E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseFloatError>,
+{
+    let set = float_in_set(input)?;
+    Ok(BasicVarType::FloatInSet(set))
+}
+
 fn bvt_subset_of_int_range<'a, E>(input: &mut &'a str) -> PResult<BasicVarType, E>
 where
     E: ParserError<&'a str> + FromExternalError<&'a str, std::num::ParseIntError>,